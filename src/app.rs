@@ -1,14 +1,35 @@
 #![allow(dead_code)]
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, NaiveDate};
 use rusqlite::Connection;
 
 use crate::{
-    config::load_config,
+    config::{header_panels, list_columns, load_config, Config, FilterPresetConfig, GoalConfig, HeaderPanel, ListColumn},
     db,
     form::TransactionForm,
-    models::{RecurringEntry, Tag, Transaction},
-    theme::Theme,
+    models::{RecurringEntry, RecurringInterval, Tag, Transaction, TransactionType},
+    locale::Locale,
+    money::{Money, Rounding},
+    search::Query,
+    state::{self, UiState},
+    theme::{Glyphs, Theme},
 };
 
+/// Rows the transaction list shows by default. A jump-to-date selection
+/// beyond this grows the window so the selected row stays in view.
+pub const LIST_LIMIT: usize = 15;
+
+/// How long a toast stays on screen.
+pub const TOAST_DURATION: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// A one-line notice for routine actions, drawn over the bottom of the
+/// screen until `TOAST_DURATION` passes. Confirms and errors use popups.
+pub struct Toast {
+    pub message: String,
+    pub shown_at: std::time::Instant,
+}
+
 #[derive(PartialEq, Debug, Clone, Copy)]
 pub enum Mode {
     Normal,
@@ -17,6 +38,15 @@ pub enum Mode {
     Popup,
     RecurringManagement,
     Filtering,
+    JumpToDate,
+    Search,
+    Retag,
+    // Typing the percentage to scale amounts by
+    ScaleAmounts,
+    // Typing only the amount of another of the last transaction
+    QuickAdd,
+    TagTrend,
+    YearSummary,
 }
 
 #[derive(PartialEq, Clone, Copy, Debug)]
@@ -44,6 +74,90 @@ impl FilterField {
     }
 }
 
+/// Column the transaction list is ordered by.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum SortKey {
+    Date,
+    Amount,
+    Source,
+    Tag,
+}
+
+impl SortKey {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Date => Self::Amount,
+            Self::Amount => Self::Source,
+            Self::Source => Self::Tag,
+            Self::Tag => Self::Date,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Date => "date",
+            Self::Amount => "amount",
+            Self::Source => "source",
+            Self::Tag => "tag",
+        }
+    }
+}
+
+impl std::str::FromStr for SortKey {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "date" => Ok(Self::Date),
+            "amount" => Ok(Self::Amount),
+            "source" => Ok(Self::Source),
+            "tag" => Ok(Self::Tag),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Order of the recurring entries list.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum RecurringSort {
+    // Most recently created first, as stored
+    Newest,
+    Source,
+    // Largest first
+    Amount,
+}
+
+impl RecurringSort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Newest => Self::Source,
+            Self::Source => Self::Amount,
+            Self::Amount => Self::Newest,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Newest => "newest",
+            Self::Source => "source",
+            Self::Amount => "amount",
+        }
+    }
+}
+
+impl std::str::FromStr for RecurringSort {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "newest" => Ok(Self::Newest),
+            "source" => Ok(Self::Source),
+            "amount" => Ok(Self::Amount),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TransactionFilter {
     pub active: bool,
@@ -51,40 +165,440 @@ pub struct TransactionFilter {
     pub end_date: String,
     pub tag_index: Option<usize>, // None represents "All"
     pub active_field: FilterField,
+    // Case-insensitive match against the source, typed in search mode
+    pub search: String,
+}
+
+impl TransactionFilter {
+    /// Drop every filter so the full list and totals come back.
+    pub fn clear(&mut self) {
+        self.active = false;
+        self.start_date.clear();
+        self.end_date.clear();
+        self.tag_index = None;
+        self.active_field = FilterField::StartDate;
+        self.search.clear();
+    }
+
+    /// Whether any criterion is set, i.e. whether the filter should be on.
+    pub fn has_criteria(&self) -> bool {
+        !self.start_date.is_empty()
+            || !self.end_date.is_empty()
+            || self.tag_index.is_some()
+            || !self.search.trim().is_empty()
+    }
+}
+
+// Longest search history kept, oldest queries drop off first
+const SEARCH_HISTORY_LEN: usize = 50;
+
+/// Search mode bookkeeping: committed queries plus where Up/Down recall is.
+#[derive(Clone, Debug, Default)]
+pub struct SearchState {
+    // Oldest first
+    pub history: Vec<String>,
+    // Index into `history` while recalling, None while typing freely
+    pub recall: Option<usize>,
+    // Query in place when search mode opened, restored by Esc
+    pub previous: String,
+    // What was typed before recall started, restored past the newest entry
+    pub draft: String,
+}
+
+impl SearchState {
+    /// Remember a committed query, skipping blanks and consecutive repeats.
+    pub fn push(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() || self.history.last().is_some_and(|last| last == query) {
+            return;
+        }
+        self.history.push(query.to_string());
+        if self.history.len() > SEARCH_HISTORY_LEN {
+            let excess = self.history.len() - SEARCH_HISTORY_LEN;
+            self.history.drain(..excess);
+        }
+    }
+}
+
+// Changes kept for undo; the oldest drop off first
+const UNDO_LIMIT: usize = 50;
+
+/// A reversible change to the transactions table, with the rows as they
+/// were on each side of it so it can be replayed in either direction.
+#[derive(Clone, Debug)]
+pub enum Change {
+    Add(Transaction),
+    Edit { before: Transaction, after: Transaction },
+    Delete(Vec<Transaction>),
+    // (before, after) for every retagged row
+    Retag(Vec<(Transaction, Transaction)>),
+    // (before, after) for every row whose amount was scaled
+    Scale(Vec<(Transaction, Transaction)>),
+}
+
+impl Change {
+    /// Short description for the undo/redo toast, e.g. "deleting 'Rent'".
+    pub fn describe(&self) -> String {
+        match self {
+            Change::Add(tx) => format!("adding '{}'", tx.source),
+            Change::Edit { before, .. } => format!("editing '{}'", before.source),
+            Change::Delete(rows) if rows.len() == 1 => format!("deleting '{}'", rows[0].source),
+            Change::Delete(rows) => format!("deleting {} transactions", rows.len()),
+            Change::Retag(rows) if rows.len() == 1 => format!("retagging '{}'", rows[0].0.source),
+            Change::Retag(rows) => format!("retagging {} transactions", rows.len()),
+            Change::Scale(rows) if rows.len() == 1 => format!("scaling '{}'", rows[0].0.source),
+            Change::Scale(rows) => format!("scaling {} transactions", rows.len()),
+        }
+    }
+
+    /// Write the change to the database, or its inverse when `undo` is set.
+    fn apply(&self, conn: &Connection, undo: bool) -> rusqlite::Result<()> {
+        match self {
+            Change::Add(tx) if undo => db::delete_transaction(conn, tx.id),
+            Change::Add(tx) => db::restore_transactions(conn, std::slice::from_ref(tx)),
+            Change::Edit { before, after } => {
+                let pair = if undo { (after, before) } else { (before, after) };
+                db::update_changed_columns(conn, &[pair])
+            }
+            Change::Delete(rows) if undo => db::restore_transactions(conn, rows),
+            Change::Delete(rows) => {
+                let ids: Vec<i32> = rows.iter().map(|tx| tx.id).collect();
+                db::delete_transactions(conn, &ids).map(|_| ())
+            }
+            Change::Retag(rows) | Change::Scale(rows) => {
+                let pairs: Vec<(&Transaction, &Transaction)> = rows
+                    .iter()
+                    .map(|(before, after)| if undo { (after, before) } else { (before, after) })
+                    .collect();
+                db::update_changed_columns(conn, &pairs)
+            }
+        }
+    }
+}
+
+/// Undo and redo stacks, newest last.
+#[derive(Clone, Debug, Default)]
+pub struct UndoHistory {
+    pub undo: Vec<Change>,
+    pub redo: Vec<Change>,
+}
+
+impl UndoHistory {
+    /// Remember a fresh change. Anything undone before it can't be redone.
+    pub fn record(&mut self, change: Change) {
+        self.redo.clear();
+        self.undo.push(change);
+        if self.undo.len() > UNDO_LIMIT {
+            let excess = self.undo.len() - UNDO_LIMIT;
+            self.undo.drain(..excess);
+        }
+    }
 }
 
 #[derive(Clone)]
 pub enum PopupAction {
     DeleteTransaction(i32),
+    // Delete a batch of marked rows
+    DeleteTransactions(Vec<i32>),
+    DeleteRecurringTransactions(i32),
+    InsertRecurring,
+    // Insert the form's transaction even though an identical one exists
+    SaveDuplicate,
+    // Write the form over the transaction being edited
+    SaveEdit,
+    // Run the month-end rollover for the current month
+    MonthRollover,
+    // Rewrite sources that differ only by whitespace or case
+    MergeSources,
+    // Multiply these rows' amounts by the factor
+    ScaleAmounts(Vec<i32>, f64),
+    // Stop a recurring rule after one of its transactions was deleted
+    PauseRecurring(i32),
+    // Clear the broken insertion history of these recurring rules
+    ResetRecurringState(Vec<i32>),
     Quit,
 }
 
+impl PopupAction {
+    /// Destructive actions default to "No" so a stray Enter can't lose data
+    /// (or, for duplicates, record the same entry twice).
+    pub fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            PopupAction::DeleteTransaction(_)
+                | PopupAction::DeleteTransactions(_)
+                | PopupAction::DeleteRecurringTransactions(_)
+                | PopupAction::SaveDuplicate
+                | PopupAction::MergeSources
+                | PopupAction::ScaleAmounts(..)
+        )
+    }
+}
+
+/// The transaction list as it's shown: filtered, sorted, and with the
+/// per-row figures the list draws. `App::sync_list_view` rebuilds it only
+/// when the transactions, filter or sort changed, so drawing a frame or
+/// moving the selection costs the rows on screen rather than the list.
+#[derive(Default)]
+pub struct ListView {
+    key: Option<ListViewKey>,
+    pub transactions: Vec<Transaction>,
+    // Balance after each transaction, in the base currency. It accumulates
+    // in date order whatever the list is sorted by, so it's keyed by id.
+    pub balances: HashMap<i32, f64>,
+    // Net per day in base-currency units, over the whole filtered list
+    pub day_totals: HashMap<String, i64>,
+    pub budget_bands: HashMap<(String, Tag), BudgetBand>,
+    // Table row of each transaction when date dividers are shown, so the
+    // list can find what's on screen without walking every row
+    pub grouped_rows: Vec<usize>,
+    // Widest amount in the filtered list, so the column doesn't shift
+    // while scrolling
+    pub amount_width: usize,
+}
+
+// What a ListView was built from
+#[derive(Clone, PartialEq)]
+struct ListViewKey {
+    version: u64,
+    // Dates, tag and search, while a filter is applied
+    filter: Option<(String, String, Option<usize>, String)>,
+    sort_key: SortKey,
+    sort_desc: bool,
+}
+
+/// Where a tag's spending stands in a month against its `tag_budgets` entry.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BudgetBand {
+    Under,
+    // At or past `budget_warning_percent` of the budget
+    Near,
+    Over,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ConfirmChoice {
+    Yes,
+    No,
+}
+
+impl ConfirmChoice {
+    pub fn toggle(self) -> Self {
+        match self {
+            ConfirmChoice::Yes => ConfirmChoice::No,
+            ConfirmChoice::No => ConfirmChoice::Yes,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub enum PopupKind {
     Confirm {
         title: String,
         message: String,
         action: PopupAction,
+        size: PopupSize,
     },
     Info {
         title: String,
         message: String,
+        size: PopupSize,
+    },
+    // Runs `action` only once `word` has been typed out, for large deletes
+    TypedConfirm {
+        title: String,
+        message: String,
+        word: String,
+        typed: String,
+        action: PopupAction,
+        size: PopupSize,
     },
 }
 
+impl PopupKind {
+    pub fn message(&self) -> &str {
+        match self {
+            PopupKind::Confirm { message, .. }
+            | PopupKind::Info { message, .. }
+            | PopupKind::TypedConfirm { message, .. } => message,
+        }
+    }
+
+    pub fn size(&self) -> PopupSize {
+        match self {
+            PopupKind::Confirm { size, .. }
+            | PopupKind::Info { size, .. }
+            | PopupKind::TypedConfirm { size, .. } => *size,
+        }
+    }
+
+    /// How the popup is colored: confirms for destructive actions stand
+    /// out from ordinary questions and notices.
+    pub fn tone(&self) -> PopupTone {
+        match self {
+            PopupKind::Confirm { action, .. } | PopupKind::TypedConfirm { action, .. }
+                if action.is_destructive() =>
+            {
+                PopupTone::Danger
+            }
+            PopupKind::Confirm { .. } | PopupKind::TypedConfirm { .. } => PopupTone::Neutral,
+            PopupKind::Info { .. } => PopupTone::Info,
+        }
+    }
+}
+
+/// Popup coloring by severity; the colors come from the theme's `danger`,
+/// `accent` and `info`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PopupTone {
+    Danger,
+    Neutral,
+    Info,
+}
+
+/// How much of the screen a popup may take. Either way its height follows
+/// the wrapped message.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PopupSize {
+    // Notices and yes/no questions
+    #[default]
+    Normal,
+    // Lists and reports, which read better wide and may fill the screen
+    Large,
+}
+
+impl PopupSize {
+    pub fn width_percent(self) -> u16 {
+        match self {
+            PopupSize::Normal => 60,
+            PopupSize::Large => 80,
+        }
+    }
+
+    pub fn max_height_percent(self) -> u16 {
+        match self {
+            PopupSize::Normal => 80,
+            PopupSize::Large => 90,
+        }
+    }
+}
+
 pub struct App {
     pub mode: Mode,
     pub form: TransactionForm,
     pub editing: Option<i32>,
+    // Recurring rule being edited in the form, instead of a transaction
+    pub editing_recurring: Option<i32>,
     pub tags: Vec<Tag>,
     pub transactions: Vec<Transaction>,
+    // #hashtags from each transaction's note, keyed by transaction id
+    pub note_tags: HashMap<i32, Vec<String>>,
     pub recurring_entries: Vec<RecurringEntry>,
     pub selected: usize,
     pub selected_recurring: usize,
     pub currency: String,
     pub popup: Option<PopupKind>,
+    pub popup_scroll: u16,
+    pub popup_choice: ConfirmChoice,
     pub theme: Theme,
     pub filter: TransactionFilter,
+    // Set whenever transactions are reloaded so derived data (stats) is
+    // only recomputed after a mutation.
+    pub dirty: bool,
+    // Bumped whenever transactions are reloaded; tells `list_view` it's stale
+    pub data_version: u64,
+    pub list_view: ListView,
+    // Upper bound on how long the main loop sleeps without input (0 = none)
+    pub poll_interval_ms: u64,
+    pub auto_confirm_recurring: bool,
+    // Ask before saving changes to an existing transaction
+    pub confirm_edit: bool,
+    pub jump_date: String,
+    pub search: SearchState,
+    pub exchange_rates: HashMap<String, f64>,
+    // Ids of transactions marked for multi-row actions
+    pub selected_ids: HashSet<i32>,
+    // Adds, edits, deletes and retags this session, for u / Ctrl+r
+    pub history: UndoHistory,
+    pub highlight_threshold: Option<f64>,
+    // Monthly budget per tag, and the percentage of it that counts as near
+    pub tag_budgets: HashMap<Tag, f64>,
+    pub budget_warning_percent: f64,
+    pub low_balance_warning: Option<f64>,
+    pub stats_months: usize,
+    pub week_start: chrono::Weekday,
+    // Rows tied on the sort column go newest first (by date, then id)
+    pub ties_newest_first: bool,
+    // (low, healthy) savings rate percentages
+    pub savings_thresholds: (f64, f64),
+    pub goals: Vec<GoalConfig>,
+    // Filters applied with the number keys, from the config
+    pub filter_presets: Vec<FilterPresetConfig>,
+    // Profile from --profile, shown in the header
+    pub profile: Option<String>,
+    // Past sources for form completion, loaded when the form opens
+    pub source_history: Vec<String>,
+    // Scroll offset of the Stats details, capped by the line count that's
+    // recorded whenever the snapshot is rebuilt
+    pub stats_scroll: u16,
+    pub stats_lines: usize,
+    // Monthly (month, balance) snapshots for the net worth chart
+    pub balance_snapshots: Vec<(String, f64)>,
+    pub opening_balance: f64,
+    // Tag chosen in the batch retag popup
+    pub retag_index: usize,
+    // Percentage typed in the scale amounts popup
+    pub scale_input: String,
+    pub sort_key: SortKey,
+    pub recurring_sort: RecurringSort,
+    // Date headers with a per-day subtotal between groups of rows
+    pub group_by_date: bool,
+    pub sort_desc: bool,
+    // Tag shown in the Stats trend popup and its zero-filled monthly spend
+    pub trend_tag_index: usize,
+    pub tag_trend: Vec<(String, f64)>,
+    // All-time (spent, earned) for the trend tag
+    pub tag_total: (f64, f64),
+    // Year shown in the annual summary and its net per month (None = no data)
+    pub summary_year: i32,
+    pub year_net: [Option<f64>; 12],
+    // Privacy mode: amounts are masked on screen, data is untouched
+    pub privacy: bool,
+    pub decimal_places: usize,
+    pub rounding: Rounding,
+    // Language of month names in charts and date labels
+    pub locale: Locale,
+    pub fallback_tag: Tag,
+    // Welcome panel for a brand-new, empty ledger
+    pub show_onboarding: bool,
+    // Transaction list columns and their percentage widths
+    pub columns: Vec<(ListColumn, u16)>,
+    // Summary panels above the list, left to right
+    pub header_panels: Vec<HeaderPanel>,
+    pub alternate_screen: bool,
+    // Set by --read-only: navigation and stats only, nothing is written
+    pub read_only: bool,
+    pub relative_dates: bool,
+    pub stripe_rows: bool,
+    pub highlight_symbol: String,
+    // Start of the transaction list's title; filters and sort are appended
+    pub list_title: String,
+    // Replaces the "No transactions yet" hint when set
+    pub empty_message: Option<String>,
+    pub amount_step: f64,
+    pub amount_step_large: f64,
+    // Batch deletes of at least this many rows need the word typed; 0 is off
+    pub typed_confirm_threshold: usize,
+    pub typed_confirm_word: String,
+    // Opened on first copy and kept, since on X11 the copied text is only
+    // served while the clipboard handle is alive
+    pub clipboard: Option<arboard::Clipboard>,
+    pub toast: Option<Toast>,
+    // File the open info popup offers to open with `o`, e.g. a fresh export
+    pub popup_file: Option<std::path::PathBuf>,
+    // Where sort, grouping and search history are saved; None keeps them
+    // in memory only
+    pub state_file: Option<std::path::PathBuf>,
 }
 
 // helpers for tab management; the UI shows three tabs and the
@@ -95,8 +609,17 @@ impl App {
     /// 0 = transactions, 1 = stats, 2 = recurring management.
     pub fn current_tab(&self) -> usize {
         match self.mode {
-            Mode::Normal | Mode::Adding | Mode::Popup | Mode::Filtering => 0,
-            Mode::Stats => 1,
+            Mode::Adding if self.editing_recurring.is_some() => 2,
+            Mode::Normal
+            | Mode::Adding
+            | Mode::Popup
+            | Mode::Filtering
+            | Mode::JumpToDate
+            | Mode::Search
+            | Mode::Retag
+            | Mode::ScaleAmounts
+            | Mode::QuickAdd => 0,
+            Mode::Stats | Mode::TagTrend | Mode::YearSummary => 1,
             Mode::RecurringManagement => 2,
         }
     }
@@ -137,20 +660,46 @@ impl App {
 }
 
 impl App {
-    pub fn new(conn: &Connection) -> Self {
-        let config = load_config();
+    /// The app as configured in config.yaml, with the UI state saved in
+    /// state.yaml.
+    pub fn new(conn: &Connection, profile: Option<&str>) -> Self {
+        let state_file = state::state_path(profile);
+        let mut app = Self::with_config(conn, load_config(profile), state::load_state(&state_file));
+        app.state_file = Some(state_file);
+        app.profile = profile.map(String::from);
+        app
+    }
 
-        let tags: Vec<Tag> = config
-            .tags
-            .into_iter()
-            .map(|s| Tag::from_str(&s))
-            .collect();
+    /// The app over `conn` with the given config and UI state, reading and
+    /// writing no files.
+    pub fn with_config(conn: &Connection, config: Config, ui_state: UiState) -> Self {
+
+        let fallback_tag = Tag::parse_or(&config.fallback_tag, "other");
+        let tags = build_tag_list(&config.tags, &fallback_tag);
+        let week_start = config.week_start();
+        let rounding = config.rounding();
+        let locale = config.locale();
+
+        let mut form = TransactionForm::new();
+        form.default_tag_index = default_tag_index(&tags, config.default_tag.as_deref());
+        form.reset();
 
         let transactions = db::get_transactions(conn).unwrap_or_default();
-        let recurring_entries = db::get_recurring_entries(conn).unwrap_or_default();
+        let note_tags = db::note_tags(conn).unwrap_or_default();
+        let mut recurring_entries = db::get_recurring_entries(conn).unwrap_or_default();
+
+        // An unknown stored key resets the sort to newest first
+        let show_onboarding = transactions.is_empty() && !ui_state.onboarded;
+        let (sort_key, sort_desc) = match ui_state.sort_key.parse::<SortKey>() {
+            Ok(key) => (key, ui_state.sort_desc),
+            Err(()) => (SortKey::Date, true),
+        };
+        let recurring_sort =
+            ui_state.recurring_sort.parse().unwrap_or(RecurringSort::Newest);
+        sort_recurring_entries(&mut recurring_entries, recurring_sort);
 
         let theme_name = &config.theme;
-        let theme = if let Some(custom_config) = config.custom_themes.get(theme_name) {
+        let mut theme = if let Some(custom_config) = config.custom_themes.get(theme_name) {
             match Theme::from_config(custom_config) {
                 Ok(t) => t,
                 Err(err) => {
@@ -164,18 +713,31 @@ impl App {
             eprintln!("Theme '{}' not found. Falling back to default.", theme_name);
             Theme::default()
         };
+        if config.ascii_mode {
+            theme.glyphs = Glyphs::ASCII;
+        }
+
+        // The welcome panel lives in the list, so a new user starts there
+        let mode = match config.startup_mode.as_str() {
+            "stats" if !show_onboarding => Mode::Stats,
+            _ => Mode::Normal,
+        };
 
         Self {
-            mode: Mode::Normal,
-            form: TransactionForm::new(),
+            mode,
+            form,
             editing: None,
+            editing_recurring: None,
             tags,
             transactions,
+            note_tags,
             recurring_entries,
             selected: 0,
             selected_recurring: 0,
             currency: config.currency,
             popup: None,
+            popup_scroll: 0,
+            popup_choice: ConfirmChoice::Yes,
             theme,
             filter: TransactionFilter {
                 active: false,
@@ -183,170 +745,1764 @@ impl App {
                 end_date: String::new(),
                 tag_index: None,
                 active_field: FilterField::StartDate,
+                search: String::new(),
             },
+            dirty: true,
+            data_version: 0,
+            list_view: ListView::default(),
+            poll_interval_ms: config.poll_interval_ms,
+            auto_confirm_recurring: config.auto_confirm_recurring,
+            confirm_edit: config.confirm_edit,
+            jump_date: String::new(),
+            search: SearchState {
+                history: ui_state.search_history,
+                ..Default::default()
+            },
+            exchange_rates: config.exchange_rates,
+            selected_ids: HashSet::new(),
+            history: UndoHistory::default(),
+            highlight_threshold: config.highlight_threshold,
+            tag_budgets: config
+                .tag_budgets
+                .iter()
+                .map(|(tag, amount)| (Tag::parse_or(tag, ""), *amount))
+                .collect(),
+            budget_warning_percent: config.budget_warning_percent,
+            low_balance_warning: config.low_balance_warning,
+            stats_months: config.stats_months,
+            week_start,
+            ties_newest_first: config.sort_tie_breaker != "oldest",
+            savings_thresholds: (config.savings_rate_low, config.savings_rate_healthy),
+            goals: config.goals,
+            filter_presets: config.filter_presets,
+            profile: None,
+            source_history: Vec::new(),
+            stats_scroll: 0,
+            stats_lines: 0,
+            balance_snapshots: db::get_balance_snapshots(conn).unwrap_or_default(),
+            opening_balance: config.opening_balance,
+            retag_index: 0,
+            scale_input: String::new(),
+            sort_key,
+            sort_desc,
+            recurring_sort,
+            group_by_date: ui_state.group_by_date,
+            trend_tag_index: 0,
+            tag_trend: Vec::new(),
+            tag_total: (0.0, 0.0),
+            summary_year: chrono::Local::now().year(),
+            year_net: [None; 12],
+            privacy: false,
+            decimal_places: config.decimal_places,
+            rounding,
+            locale,
+            fallback_tag,
+            show_onboarding,
+            columns: list_columns(&config.columns),
+            header_panels: header_panels(&config.header_panels),
+            alternate_screen: config.alternate_screen,
+            read_only: false,
+            relative_dates: config.relative_dates,
+            stripe_rows: config.stripe_rows,
+            highlight_symbol: config.highlight_symbol,
+            list_title: config.list_title,
+            empty_message: config.empty_message,
+            amount_step: config.amount_step,
+            amount_step_large: config.amount_step_large,
+            typed_confirm_threshold: config.typed_confirm_threshold,
+            typed_confirm_word: config.typed_confirm_word,
+            clipboard: None,
+            toast: None,
+            popup_file: None,
+            state_file: None,
         }
     }
 
     pub fn refresh(&mut self, conn: &Connection) {
         self.transactions = db::get_transactions(conn).unwrap_or_default();
+        self.note_tags = db::note_tags(conn).unwrap_or_default();
         self.recurring_entries = db::get_recurring_entries(conn).unwrap_or_default();
+        sort_recurring_entries(&mut self.recurring_entries, self.recurring_sort);
+        self.dirty = true;
+        self.data_version += 1;
+
+        // Forget marks on rows that no longer exist
+        let transactions = &self.transactions;
+        self.selected_ids
+            .retain(|id| transactions.iter().any(|tx| tx.id == *id));
+
+        let len = self.transactions.len();
+        if self.selected >= len && len > 0 {
+            self.selected = len - 1;
+        }
 
-        let max_len = std::cmp::min(15, self.transactions.len());
-        if self.selected >= max_len && max_len > 0 {
-            self.selected = max_len - 1;
+        // The welcome panel goes away for good with the first transaction;
+        // main saves that to state.yaml on exit
+        if len > 0 {
+            self.show_onboarding = false;
         }
     }
 
-    pub fn save_transaction(&mut self, conn: &Connection) {
-        let amount: f64 = self.form.amount.trim().parse().unwrap_or(0.0);
+    /// Insert an example credit so a new user has something to look at.
+    pub fn add_sample_transaction(&mut self, conn: &Connection) {
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        db::add_transaction(
+            conn,
+            "Sample paycheck",
+            1000.0,
+            TransactionType::Credit,
+            &self.fallback_tag,
+            &today,
+        )
+        .unwrap();
+        self.refresh(conn);
+    }
 
-        let tag = self
-            .tags
-            .get(self.form.tag_index)
-            .unwrap_or(&Tag("other".into()))
-            .clone();
+    /// Save the form as a new or edited transaction. Returns `false` and
+    /// leaves the reason in `form.error` when the form doesn't validate.
+    /// A new transaction matching an existing source, amount and date asks
+    /// for confirmation first, as does any edit with `confirm_edit` set;
+    /// the form stays open behind that popup.
+    pub fn save_transaction(&mut self, conn: &Connection) -> bool {
+        // Enter again after a day-first date was flagged takes the correction
+        if let Some(fixed) = self.form.date_fix.take() {
+            self.form.date = fixed;
+        }
+        self.form.normalize_date();
+        // "Groceries " and "Groceries" should never become two sources
+        self.form.source = self.form.source.trim().to_string();
+        let valid = self.form.validate().and_then(|()| {
+            crate::form::validate_currency(&self.form.currency, &self.currency, &self.exchange_rates)
+        });
+        if let Err(msg) = valid {
+            self.form.date_fix = crate::form::day_first_suggestion(&self.form.date);
+            self.form.error = Some(match self.form.date_fix {
+                Some(_) => format!("{} Press Enter again to use it", msg),
+                None => msg,
+            });
+            return false;
+        }
+
+        if self.editing.is_none()
+            && self.editing_recurring.is_none()
+            && db::transaction_exists(conn, &self.form.source, self.form.amount_value(), self.form.currency.trim(), &self.form.date)
+                .unwrap_or(false)
+        {
+            self.open_confirm_popup(
+                "Possible Duplicate",
+                format!(
+                    "'{}' for {} on {} is already recorded.\n\nAdd it again?",
+                    self.form.source,
+                    self.money().format(self.form.amount_value()),
+                    self.form.date
+                ),
+                PopupAction::SaveDuplicate,
+            );
+            return false;
+        }
+
+        if self.confirm_edit && self.editing.is_some() {
+            self.open_confirm_popup(
+                "Confirm Edit",
+                format!("Save your changes to '{}'?", self.form.source),
+                PopupAction::SaveEdit,
+            );
+            return false;
+        }
+
+        self.commit_form(conn);
+        true
+    }
 
-        if let Some(id) = self.editing {
-            db::update_transaction(
-                conn,
+    /// Write the form to the database without any checks.
+    pub fn commit_form(&mut self, conn: &Connection) {
+        let amount = self.form.amount_value();
+
+        let tag = self.tag_at(self.form.tag_index);
+
+        if let Some(id) = self.editing_recurring.take() {
+            let entry = RecurringEntry {
                 id,
-                &self.form.source,
+                source: self.form.source.clone(),
                 amount,
-                self.form.kind,
-                &tag,
-                &self.form.date,
-            )
-            .unwrap();
+                kind: self.form.kind,
+                tag,
+                interval: self.form.recurring_interval.clone(),
+                original_date: self.form.date.clone(),
+                last_inserted_date: String::new(),
+                active: true,
+                prorate_first: self.form.prorate_first,
+            };
+            db::update_recurring_entry(conn, &entry).unwrap();
+            self.refresh(conn);
+            self.show_toast("Recurring entry updated");
+            return;
+        }
+
+        // When editing, start from the stored row so columns the form
+        // doesn't expose are kept as they were
+        let existing = self
+            .editing
+            .and_then(|id| self.transactions.iter().find(|t| t.id == id).cloned())
+            .unwrap_or_default();
+        let before = existing.clone();
+
+        let mut tx = Transaction {
+            id: self.editing.unwrap_or(0),
+            source: self.form.source.clone(),
+            amount,
+            kind: self.form.kind,
+            tag: tag.clone(),
+            date: self.form.date.clone(),
+            currency: self.form.currency.trim().to_string(),
+            receipt_path: self.form.receipt_path.trim().to_string(),
+            note: self.form.note.trim().to_string(),
+            ..existing
+        };
+
+        if self.editing.is_some() {
+            db::replace_transaction(conn, &tx).unwrap();
+            self.history.record(Change::Edit { before, after: tx.clone() });
 
             self.editing = None;
         } else {
-            db::add_transaction(
-                conn,
-                &self.form.source,
-                amount,
-                self.form.kind,
-                &tag,
-                &self.form.date,
-            )
-            .unwrap();
+            let date = NaiveDate::parse_from_str(&self.form.date, "%Y-%m-%d")
+                .unwrap_or_else(|_| chrono::Local::now().date_naive());
+            let interval = self.form.recurring_interval.clone();
+
+            // This row is the rule's first charge, so it's the one prorated
+            if self.form.recurring && self.form.prorate_first && interval == RecurringInterval::Monthly {
+                tx.amount = db::prorated_amount(amount, date);
+            }
+
+            let id = db::insert_transaction(conn, &tx).unwrap();
+            // Undo removes only the row; a recurring rule saved with it stays
+            self.history.record(Change::Add(Transaction { id: id as i32, ..tx.clone() }));
 
             if self.form.recurring {
-                db::add_recurring_entry(
-                    conn,
-                    &self.form.source,
-                    amount,
-                    self.form.kind,
-                    &tag,
-                    &self.form.recurring_interval,
-                    &self.form.date,
-                )
-                .unwrap();
+                // The row above covers this period, so the rule starts with the next
+                let entry = RecurringEntry {
+                    last_inserted_date: db::current_period(&interval, date),
+                    prorate_first: self.form.prorate_first,
+                    ..RecurringEntry::new(&self.form.source, amount, self.form.kind, tag, interval, &self.form.date)
+                };
+                db::add_recurring_entry(conn, &entry).unwrap();
             }
         }
 
         self.refresh(conn);
-    }
 
-    pub fn get_filtered_transactions(&self) -> Vec<Transaction> {
-        if !self.filter.active {
-            return self.transactions.clone();
+        // The row is saved either way; a missing receipt is only a warning
+        let receipt = tx.receipt_path;
+        if !receipt.is_empty() && !std::path::Path::new(&receipt).exists() {
+            self.open_info_popup(
+                "Receipt Not Found",
+                format!("Saved, but no file exists at\n{}", receipt),
+            );
+        } else {
+            self.show_toast(if tx.id != 0 { "Transaction updated" } else { "Transaction saved" });
         }
-        self.transactions
-            .iter()
-            .filter(|tx| {
-                if let Some(tag_idx) = self.filter.tag_index {
-                    if tx.tag.as_str() != self.tags[tag_idx].as_str() {
-                        return false;
-                    }
-                }
-                if !self.filter.start_date.is_empty() {
-                    if tx.date < self.filter.start_date {
-                        return false;
-                    }
-                }
-                if !self.filter.end_date.is_empty() {
-                    if tx.date > self.filter.end_date {
-                        return false;
-                    }
-                }
-                true
-            })
-            .cloned()
-            .collect()
     }
 
-    pub fn begin_edit_selected(&mut self) {
-        let tx = match self.selected_transaction() {
-            Some(t) => t,
-            None => return,
-        };
+    /// Store this month's balance if it hasn't been recorded yet, so the
+    /// net worth trend survives old transactions being removed.
+    pub fn record_balance_snapshot(&mut self, conn: &Connection) {
+        let month = chrono::Local::now().format("%Y-%m").to_string();
+        let _ = db::record_balance_snapshot(conn, &month, self.current_balance());
+        self.balance_snapshots = db::get_balance_snapshots(conn).unwrap_or_default();
+    }
 
-        self.form.source = tx.source.clone();
-        self.form.amount = format!("{:.2}", tx.amount);
-        self.form.kind = tx.kind;
+    /// Balance across every transaction in the base currency, including the
+    /// opening balance.
+    fn current_balance(&self) -> f64 {
+        self.opening_balance
+            + self
+                .transactions_in_base()
+                .iter()
+                .map(|tx| match tx.kind {
+                    TransactionType::Credit => tx.amount,
+                    TransactionType::Debit => -tx.amount,
+                })
+                .sum::<f64>()
+    }
 
-        self.form.tag_index = self
-            .tags
-            .iter()
-            .position(|t| t.as_str() == tx.tag.as_str())
-            .unwrap_or(0);
+    /// What the overall balance would be if the form were saved as it
+    /// stands. `None` while the amount isn't a number, or when the form is
+    /// editing a recurring rule, which doesn't touch the balance.
+    pub fn balance_preview(&self) -> Option<f64> {
+        if self.editing_recurring.is_some() {
+            return None;
+        }
+        let amount: f64 = self.form.amount.trim().parse().ok()?;
 
-        self.form.date = tx.date.clone();
-        self.form.active = crate::form::Field::Source;
+        let signed = |tx: &Transaction| match tx.kind {
+            TransactionType::Credit => tx.base_amount(&self.exchange_rates),
+            TransactionType::Debit => -tx.base_amount(&self.exchange_rates),
+        };
+        let new = Transaction {
+            amount: amount.abs(),
+            kind: self.form.kind,
+            currency: self.form.currency.trim().to_string(),
+            ..Default::default()
+        };
+        // An edit replaces the stored row's contribution
+        let old = self
+            .editing
+            .and_then(|id| self.transactions.iter().find(|t| t.id == id))
+            .map(signed)
+            .unwrap_or(0.0);
 
-        let recurring_entry = self.recurring_entries.iter().find(|r| {
-            r.source == tx.source
-                && r.amount == tx.amount
-                && r.kind == tx.kind
-                && r.tag == tx.tag
-        });
+        Some(self.current_balance() - old + signed(&new))
+    }
 
-        if let Some(entry) = recurring_entry {
-            self.form.recurring = true;
-            self.form.recurring_interval = entry.interval.clone();
-        } else {
-            self.form.recurring = false;
-            self.form.recurring_interval = crate::models::RecurringInterval::Monthly;
+    /// List sources that differ only by whitespace or case and ask before
+    /// merging them.
+    pub fn begin_source_merge(&mut self, conn: &Connection) {
+        let merges = db::source_variants(conn).unwrap_or_default();
+        if merges.is_empty() {
+            self.open_info_popup("Merge Sources", "No duplicate sources found.".into());
+            return;
         }
 
-        self.mode = Mode::Adding;
-        self.editing = Some(tx.id);
-    }
-
-    pub fn delete_selected(&mut self, conn: &Connection) {
-        if let Some(tx) = self.selected_transaction() {
-            db::delete_transaction(conn, tx.id).unwrap();
-            self.refresh(conn);
+        let mut message = String::from("These spellings will be rewritten:\n");
+        for (canonical, variants) in &merges {
+            for variant in variants {
+                message.push_str(&format!("\n'{}' → '{}'", variant, canonical));
+            }
         }
+        message.push_str("\n\nMerge them?");
+
+        self.open_confirm_popup("Merge Sources", message, PopupAction::MergeSources);
+        self.set_popup_size(PopupSize::Large);
     }
 
-    pub fn open_confirm_popup(
-        &mut self,
-        title: &str,
-        message: String,
-        action: PopupAction,
-    ) {
-        self.popup = Some(PopupKind::Confirm {
-            title: title.into(),
-            message,
-            action,
-        });
+    pub fn merge_sources(&mut self, conn: &Connection) {
+        let merges = db::source_variants(conn).unwrap_or_default();
+        db::merge_sources(conn, &merges).unwrap();
+        self.refresh(conn);
+    }
 
-        self.mode = Mode::Popup;
+    /// Reset all filters and say so.
+    pub fn clear_filters(&mut self) {
+        self.filter.clear();
+        self.selected = 0;
+        self.show_toast(format!("Filters cleared, showing all {} transactions", self.transactions.len()));
     }
 
-    pub fn open_info_popup(&mut self, title: &str, message: String) {
-        self.popup = Some(PopupKind::Info {
-            title: title.into(),
-            message,
-        });
+    /// The filter a preset sets up on `today`, or why it can't be applied.
+    fn preset_filter(&self, preset: &FilterPresetConfig, today: NaiveDate) -> Result<TransactionFilter, String> {
+        let tag_index = match preset.tag.as_deref() {
+            None => None,
+            Some(name) => Some(
+                self.tags
+                    .iter()
+                    .position(|t| t.as_str().eq_ignore_ascii_case(name.trim()))
+                    .ok_or_else(|| format!("Preset \"{}\": no tag named {}", preset.name, name))?,
+            ),
+        };
 
-        self.mode = Mode::Popup;
+        let day = |d: NaiveDate| d.format("%Y-%m-%d").to_string();
+        let (start_date, end_date) = match preset.range.as_deref() {
+            Some("today") => (day(today), day(today)),
+            Some("this_week") => {
+                let first = crate::stats::week_start_of(today, self.week_start);
+                (day(first), day(first + chrono::Duration::days(6)))
+            }
+            Some("this_month") => {
+                let first = today.with_day(1).unwrap();
+                let last = first + chrono::Months::new(1) - chrono::Duration::days(1);
+                (day(first), day(last))
+            }
+            Some("last_30_days") => (day(today - chrono::Duration::days(29)), day(today)),
+            Some("this_year") => (format!("{}-01-01", today.year()), format!("{}-12-31", today.year())),
+            _ => (
+                preset.start_date.clone().unwrap_or_default(),
+                preset.end_date.clone().unwrap_or_default(),
+            ),
+        };
+
+        let mut filter = TransactionFilter {
+            active: false,
+            start_date,
+            end_date,
+            tag_index,
+            active_field: FilterField::StartDate,
+            search: preset.search.clone().unwrap_or_default(),
+        };
+        filter.active = filter.has_criteria();
+        Ok(filter)
     }
 
-    pub fn close_popup(&mut self) {
-        self.popup = None;
-        self.mode = Mode::Normal;
+    /// Index of the preset the current filter was set up by, if any.
+    pub fn active_preset(&self, today: NaiveDate) -> Option<usize> {
+        if !self.filter.active {
+            return None;
+        }
+        self.filter_presets.iter().position(|preset| {
+            self.preset_filter(preset, today).is_ok_and(|f| {
+                f.start_date == self.filter.start_date
+                    && f.end_date == self.filter.end_date
+                    && f.tag_index == self.filter.tag_index
+                    && f.search.trim() == self.filter.search.trim()
+            })
+        })
+    }
+
+    /// Switch to filter preset `index`. Pressing the key of the preset
+    /// already in use clears the filter instead.
+    pub fn apply_filter_preset(&mut self, index: usize, today: NaiveDate) {
+        let Some(preset) = self.filter_presets.get(index) else {
+            self.show_toast(format!("No filter preset {}", index + 1));
+            return;
+        };
+        if self.active_preset(today) == Some(index) {
+            self.clear_filters();
+            return;
+        }
+
+        match self.preset_filter(preset, today) {
+            Ok(filter) => {
+                let name = preset.name.clone();
+                self.filter = filter;
+                self.selected = 0;
+                self.show_toast(format!("Filter: {}", name));
+            }
+            Err(message) => self.show_toast(message),
+        }
+    }
+
+    /// Ask before running the month-end rollover.
+    pub fn confirm_month_rollover(&mut self) {
+        let month = chrono::Local::now().format("%Y-%m").to_string();
+        self.open_confirm_popup(
+            "Month Rollover",
+            format!(
+                "Close {}?\n\nThis adds any due recurring entries, records the \
+                 month's closing balance and shows its totals.",
+                month
+            ),
+            PopupAction::MonthRollover,
+        );
+    }
+
+    /// Month-end routine: add due recurring entries, snapshot the closing
+    /// balance and summarize the month. Runs at most once per month.
+    pub fn month_rollover(&mut self, conn: &Connection) {
+        let month = chrono::Local::now().format("%Y-%m").to_string();
+        let (added, balance) = match self.run_month_rollover(conn, &month) {
+            Ok(Some(result)) => result,
+            Ok(None) => {
+                self.open_info_popup(
+                    "Month Rollover",
+                    format!("{} has already been rolled over.", month),
+                );
+                return;
+            }
+            Err(err) => {
+                // Nothing was written; drop what refresh read mid-way
+                self.refresh(conn);
+                self.open_info_popup("Rollover Failed", err.to_string());
+                return;
+            }
+        };
+        self.balance_snapshots = db::get_balance_snapshots(conn).unwrap_or_default();
+
+        let transactions = self.transactions_in_base();
+        let earned = crate::stats::total_earned_in_month(&transactions, &month);
+        let spent = crate::stats::total_spent_in_month(&transactions, &month);
+        let money = self.money();
+        self.open_info_popup(
+            "Month Rollover",
+            format!(
+                "{} closed.\n\nEarned:  {}\nSpent:   {}\nNet:     {}\nBalance: {}\n\n{} recurring entries added.",
+                month,
+                money.format(earned),
+                money.format(spent),
+                money.format(earned - spent),
+                money.format(balance),
+                added
+            ),
+        );
+    }
+
+    /// Insert the due recurring entries, snapshot the balance and mark
+    /// `month` done, all in one transaction with the mark written last.
+    /// Returns the number of rows added and the balance, or `None` if
+    /// `month` was already rolled over.
+    fn run_month_rollover(&mut self, conn: &Connection, month: &str) -> rusqlite::Result<Option<(usize, f64)>> {
+        if db::is_rolled_over(conn, month)? {
+            return Ok(None);
+        }
+
+        let db_tx = conn.unchecked_transaction()?;
+        let before = self.transactions.len();
+        db::insert_recurring_for_month(&db_tx, month)?;
+        self.refresh(&db_tx);
+        let added = self.transactions.len().saturating_sub(before);
+
+        let balance = self.current_balance();
+        db::set_balance_snapshot(&db_tx, month, balance)?;
+        db::record_rollover(&db_tx, month)?;
+        db_tx.commit()?;
+
+        Ok(Some((added, balance)))
+    }
+
+    /// Formatter for every amount shown on screen.
+    pub fn money(&self) -> Money {
+        Money {
+            hidden: self.privacy,
+            decimals: self.decimal_places,
+            rounding: self.rounding,
+            ..Money::new(&self.currency)
+        }
+    }
+
+    pub fn toggle_privacy(&mut self) {
+        self.privacy = !self.privacy;
+    }
+
+    /// The tag at `index`, or the configured fallback tag when out of range.
+    pub fn tag_at(&self, index: usize) -> Tag {
+        self.tags
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| self.fallback_tag.clone())
+    }
+
+    /// Whether any exchange rates are configured, i.e. totals may mix
+    /// converted amounts and should say which currency they're in.
+    pub fn is_multi_currency(&self) -> bool {
+        !self.exchange_rates.is_empty()
+    }
+
+    /// Transactions with amounts converted to the base currency, for totals
+    /// and stats that must not mix currencies.
+    pub fn transactions_in_base(&self) -> Vec<Transaction> {
+        self.transactions
+            .iter()
+            .map(|tx| Transaction {
+                amount: tx.base_amount(&self.exchange_rates),
+                currency: String::new(),
+                ..tx.clone()
+            })
+            .collect()
+    }
+
+    pub fn get_filtered_transactions(&self) -> Vec<Transaction> {
+        let mut transactions = if self.filter.active {
+            self.filter_transactions()
+        } else {
+            self.transactions.clone()
+        };
+
+        self.sort_transactions(&mut transactions);
+        transactions
+    }
+
+    /// Rebuild `list_view` if the transactions, filter or sort changed since
+    /// it was last built.
+    pub fn sync_list_view(&mut self) {
+        let key = ListViewKey {
+            version: self.data_version,
+            filter: self.filter.active.then(|| {
+                (
+                    self.filter.start_date.clone(),
+                    self.filter.end_date.clone(),
+                    self.filter.tag_index,
+                    self.filter.search.clone(),
+                )
+            }),
+            sort_key: self.sort_key,
+            sort_desc: self.sort_desc,
+        };
+        if self.list_view.key.as_ref() == Some(&key) {
+            return;
+        }
+
+        let transactions = self.get_filtered_transactions();
+
+        let mut by_date: Vec<&Transaction> = transactions.iter().collect();
+        by_date.sort_by(|a, b| a.date.cmp(&b.date).then(a.id.cmp(&b.id)));
+        let mut balance = self.opening_balance;
+        let mut balances = HashMap::with_capacity(by_date.len());
+        for tx in by_date {
+            let amount = tx.base_amount(&self.exchange_rates);
+            match tx.kind {
+                TransactionType::Credit => balance += amount,
+                TransactionType::Debit => balance -= amount,
+            }
+            balances.insert(tx.id, balance);
+        }
+
+        let mut grouped_rows = Vec::with_capacity(transactions.len());
+        for (i, tx) in transactions.iter().enumerate() {
+            let new_day = i == 0 || transactions[i - 1].date != tx.date;
+            let previous = grouped_rows.last().copied();
+            grouped_rows.push(previous.map_or(1, |row| row + 1 + usize::from(new_day)));
+        }
+
+        let mut day_totals: HashMap<String, i64> = HashMap::new();
+        for tx in &transactions {
+            let units = crate::money::to_units(tx.base_amount(&self.exchange_rates));
+            *day_totals.entry(tx.date.clone()).or_insert(0) += match tx.kind {
+                TransactionType::Credit => units,
+                TransactionType::Debit => -units,
+            };
+        }
+
+        let money = self.money();
+        let amount_width = transactions
+            .iter()
+            .map(|tx| crate::ui::amount_text(tx, &money, &self.theme.glyphs).chars().count())
+            .max()
+            .unwrap_or(0);
+
+        self.list_view = ListView {
+            key: Some(key),
+            transactions,
+            balances,
+            day_totals,
+            budget_bands: self.budget_bands(),
+            grouped_rows,
+            amount_width,
+        };
+    }
+
+    /// Order `transactions` by the current sort key. Rows that tie are
+    /// ordered by date, then id: newest first with `ties_newest_first`,
+    /// oldest first otherwise, whichever way the column itself is sorted.
+    /// Newest-first by date with newest-first ties is already how the
+    /// database returns them, so that case is left alone.
+    pub fn sort_transactions(&self, transactions: &mut [Transaction]) {
+        // The database already returns newest first, ties broken by id
+        if self.sort_key == SortKey::Date && self.sort_desc && self.ties_newest_first {
+            return;
+        }
+
+        transactions.sort_by(|a, b| {
+            let ord = match self.sort_key {
+                SortKey::Date => a.date.cmp(&b.date),
+                SortKey::Amount => a
+                    .base_amount(&self.exchange_rates)
+                    .total_cmp(&b.base_amount(&self.exchange_rates)),
+                SortKey::Source => a.source.to_lowercase().cmp(&b.source.to_lowercase()),
+                SortKey::Tag => a.tag.as_str().cmp(b.tag.as_str()),
+            };
+            let ord = if self.sort_desc { ord.reverse() } else { ord };
+
+            // Ties keep a fixed order whichever way the column is sorted
+            let tie = a.date.cmp(&b.date).then(a.id.cmp(&b.id));
+            ord.then(if self.ties_newest_first { tie.reverse() } else { tie })
+        });
+    }
+
+    /// Move to the next sort column, keeping the direction.
+    pub fn cycle_sort_key(&mut self) {
+        self.sort_key = self.sort_key.next();
+        self.selected = 0;
+    }
+
+    pub fn toggle_sort_direction(&mut self) {
+        self.sort_desc = !self.sort_desc;
+        self.selected = 0;
+    }
+
+    /// Whether the list is in its natural newest-first order, where date
+    /// group dividers make sense.
+    pub fn is_date_sorted(&self) -> bool {
+        self.sort_key == SortKey::Date
+    }
+
+    /// Whether the list is drawn with per-day headers: grouping is on and
+    /// the rows are in date order, so each day's rows are together.
+    pub fn shows_date_groups(&self) -> bool {
+        self.group_by_date && self.is_date_sorted()
+    }
+
+    pub fn toggle_date_groups(&mut self) {
+        self.group_by_date = !self.group_by_date;
+    }
+
+    /// Move the recurring list to its next order.
+    pub fn cycle_recurring_sort(&mut self) {
+        self.recurring_sort = self.recurring_sort.next();
+        sort_recurring_entries(&mut self.recurring_entries, self.recurring_sort);
+        self.selected_recurring = 0;
+    }
+
+    /// Open search mode, remembering the current query for Esc.
+    pub fn begin_search(&mut self) {
+        self.search.previous = self.filter.search.clone();
+        self.search.recall = None;
+        self.mode = Mode::Search;
+    }
+
+    /// Replace the live query; the list narrows as the user types.
+    pub fn set_search(&mut self, query: String) {
+        self.filter.search = query;
+        self.filter.active = self.filter.has_criteria();
+        self.selected = 0;
+    }
+
+    pub fn commit_search(&mut self) {
+        let query = self.filter.search.clone();
+        self.search.push(&query);
+        self.search.recall = None;
+        self.mode = Mode::Normal;
+    }
+
+    /// Put back the query from before search mode opened; nothing is recorded.
+    pub fn cancel_search(&mut self) {
+        self.search.recall = None;
+        let previous = std::mem::take(&mut self.search.previous);
+        self.set_search(previous);
+        self.mode = Mode::Normal;
+    }
+
+    /// Step back to an older query (Up).
+    pub fn recall_older_search(&mut self) {
+        let idx = match self.search.recall {
+            None => {
+                let Some(last) = self.search.history.len().checked_sub(1) else {
+                    return;
+                };
+                self.search.draft = self.filter.search.clone();
+                last
+            }
+            Some(idx) => idx.saturating_sub(1),
+        };
+        self.search.recall = Some(idx);
+        self.set_search(self.search.history[idx].clone());
+    }
+
+    /// Step forward to a newer query (Down), ending on what was typed.
+    pub fn recall_newer_search(&mut self) {
+        let Some(idx) = self.search.recall else {
+            return;
+        };
+        if idx + 1 < self.search.history.len() {
+            self.search.recall = Some(idx + 1);
+            self.set_search(self.search.history[idx + 1].clone());
+        } else {
+            self.search.recall = None;
+            let draft = std::mem::take(&mut self.search.draft);
+            self.set_search(draft);
+        }
+    }
+
+    pub fn save_ui_state(&self) {
+        let Some(path) = &self.state_file else {
+            return;
+        };
+        state::save_state(path, &UiState {
+            sort_key: self.sort_key.as_str().to_string(),
+            sort_desc: self.sort_desc,
+            recurring_sort: self.recurring_sort.as_str().to_string(),
+            group_by_date: self.group_by_date,
+            onboarded: !self.show_onboarding,
+            search_history: self.search.history.clone(),
+        });
+    }
+
+    fn filter_transactions(&self) -> Vec<Transaction> {
+        let search = self.filter.search.trim();
+        let query = (!search.is_empty()).then(|| Query::parse(search));
+
+        self.transactions
+            .iter()
+            .filter(|tx| {
+                if let Some(tag_idx) = self.filter.tag_index
+                    && tx.tag.as_str() != self.tags[tag_idx].as_str()
+                {
+                    return false;
+                }
+                if !self.filter.start_date.is_empty() && tx.date < self.filter.start_date {
+                    return false;
+                }
+                if !self.filter.end_date.is_empty() && tx.date > self.filter.end_date {
+                    return false;
+                }
+                if let Some(query) = &query
+                    && !query.matches(tx, self.note_tags.get(&tx.id))
+                {
+                    return false;
+                }
+                true
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Insert recurring entries that are due, either silently or after the
+    /// user confirms a preview, depending on `auto_confirm_recurring`.
+    pub fn process_recurring(&mut self, conn: &Connection) {
+        if self.auto_confirm_recurring {
+            db::insert_recurring_transactions(conn).unwrap();
+            self.refresh(conn);
+            return;
+        }
+
+        let due = db::due_recurring_entries(conn).unwrap_or_default();
+        if due.is_empty() {
+            return;
+        }
+
+        let money = self.money();
+        let mut message = format!("{} recurring entries are due:\n", due.len());
+        for entry in &due {
+            message.push_str(&format!(
+                "\n{}  {}  ({})",
+                entry.source,
+                money.format(entry.amount),
+                entry.interval.display()
+            ));
+        }
+        message.push_str("\n\nAdd them now?");
+
+        self.open_confirm_popup("Recurring Entries", message, PopupAction::InsertRecurring);
+        self.set_popup_size(PopupSize::Large);
+    }
+
+    /// Report recurring rules whose last insertion is malformed or in the
+    /// future and offer to reset them. Returns whether the popup opened.
+    pub fn check_recurring_state(&mut self, conn: &Connection) -> bool {
+        let invalid = db::invalid_recurring_entries(conn).unwrap_or_default();
+        if invalid.is_empty() {
+            return false;
+        }
+
+        let mut message = format!("{} recurring entries have a bad last insertion:\n", invalid.len());
+        for (entry, reason) in &invalid {
+            message.push_str(&format!(
+                "\n{}  ({}, {})",
+                entry.source,
+                entry.last_inserted_date,
+                reason
+            ));
+        }
+        message.push_str("\n\nReset them? They'll be inserted again on their next due day.");
+
+        let ids = invalid.iter().map(|(entry, _)| entry.id).collect();
+        self.open_confirm_popup("Recurring State", message, PopupAction::ResetRecurringState(ids));
+        self.set_popup_size(PopupSize::Large);
+        true
+    }
+
+    /// Export what the user is looking at: the filtered view when a filter
+    /// is applied, otherwise every transaction.
+    pub fn export_view(&mut self, conn: &Connection) {
+        let path = db::export_path();
+
+        let result = if self.filter.active {
+            db::write_transactions_csv(&self.get_filtered_transactions(), &path, self.decimal_places)
+        } else {
+            db::export_transactions_csv(conn, &path, self.decimal_places)
+        };
+
+        match result {
+            Ok(count) => {
+                // Show the full path; in debug builds data_dir is relative
+                let path = std::fs::canonicalize(&path).unwrap_or(path);
+                self.open_info_popup(
+                    "Export Complete",
+                    format!("Exported {} transactions to\n{}", count, path.display()),
+                );
+                self.offer_to_open(path);
+            }
+            Err(err) => self.open_info_popup("Export Failed", err.to_string()),
+        }
+    }
+
+    /// Export spending per tag, limited to the filtered view when a filter
+    /// is applied.
+    pub fn export_tag_summary(&mut self, conn: &Connection) {
+        let path = db::tag_summary_path();
+
+        let result = if self.filter.active {
+            let filtered: Vec<Transaction> = self
+                .get_filtered_transactions()
+                .into_iter()
+                .map(|tx| Transaction { amount: tx.base_amount(&self.exchange_rates), currency: String::new(), ..tx })
+                .collect();
+            let per_tag = crate::stats::calculate_spent_per_tag(&filtered);
+            db::write_tag_summary_csv(&per_tag, &path, self.decimal_places)
+        } else {
+            db::export_tag_summary_csv(conn, &self.exchange_rates, &path, self.decimal_places)
+        };
+
+        match result {
+            Ok(count) => {
+                let path = std::fs::canonicalize(&path).unwrap_or(path);
+                self.open_info_popup(
+                    "Export Complete",
+                    format!("Exported totals for {} tags to\n{}", count, path.display()),
+                );
+                self.offer_to_open(path);
+            }
+            Err(err) => self.open_info_popup("Export Failed", err.to_string()),
+        }
+    }
+
+    /// Export the monthly earned/spent/net and per-tag series as tidy CSV
+    /// for plotting elsewhere. Always covers every transaction.
+    pub fn export_chart_data(&mut self, conn: &Connection) {
+        let path = db::chart_data_path();
+
+        match db::export_chart_data_csv(conn, &self.exchange_rates, &path, self.decimal_places) {
+            Ok(count) => {
+                let path = std::fs::canonicalize(&path).unwrap_or(path);
+                self.open_info_popup(
+                    "Export Complete",
+                    format!("Exported {} chart data rows to\n{}", count, path.display()),
+                );
+                self.offer_to_open(path);
+            }
+            Err(err) => self.open_info_popup("Export Failed", err.to_string()),
+        }
+    }
+
+    /// Copy the highlighted transaction as one line of text, e.g.
+    /// `2024-06-01  Groceries  -$12.50  #food`.
+    pub fn copy_selected(&mut self) {
+        let Some(tx) = self.selected_transaction() else {
+            return;
+        };
+        // Privacy mode only hides the screen; the copy gets real amounts
+        let line = Money { hidden: false, ..self.money() }.describe(&tx);
+
+        if self.clipboard.is_none() {
+            match arboard::Clipboard::new() {
+                Ok(clipboard) => self.clipboard = Some(clipboard),
+                Err(err) => {
+                    self.open_info_popup("Clipboard Unavailable", clipboard_error(&err, &line));
+                    return;
+                }
+            }
+        }
+
+        if let Some(clipboard) = self.clipboard.as_mut() {
+            match clipboard.set_text(line.clone()) {
+                Ok(()) => self.show_toast("Copied to clipboard"),
+                Err(err) => self.open_info_popup("Clipboard Unavailable", clipboard_error(&err, &line)),
+            }
+        }
+    }
+
+    /// Flash `message` at the bottom of the screen for `TOAST_DURATION`.
+    pub fn show_toast(&mut self, message: impl Into<String>) {
+        self.toast = Some(Toast {
+            message: message.into(),
+            shown_at: std::time::Instant::now(),
+        });
+    }
+
+    /// Drop the toast once it has been shown long enough. Returns whether
+    /// one was removed, so the screen needs redrawing.
+    pub fn expire_toast(&mut self, now: std::time::Instant) -> bool {
+        let expired = self
+            .toast
+            .as_ref()
+            .is_some_and(|toast| now.duration_since(toast.shown_at) >= TOAST_DURATION);
+        if expired {
+            self.toast = None;
+        }
+        expired
+    }
+
+    /// How long the main loop may block waiting for input: until just after
+    /// midnight, when recurring entries may fall due, or sooner if
+    /// `poll_interval_ms` asks for it.
+    pub fn idle_timeout(&self, now: chrono::NaiveDateTime) -> std::time::Duration {
+        let next_day = now.date().succ_opt().unwrap_or(now.date()).and_time(chrono::NaiveTime::MIN);
+        let until_midnight = (next_day - now)
+            .to_std()
+            .unwrap_or_default()
+            + std::time::Duration::from_secs(1);
+
+        let timeout = if self.poll_interval_ms > 0 {
+            until_midnight.min(std::time::Duration::from_millis(self.poll_interval_ms))
+        } else {
+            until_midnight
+        };
+
+        // Wake up in time to take a toast down
+        match &self.toast {
+            Some(toast) => timeout.min(TOAST_DURATION.saturating_sub(toast.shown_at.elapsed())),
+            None => timeout,
+        }
+    }
+
+    /// The once-a-day work: due recurring entries and the monthly balance
+    /// snapshot. Runs at startup and again if the app is left open past
+    /// midnight.
+    pub fn new_day(&mut self, conn: &Connection) {
+        // Due entries wait for the next session that can write them
+        if !self.read_only {
+            // A broken rule is reported first; recurring runs once it's answered
+            if !self.check_recurring_state(conn) {
+                self.process_recurring(conn);
+            }
+            self.record_balance_snapshot(conn);
+        }
+        self.dirty = true;
+    }
+
+    /// Explain why a key did nothing in read-only mode.
+    pub fn refuse_read_only(&mut self) {
+        self.open_info_popup(
+            "Read-only",
+            "Started with --read-only, so changes are disabled.\n\nRestart without it to edit."
+                .into(),
+        );
+    }
+
+    /// Mark or unmark the highlighted transaction for multi-row actions.
+    pub fn toggle_mark_selected(&mut self) {
+        if let Some(tx) = self.selected_transaction()
+            && !self.selected_ids.remove(&tx.id)
+        {
+            self.selected_ids.insert(tx.id);
+        }
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.selected_ids.clear();
+    }
+
+    /// Rows a batch action applies to: the marked ones, or the selected row
+    /// when nothing is marked.
+    pub fn batch_targets(&self) -> Vec<i32> {
+        if !self.selected_ids.is_empty() {
+            return self.selected_ids.iter().copied().collect();
+        }
+
+        self.selected_transaction().map(|tx| vec![tx.id]).unwrap_or_default()
+    }
+
+    /// Open the tag picker for retagging, starting on the selected row's tag.
+    pub fn begin_retag(&mut self) {
+        let Some(tx) = self.selected_transaction() else {
+            return;
+        };
+
+        self.retag_index = self
+            .tags
+            .iter()
+            .position(|t| t.as_str() == tx.tag.as_str())
+            .unwrap_or(0);
+        self.mode = Mode::Retag;
+    }
+
+    /// Move the batch targets to the picked tag and clear the marks.
+    pub fn apply_retag(&mut self, conn: &Connection) {
+        if let Some(tag) = self.tags.get(self.retag_index).cloned() {
+            let ids = self.batch_targets();
+            db::retag_transactions(conn, &ids, &tag).unwrap();
+
+            let rows: Vec<(Transaction, Transaction)> = self
+                .transactions
+                .iter()
+                .filter(|tx| ids.contains(&tx.id))
+                .map(|tx| (tx.clone(), Transaction { tag: tag.clone(), ..tx.clone() }))
+                .collect();
+            if !rows.is_empty() {
+                self.history.record(Change::Retag(rows));
+            }
+        }
+
+        self.clear_marks();
+        self.mode = Mode::Normal;
+        self.refresh(conn);
+    }
+
+    /// Rows a percentage change applies to: the marked ones, every row the
+    /// active filter shows (e.g. one tag), or else the selected row.
+    pub fn scale_targets(&self) -> Vec<i32> {
+        if self.selected_ids.is_empty() && self.filter.active {
+            return self.get_filtered_transactions().iter().map(|tx| tx.id).collect();
+        }
+
+        self.batch_targets()
+    }
+
+    /// Open the percentage prompt for scaling amounts.
+    pub fn begin_scale(&mut self) {
+        if self.scale_targets().is_empty() {
+            return;
+        }
+
+        self.scale_input.clear();
+        self.mode = Mode::ScaleAmounts;
+    }
+
+    /// Check the typed percentage and ask for confirmation, showing the
+    /// credit and debit totals before and after.
+    pub fn confirm_scale(&mut self) {
+        let factor = match crate::form::parse_percent_change(&self.scale_input) {
+            Ok(factor) => factor,
+            Err(msg) => {
+                self.open_info_popup("Invalid Percentage", msg);
+                return;
+            }
+        };
+
+        let ids = self.scale_targets();
+        // Before and after totals, each row rounded the way it'll be stored
+        let (credits, debits, scaled_credits, scaled_debits) = self
+            .transactions
+            .iter()
+            .filter(|tx| ids.contains(&tx.id))
+            .fold((0.0, 0.0, 0.0, 0.0), |(credit, debit, new_credit, new_debit), tx| {
+                let amount = tx.base_amount(&self.exchange_rates);
+                let scaled = Transaction {
+                    amount: self.rounding.scale(tx.amount, factor, self.decimal_places),
+                    ..tx.clone()
+                }
+                .base_amount(&self.exchange_rates);
+                match tx.kind {
+                    TransactionType::Credit => (credit + amount, debit, new_credit + scaled, new_debit),
+                    TransactionType::Debit => (credit, debit + amount, new_credit, new_debit + scaled),
+                }
+            });
+
+        let money = self.money();
+        let message = format!(
+            "Scale {} transactions by {:+}%?\n\nCredits {} {} {}\nDebits  {} {} {}",
+            ids.len(),
+            crate::money::Rounding::HalfUp.round((factor - 1.0) * 100.0, 2),
+            money.format(credits),
+            self.theme.glyphs.right,
+            money.format(scaled_credits),
+            money.format(debits),
+            self.theme.glyphs.right,
+            money.format(scaled_debits)
+        );
+        self.open_confirm_popup("Confirm Scale", message, PopupAction::ScaleAmounts(ids, factor));
+    }
+
+    /// Multiply the amounts of `ids` by `factor` and clear the marks.
+    pub fn scale_amounts(&mut self, conn: &Connection, ids: &[i32], factor: f64) {
+        let before: Vec<Transaction> =
+            self.transactions.iter().filter(|t| ids.contains(&t.id)).cloned().collect();
+        let changed = db::scale_amounts(conn, ids, factor, self.decimal_places, self.rounding).unwrap();
+        self.clear_marks();
+        self.refresh(conn);
+
+        let rows: Vec<(Transaction, Transaction)> = before
+            .into_iter()
+            .filter_map(|old| {
+                let new = self.transactions.iter().find(|t| t.id == old.id)?.clone();
+                Some((old, new))
+            })
+            .collect();
+        if !rows.is_empty() {
+            self.history.record(Change::Scale(rows));
+        }
+        self.show_toast(format!("Scaled {} transactions", changed));
+    }
+
+    /// Flip the cleared flag on the batch targets. A mixed batch is cleared
+    /// as a whole rather than flipped row by row.
+    pub fn toggle_cleared(&mut self, conn: &Connection) {
+        let ids = self.batch_targets();
+        if ids.is_empty() {
+            return;
+        }
+
+        let all_cleared = self
+            .transactions
+            .iter()
+            .filter(|tx| ids.contains(&tx.id))
+            .all(|tx| tx.cleared);
+        db::set_cleared(conn, &ids, !all_cleared).unwrap();
+
+        self.clear_marks();
+        self.refresh(conn);
+    }
+
+    /// Sum of the marked transactions as (credits, debits), in the base currency.
+    pub fn marked_totals(&self) -> (f64, f64) {
+        self.transactions
+            .iter()
+            .filter(|tx| self.selected_ids.contains(&tx.id))
+            .fold((0.0, 0.0), |(credit, debit), tx| {
+                let amount = tx.base_amount(&self.exchange_rates);
+                match tx.kind {
+                    TransactionType::Credit => (credit + amount, debit),
+                    TransactionType::Debit => (credit, debit + amount),
+                }
+            })
+    }
+
+    /// Whether `tx` is a debit large enough to be highlighted in the list.
+    pub fn is_large_debit(&self, tx: &Transaction) -> bool {
+        match self.highlight_threshold {
+            Some(threshold) => {
+                tx.kind == TransactionType::Debit
+                    && tx.base_amount(&self.exchange_rates) > threshold
+            }
+            None => false,
+        }
+    }
+
+    /// How each budgeted tag's month stands against its budget, keyed by
+    /// (YYYY-MM, tag). Counts every debit, whatever the list filter shows,
+    /// so the current month reflects spending to date.
+    pub fn budget_bands(&self) -> HashMap<(String, Tag), BudgetBand> {
+        let mut spent: HashMap<(String, Tag), f64> = HashMap::new();
+        for tx in &self.transactions {
+            if tx.kind != TransactionType::Debit || !self.tag_budgets.contains_key(&tx.tag) {
+                continue;
+            }
+            let month = tx.date.get(..7).unwrap_or(&tx.date).to_string();
+            *spent.entry((month, tx.tag.clone())).or_insert(0.0) += tx.base_amount(&self.exchange_rates);
+        }
+
+        spent
+            .into_iter()
+            .map(|(key, total)| {
+                let budget = self.tag_budgets[&key.1];
+                let band = if total > budget {
+                    BudgetBand::Over
+                } else if total >= budget * self.budget_warning_percent / 100.0 {
+                    BudgetBand::Near
+                } else {
+                    BudgetBand::Under
+                };
+                (key, band)
+            })
+            .collect()
+    }
+
+    /// Number of list rows to render for a list of `len` transactions.
+    pub fn visible_limit(&self, len: usize) -> usize {
+        len.min(LIST_LIMIT.max(self.selected + 1))
+    }
+
+    pub fn select_next(&mut self, len: usize) {
+        // Past the default window (after a jump) the whole list is reachable
+        let max_len = if self.selected >= LIST_LIMIT { len } else { len.min(LIST_LIMIT) };
+        if self.selected + 1 < max_len {
+            self.selected += 1;
+        }
+    }
+
+    /// Move the selection to the next (or previous) row in the current view
+    /// whose kind differs from the selected one, wrapping at the ends.
+    pub fn jump_to_other_kind(&mut self, forward: bool) {
+        let transactions = self.get_filtered_transactions();
+        let len = transactions.len();
+        let Some(current) = transactions.get(self.selected) else {
+            return;
+        };
+
+        let target = (1..len)
+            .map(|step| if forward { (self.selected + step) % len } else { (self.selected + len - step) % len })
+            .find(|&i| transactions[i].kind != current.kind);
+
+        if let Some(idx) = target {
+            self.selected = idx;
+        }
+    }
+
+    /// Select the newest transaction dated on or before `date` in the
+    /// current (date-descending) view.
+    pub fn jump_to_date(&mut self, date: &str) {
+        if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+            self.open_info_popup("Jump to Date", format!("'{}' is not a valid YYYY-MM-DD date.", date));
+            return;
+        }
+
+        let transactions = self.get_filtered_transactions();
+        if transactions.is_empty() {
+            self.open_info_popup("Jump to Date", "There are no transactions to jump to.".into());
+            return;
+        }
+
+        // The newest row on or before the date, wherever the sort puts it
+        let target = transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, tx)| tx.date.as_str() <= date)
+            .max_by(|(i, a), (j, b)| a.date.cmp(&b.date).then(j.cmp(i)))
+            .map(|(i, _)| i);
+
+        if let Some(idx) = target {
+            self.selected = idx;
+        } else {
+            // Everything is newer than the target; the oldest is closest
+            self.selected = transactions
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.date.cmp(&b.date))
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            self.open_info_popup(
+                "Jump to Date",
+                format!(
+                    "No transactions on or before {}.\nJumped to the oldest one ({}).",
+                    date, transactions[self.selected].date
+                ),
+            );
+        }
+    }
+
+    /// Show the monthly spending trend of the current trend tag.
+    pub fn open_tag_trend(&mut self, conn: &Connection) {
+        if self.tags.is_empty() {
+            return;
+        }
+
+        self.trend_tag_index = self.trend_tag_index.min(self.tags.len() - 1);
+        self.load_tag_trend(conn);
+        self.mode = Mode::TagTrend;
+    }
+
+    /// Step the trend popup to the next (`step` > 0) or previous tag.
+    pub fn cycle_trend_tag(&mut self, conn: &Connection, step: isize) {
+        let len = self.tags.len() as isize;
+        if len == 0 {
+            return;
+        }
+
+        self.trend_tag_index = (self.trend_tag_index as isize + step).rem_euclid(len) as usize;
+        self.load_tag_trend(conn);
+    }
+
+    fn load_tag_trend(&mut self, conn: &Connection) {
+        let tag = &self.tags[self.trend_tag_index];
+        let series = db::spent_per_tag_per_month(conn, tag, &self.exchange_rates).unwrap_or_default();
+        let today = chrono::Local::now().date_naive();
+
+        self.tag_trend = crate::stats::zero_fill_months(&series, today, self.stats_months);
+        self.tag_total = db::total_for_tag(conn, tag, &self.exchange_rates).unwrap_or_default();
+    }
+
+    /// Open the year-at-a-glance popup on the current year.
+    pub fn open_year_summary(&mut self, conn: &Connection) {
+        self.summary_year = chrono::Local::now().year();
+        self.load_year_summary(conn);
+        self.mode = Mode::YearSummary;
+    }
+
+    /// Move the annual summary `step` years forward or back.
+    pub fn step_summary_year(&mut self, conn: &Connection, step: i32) {
+        self.summary_year += step;
+        self.load_year_summary(conn);
+    }
+
+    fn load_year_summary(&mut self, conn: &Connection) {
+        self.year_net = [None; 12];
+        for (month, net) in db::net_per_month_for_year(conn, self.summary_year, &self.exchange_rates).unwrap_or_default() {
+            // Rows with a malformed date can report month 0
+            if let Some(slot) = (month as usize).checked_sub(1).and_then(|i| self.year_net.get_mut(i)) {
+                *slot = Some(net);
+            }
+        }
+    }
+
+    /// Report every source with its total spend and number of debits,
+    /// biggest first, to show where the money goes by vendor.
+    pub fn open_source_report(&mut self, conn: &Connection) {
+        let rows = db::spend_per_source(conn, &self.exchange_rates).unwrap_or_default();
+        if rows.is_empty() {
+            self.open_info_popup("Spending by Source", "No spending recorded yet.".into());
+            return;
+        }
+
+        let money = self.money();
+        let ellipsis = self.theme.glyphs.ellipsis;
+        let names: Vec<String> =
+            rows.iter().map(|(source, _, _)| crate::ui::truncate_string(source, 24, ellipsis)).collect();
+        let width = names.iter().map(|n| n.chars().count()).max().unwrap_or(0).max("Source".len());
+        let amount_width = rows.iter().map(|(_, total, _)| money.number(*total).len()).max().unwrap_or(0);
+
+        let total = crate::money::sum_amounts(rows.iter().map(|(_, total, _)| *total));
+        let mut report = format!(
+            "{} sources, {} in total\n\n{:<width$}  {:>5}  Total\n",
+            rows.len(),
+            money.format(total),
+            "Source",
+            "Count",
+        );
+        for (name, (_, amount, count)) in names.iter().zip(&rows) {
+            report.push_str(&format!(
+                "\n{:<width$}  {:>5}  {}",
+                name,
+                count,
+                money.padded(*amount, amount_width)
+            ));
+        }
+
+        self.open_info_popup("Spending by Source", report);
+        self.set_popup_size(PopupSize::Large);
+    }
+
+    /// Open the selected row's receipt with the system's default viewer.
+    pub fn open_selected_receipt(&mut self) {
+        let Some(tx) = self.selected_transaction() else {
+            return;
+        };
+
+        if tx.receipt_path.is_empty() {
+            self.open_info_popup("Receipt", "This transaction has no receipt attached.".into());
+        } else if let Err(err) = open::that_detached(&tx.receipt_path) {
+            self.open_info_popup(
+                "Receipt",
+                format!("Couldn't open {}\n{}", tx.receipt_path, err),
+            );
+        }
+    }
+
+    /// Let the open popup offer `path` to the default application. Without
+    /// a desktop session the popup keeps showing just the path.
+    pub fn offer_to_open(&mut self, path: std::path::PathBuf) {
+        if gui_available() {
+            self.popup_file = Some(path);
+        }
+    }
+
+    /// Open the file offered by the popup and close it.
+    pub fn open_popup_file(&mut self) {
+        let Some(path) = self.popup_file.take() else {
+            return;
+        };
+
+        self.close_popup();
+        if let Err(err) = open::that_detached(&path) {
+            self.open_info_popup(
+                "Open Failed",
+                format!("Couldn't open {}\n{}", path.display(), err),
+            );
+        }
+    }
+
+    /// Cache the known sources so completion doesn't query on every key.
+    pub fn load_source_history(&mut self, conn: &Connection) {
+        self.source_history = db::distinct_sources(conn).unwrap_or_default();
+    }
+
+    /// Ask only for the amount of another of the most recently added
+    /// transaction, e.g. a second coffee: same source, tag, kind and
+    /// currency, dated today. The prompt fills in `form`, so saving goes
+    /// through the usual checks. An empty ledger gets the blank form.
+    pub fn begin_quick_add(&mut self, conn: &Connection) {
+        self.form.reset();
+        self.editing = None;
+
+        // Highest id = added last, whatever date it was given
+        let Some(last) = self.transactions.iter().max_by_key(|tx| tx.id) else {
+            self.load_source_history(conn);
+            self.mode = Mode::Adding;
+            return;
+        };
+
+        self.form.source = last.source.clone();
+        self.form.kind = last.kind;
+        if let Some(index) = self.tags.iter().position(|t| t.as_str() == last.tag.as_str()) {
+            self.form.tag_index = index;
+        }
+        self.form.currency = last.currency.clone();
+        self.form.active = crate::form::Field::Amount;
+        self.mode = Mode::QuickAdd;
+    }
+
+    pub fn begin_edit_selected(&mut self) {
+        let tx = match self.selected_transaction() {
+            Some(t) => t,
+            None => return,
+        };
+
+        self.form.source = tx.source.clone();
+        self.form.amount = crate::form::amount_for_editing(tx.amount, self.decimal_places);
+        self.form.kind = tx.kind;
+
+        self.form.tag_index = self
+            .tags
+            .iter()
+            .position(|t| t.as_str() == tx.tag.as_str())
+            .unwrap_or(0);
+
+        self.form.date = tx.date.clone();
+        self.form.currency = tx.currency.clone();
+        self.form.receipt_path = tx.receipt_path.clone();
+        self.form.note = tx.note.clone();
+        self.form.active = crate::form::Field::Source;
+
+        let recurring_entry = self.recurring_entries.iter().find(|r| {
+            r.source == tx.source
+                && r.amount == tx.amount
+                && r.kind == tx.kind
+                && r.tag == tx.tag
+        });
+
+        if let Some(entry) = recurring_entry {
+            self.form.recurring = true;
+            self.form.recurring_interval = entry.interval.clone();
+        } else {
+            self.form.recurring = false;
+            self.form.recurring_interval = crate::models::RecurringInterval::Monthly;
+        }
+
+        self.mode = Mode::Adding;
+        self.editing = Some(tx.id);
+    }
+
+    /// Load the highlighted recurring rule into the form. Saving updates the
+    /// rule; transactions it already inserted are left alone.
+    pub fn begin_edit_recurring(&mut self) {
+        let Some(entry) = self.recurring_entries.get(self.selected_recurring).cloned() else {
+            return;
+        };
+
+        self.form.reset();
+        self.form.source = entry.source.clone();
+        self.form.amount = crate::form::amount_for_editing(entry.amount, self.decimal_places);
+        self.form.kind = entry.kind;
+        self.form.tag_index = self.tags.iter().position(|t| *t == entry.tag).unwrap_or(0);
+        self.form.date = entry.original_date.clone();
+        self.form.recurring = true;
+        self.form.recurring_interval = entry.interval;
+        self.form.prorate_first = entry.prorate_first;
+        self.form.active = crate::form::Field::Source;
+
+        self.editing_recurring = Some(entry.id);
+        self.mode = Mode::Adding;
+    }
+
+    /// Delete one transaction. If an active recurring rule inserted it, ask
+    /// whether to pause the rule too, since it would only add it again.
+    pub fn delete_transaction(&mut self, conn: &Connection, id: i32) {
+        let rule = self
+            .transactions
+            .iter()
+            .find(|t| t.id == id)
+            .and_then(|t| t.created_by_recurring_id)
+            .and_then(|rec_id| self.recurring_entries.iter().find(|r| r.id == rec_id && r.active))
+            .cloned();
+
+        let removed: Vec<Transaction> = self.transactions.iter().filter(|t| t.id == id).cloned().collect();
+        db::delete_transaction(conn, id).unwrap();
+        if !removed.is_empty() {
+            self.history.record(Change::Delete(removed));
+        }
+        self.refresh(conn);
+
+        if let Some(rule) = rule {
+            self.open_confirm_popup(
+                "Pause Recurring Rule",
+                format!(
+                    "'{}' was added by a {} recurring rule, which will add it again.\n\nPause the rule too?",
+                    rule.source,
+                    rule.interval.display().to_lowercase()
+                ),
+                PopupAction::PauseRecurring(rule.id),
+            );
+        }
+    }
+
+    /// Ask before deleting: the selected row with y/n, or the marked rows,
+    /// which need the confirm word typed once there are enough of them.
+    pub fn confirm_delete(&mut self) {
+        if self.selected_ids.is_empty() {
+            if let Some(tx) = self.selected_transaction() {
+                let money = self.money();
+                let amount = if tx.currency.is_empty() {
+                    money.format(tx.amount)
+                } else {
+                    money.with_code(&tx.currency, tx.amount)
+                };
+                self.open_confirm_popup(
+                    "Confirm Delete",
+                    format!("Delete this transaction?\n\n{}  ({})", tx.source, amount),
+                    PopupAction::DeleteTransaction(tx.id),
+                );
+            }
+            return;
+        }
+
+        let ids = self.batch_targets();
+        let (credits, debits) = self.marked_totals();
+        let money = self.money();
+        let message = format!(
+            "Delete {} marked transactions?\n\nCredits {}  Debits {}",
+            ids.len(),
+            money.format(credits),
+            money.format(debits)
+        );
+
+        if self.typed_confirm_threshold > 0 && ids.len() >= self.typed_confirm_threshold {
+            let word = self.typed_confirm_word.clone();
+            self.open_typed_confirm_popup(
+                "Confirm Delete",
+                format!("{}\n\nType {} to confirm.", message, word),
+                word,
+                PopupAction::DeleteTransactions(ids),
+            );
+        } else {
+            self.open_confirm_popup("Confirm Delete", message, PopupAction::DeleteTransactions(ids));
+        }
+    }
+
+    /// Delete a batch of rows and clear the marks.
+    pub fn delete_transactions(&mut self, conn: &Connection, ids: &[i32]) {
+        let rows: Vec<Transaction> =
+            self.transactions.iter().filter(|t| ids.contains(&t.id)).cloned().collect();
+        let removed = db::delete_transactions(conn, ids).unwrap();
+        if !rows.is_empty() {
+            self.history.record(Change::Delete(rows));
+        }
+        self.clear_marks();
+        self.refresh(conn);
+        self.show_toast(format!("Deleted {} transactions", removed));
+    }
+
+    /// Reverse the most recent add, edit, delete or retag.
+    pub fn undo(&mut self, conn: &Connection) {
+        let Some(change) = self.history.undo.pop() else {
+            self.show_toast("Nothing to undo");
+            return;
+        };
+
+        match change.apply(conn, true) {
+            Ok(()) => {
+                self.show_toast(format!("Undid {}", change.describe()));
+                self.history.redo.push(change);
+            }
+            Err(err) => self.open_info_popup("Undo Failed", err.to_string()),
+        }
+        self.refresh(conn);
+    }
+
+    /// Apply the most recently undone change again.
+    pub fn redo(&mut self, conn: &Connection) {
+        let Some(change) = self.history.redo.pop() else {
+            self.show_toast("Nothing to redo");
+            return;
+        };
+
+        match change.apply(conn, false) {
+            Ok(()) => {
+                self.show_toast(format!("Redid {}", change.describe()));
+                self.history.undo.push(change);
+            }
+            Err(err) => self.open_info_popup("Redo Failed", err.to_string()),
+        }
+        self.refresh(conn);
+    }
+
+    pub fn delete_selected(&mut self, conn: &Connection) {
+        if let Some(tx) = self.selected_transaction() {
+            db::delete_transaction(conn, tx.id).unwrap();
+            self.refresh(conn);
+        }
+    }
+
+    pub fn open_confirm_popup(
+        &mut self,
+        title: &str,
+        message: String,
+        action: PopupAction,
+    ) {
+        self.popup_choice = if action.is_destructive() {
+            ConfirmChoice::No
+        } else {
+            ConfirmChoice::Yes
+        };
+        self.popup = Some(PopupKind::Confirm {
+            title: title.into(),
+            message,
+            action,
+            size: PopupSize::Normal,
+        });
+        self.popup_scroll = 0;
+
+        self.mode = Mode::Popup;
+    }
+
+    /// Like `open_confirm_popup`, but the action only runs after `word` is
+    /// typed in full.
+    pub fn open_typed_confirm_popup(
+        &mut self,
+        title: &str,
+        message: String,
+        word: String,
+        action: PopupAction,
+    ) {
+        self.popup = Some(PopupKind::TypedConfirm {
+            title: title.into(),
+            message,
+            word,
+            typed: String::new(),
+            action,
+            size: PopupSize::Normal,
+        });
+        self.popup_scroll = 0;
+
+        self.mode = Mode::Popup;
+    }
+
+    pub fn open_info_popup(&mut self, title: &str, message: String) {
+        self.popup = Some(PopupKind::Info {
+            title: title.into(),
+            message,
+            size: PopupSize::Normal,
+        });
+        self.popup_file = None;
+        self.popup_scroll = 0;
+
+        self.mode = Mode::Popup;
+    }
+
+    /// Resize the open popup, e.g. to give a list more room.
+    pub fn set_popup_size(&mut self, new_size: PopupSize) {
+        if let Some(
+            PopupKind::Confirm { size, .. }
+            | PopupKind::Info { size, .. }
+            | PopupKind::TypedConfirm { size, .. },
+        ) = &mut self.popup
+        {
+            *size = new_size;
+        }
+    }
+
+    pub fn close_popup(&mut self) {
+        self.popup = None;
+        self.popup_file = None;
+        self.popup_scroll = 0;
+        self.mode = Mode::Normal;
+    }
+
+    pub fn scroll_stats_up(&mut self) {
+        self.stats_scroll = self.stats_scroll.saturating_sub(1);
+    }
+
+    /// Like `scroll_popup_down`, capped at the last details line; the
+    /// renderer clamps to the visible height.
+    pub fn scroll_stats_down(&mut self) {
+        let max = self.stats_lines.saturating_sub(1) as u16;
+        self.stats_scroll = (self.stats_scroll + 1).min(max);
+    }
+
+    pub fn scroll_popup_up(&mut self) {
+        self.popup_scroll = self.popup_scroll.saturating_sub(1);
+    }
+
+    /// Scroll the popup message down one line. The offset is capped at the
+    /// last row the message can wrap to here; the renderer clamps it further
+    /// to the visible height since only it knows the popup size.
+    pub fn scroll_popup_down(&mut self) {
+        let line_count = self
+            .popup
+            .as_ref()
+            .map(|p| crate::ui::popup_line_bound(p.message()))
+            .unwrap_or(0);
+
+        let max = line_count.saturating_sub(1) as u16;
+        if self.popup_scroll < max {
+            self.popup_scroll += 1;
+        }
     }
 
     pub fn selected_transaction(&self) -> Option<Transaction> {
@@ -354,27 +2510,824 @@ impl App {
         filtered.get(self.selected).cloned()
     }
 
-    pub fn get_recurring_for_transaction(&self, tx: &Transaction) -> Option<&RecurringEntry> {
-        self.recurring_entries.iter().find(|r| {
-            r.source == tx.source
-                && r.amount == tx.amount
-                && r.kind == tx.kind
-                && r.tag == tx.tag
-                && r.active
-        })
+    pub fn get_recurring_for_transaction(&self, tx: &Transaction) -> Option<&RecurringEntry> {
+        // Prefer the explicit link recorded at insert time
+        if let Some(rec_id) = tx.created_by_recurring_id
+            && let Some(entry) = self.recurring_entries.iter().find(|r| r.id == rec_id && r.active)
+        {
+            return Some(entry);
+        }
+
+        self.recurring_entries.iter().find(|r| {
+            r.source == tx.source
+                && r.amount == tx.amount
+                && r.kind == tx.kind
+                && r.tag == tx.tag
+                && r.active
+        })
+    }
+}
+
+/// Parse the configured tag names, dropping duplicates and making sure the
+/// fallback tag is always selectable.
+pub fn build_tag_list(names: &[String], fallback: &Tag) -> Vec<Tag> {
+    let mut tags: Vec<Tag> = Vec::new();
+    for name in names {
+        let tag = Tag::parse_or(name, fallback.as_str());
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    if !tags.contains(fallback) {
+        tags.push(fallback.clone());
+    }
+    tags
+}
+
+/// Position of the `default_tag` config in `tags`, ignoring case. Falls
+/// back to the first tag when unset or not in the list.
+pub fn default_tag_index(tags: &[Tag], name: Option<&str>) -> usize {
+    name.and_then(|name| tags.iter().position(|t| t.as_str().eq_ignore_ascii_case(name.trim())))
+        .unwrap_or(0)
+}
+
+/// Whether there's a desktop to open files in. On Linux and the BSDs that
+/// means an X11 or Wayland session; SSH and console logins have neither.
+fn gui_available() -> bool {
+    if cfg!(any(target_os = "windows", target_os = "macos")) {
+        return true;
+    }
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|v| !v.is_empty()))
+}
+
+/// Popup text when copying fails, e.g. over SSH without a display. The
+/// line is shown so it can still be selected by hand.
+fn clipboard_error(err: &arboard::Error, line: &str) -> String {
+    format!("Couldn't reach the system clipboard ({}).\n\n{}", err, line)
+}
+
+/// Order recurring entries in place. The database returns newest first, so
+/// that order only needs restoring after another sort.
+pub fn sort_recurring_entries(entries: &mut [RecurringEntry], sort: RecurringSort) {
+    match sort {
+        RecurringSort::Newest => entries.sort_by_key(|e| std::cmp::Reverse(e.id)),
+        RecurringSort::Source => entries.sort_by_key(|e| e.source.to_lowercase()),
+        RecurringSort::Amount => entries.sort_by(|a, b| b.amount.total_cmp(&a.amount)),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// tests for tab navigation helpers
+// ---------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An app over `conn` with the default config and no saved state, so
+    /// results don't depend on the machine's config.yaml or state.yaml.
+    fn app_for(conn: &Connection) -> App {
+        App::with_config(conn, Config::default(), UiState::default())
+    }
+
+    fn base_app() -> App {
+        app_for(&db::init_in_memory().unwrap())
+    }
+
+    #[test]
+    fn tag_list_always_contains_fallback() {
+        let fallback = Tag::from_str("uncategorized");
+        let names = vec!["food".to_string(), " ".to_string(), "food".to_string()];
+
+        let tags = build_tag_list(&names, &fallback);
+        assert_eq!(tags, vec![Tag::from_str("food"), fallback.clone()]);
+
+        let mut app = base_app();
+        app.tags = tags;
+        app.fallback_tag = fallback.clone();
+        assert_eq!(app.tag_at(99), fallback);
+    }
+
+    #[test]
+    fn default_tag_is_preselected_after_reset() {
+        let tags = vec![Tag::from_str("food"), Tag::from_str("Bills"), Tag::from_str("other")];
+        assert_eq!(default_tag_index(&tags, Some(" bills ")), 1);
+        assert_eq!(default_tag_index(&tags, Some("rent")), 0);
+        assert_eq!(default_tag_index(&tags, None), 0);
+
+        let mut app = base_app();
+        app.form.default_tag_index = 2;
+        app.form.tag_index = 1;
+        app.form.reset();
+        assert_eq!(app.form.tag_index, 2);
+        assert_eq!(app.form.default_tag_index, 2);
+    }
+
+    #[test]
+    fn sample_transaction_uses_fallback_tag() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = base_app();
+        app.show_onboarding = true;
+
+        app.add_sample_transaction(&conn);
+        assert_eq!(app.transactions.len(), 1);
+        assert_eq!(app.transactions[0].tag, app.fallback_tag);
+        assert!(!app.show_onboarding);
+    }
+
+    #[test]
+    fn month_rollover_runs_once_per_month() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = base_app();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        db::add_transaction(&conn, "Pay", 100.0, TransactionType::Credit, &Tag::from_str("salary"), &today).unwrap();
+        app.refresh(&conn);
+
+        app.month_rollover(&conn);
+        match &app.popup {
+            Some(PopupKind::Info { message, .. }) => assert!(message.contains("closed")),
+            _ => panic!("expected a summary popup"),
+        }
+        assert_eq!(app.balance_snapshots.last().map(|(_, b)| *b), Some(100.0));
+
+        app.close_popup();
+        app.month_rollover(&conn);
+        match &app.popup {
+            Some(PopupKind::Info { message, .. }) => assert!(message.contains("already")),
+            _ => panic!("expected a warning popup"),
+        }
+    }
+
+    #[test]
+    fn failed_month_rollover_writes_nothing() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = base_app();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let month = &today[..7];
+        db::add_recurring_entry(&conn, &RecurringEntry::new("rent", 500.0, TransactionType::Debit, Tag::from_str("bills"), RecurringInterval::Daily, &today)).unwrap();
+        // Make the snapshot, written after the inserts, fail
+        conn.execute("DROP TABLE balance_snapshots", []).unwrap();
+
+        app.month_rollover(&conn);
+        assert!(matches!(&app.popup, Some(PopupKind::Info { title, .. }) if title == "Rollover Failed"));
+        assert!(app.transactions.is_empty());
+        assert!(db::get_transactions(&conn).unwrap().is_empty());
+        assert!(!db::is_rolled_over(&conn, month).unwrap());
+    }
+
+    #[test]
+    fn year_summary_leaves_empty_months_blank() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = base_app();
+        let tag = Tag::from_str("other");
+        db::add_transaction(&conn, "pay", 100.0, TransactionType::Credit, &tag, "2024-02-01").unwrap();
+        db::add_transaction(&conn, "pay", 50.0, TransactionType::Credit, &tag, "2023-12-01").unwrap();
+
+        app.open_year_summary(&conn);
+        assert_eq!(app.mode, Mode::YearSummary);
+
+        app.summary_year = 2024;
+        app.step_summary_year(&conn, 0);
+        assert_eq!(app.year_net[1], Some(100.0));
+        assert_eq!(app.year_net.iter().flatten().count(), 1);
+
+        app.step_summary_year(&conn, -1);
+        assert_eq!(app.summary_year, 2023);
+        assert_eq!(app.year_net[11], Some(50.0));
+    }
+
+    #[test]
+    fn clear_filters_resets_everything() {
+        let mut app = base_app();
+        app.filter.active = true;
+        app.filter.start_date = "2026-01-01".into();
+        app.filter.end_date = "2026-01-31".into();
+        app.filter.tag_index = Some(0);
+        app.selected = 3;
+
+        app.clear_filters();
+        assert!(!app.filter.active);
+        assert!(app.filter.start_date.is_empty() && app.filter.end_date.is_empty());
+        assert_eq!(app.filter.tag_index, None);
+        assert_eq!(app.selected, 0);
+        // Routine feedback is a toast, not a popup
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.toast.as_ref().is_some_and(|t| t.message.starts_with("Filters cleared")));
+    }
+
+    #[test]
+    fn filter_presets_apply_and_toggle() {
+        let mut app = base_app();
+        app.tags = vec![Tag::from_str("food"), Tag::from_str("subscriptions")];
+        app.week_start = chrono::Weekday::Sun;
+        app.filter_presets = vec![
+            FilterPresetConfig {
+                name: "Subscriptions".into(),
+                tag: Some("Subscriptions".into()),
+                ..Default::default()
+            },
+            FilterPresetConfig {
+                name: "This week".into(),
+                range: Some("this_week".into()),
+                ..Default::default()
+            },
+            FilterPresetConfig {
+                name: "Gym".into(),
+                tag: Some("fitness".into()),
+                ..Default::default()
+            },
+        ];
+        let today = NaiveDate::from_ymd_opt(2026, 3, 11).unwrap(); // a Wednesday
+
+        app.apply_filter_preset(1, today);
+        assert!(app.filter.active);
+        assert_eq!((app.filter.start_date.as_str(), app.filter.end_date.as_str()), ("2026-03-08", "2026-03-14"));
+        assert_eq!(app.active_preset(today), Some(1));
+
+        app.apply_filter_preset(0, today);
+        assert_eq!(app.filter.tag_index, Some(1));
+        assert!(app.filter.start_date.is_empty());
+        assert_eq!(app.active_preset(today), Some(0));
+
+        // Changing the filter by hand drops the preset indicator
+        app.filter.search = "netflix".into();
+        assert_eq!(app.active_preset(today), None);
+
+        // An unknown tag leaves the filter alone
+        app.apply_filter_preset(2, today);
+        assert_eq!(app.filter.search, "netflix");
+        app.apply_filter_preset(8, today);
+        assert_eq!(app.toast.as_ref().unwrap().message, "No filter preset 9");
+
+        // The same key again clears it
+        app.apply_filter_preset(0, today);
+        app.apply_filter_preset(0, today);
+        assert!(!app.filter.active);
+    }
+
+    #[test]
+    fn toast_expires_after_its_duration() {
+        let mut app = base_app();
+        app.poll_interval_ms = 0;
+        app.show_toast("Saved");
+        let shown_at = app.toast.as_ref().unwrap().shown_at;
+
+        let evening = chrono::NaiveDate::from_ymd_opt(2026, 3, 10)
+            .unwrap()
+            .and_hms_opt(20, 0, 0)
+            .unwrap();
+        assert!(app.idle_timeout(evening) <= TOAST_DURATION);
+
+        assert!(!app.expire_toast(shown_at + TOAST_DURATION / 2));
+        assert!(app.toast.is_some());
+        assert!(app.expire_toast(shown_at + TOAST_DURATION));
+        assert!(app.toast.is_none());
+        assert!(!app.expire_toast(shown_at + TOAST_DURATION));
+    }
+
+    #[test]
+    fn search_history_dedups_caps_and_recalls() {
+        let mut app = base_app();
+        for query in ["rent", "rent", "coffee", "  ", "rent"] {
+            app.begin_search();
+            app.set_search(query.into());
+            app.commit_search();
+        }
+        assert_eq!(app.search.history, vec!["rent", "coffee", "rent"]);
+
+        app.begin_search();
+        app.set_search("gro".into());
+        app.recall_older_search();
+        app.recall_older_search();
+        assert_eq!(app.filter.search, "coffee");
+        app.recall_newer_search();
+        app.recall_newer_search();
+        assert_eq!(app.filter.search, "gro");
+
+        for i in 0..SEARCH_HISTORY_LEN + 5 {
+            app.search.push(&format!("q{}", i));
+        }
+        assert_eq!(app.search.history.len(), SEARCH_HISTORY_LEN);
+        assert_eq!(app.search.history[0], "q5");
+    }
+
+    #[test]
+    fn esc_restores_query_without_recording() {
+        let mut app = base_app();
+        app.begin_search();
+        app.set_search("rent".into());
+        app.commit_search();
+        assert!(app.filter.active);
+
+        app.begin_search();
+        app.set_search("coffee".into());
+        app.cancel_search();
+        assert_eq!(app.filter.search, "rent");
+        assert_eq!(app.search.history, vec!["rent"]);
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn save_rejects_blank_source() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = app_for(&conn);
+        app.form.source = "  ".into();
+        app.form.amount = "10".into();
+
+        assert!(!app.save_transaction(&conn));
+        assert!(app.form.error.is_some());
+        assert!(db::get_transactions(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn stats_scroll_is_capped() {
+        let mut app = base_app();
+        app.stats_lines = 3;
+
+        app.scroll_stats_up();
+        assert_eq!(app.stats_scroll, 0);
+
+        for _ in 0..10 {
+            app.scroll_stats_down();
+        }
+        assert_eq!(app.stats_scroll, 2);
+    }
+
+    #[test]
+    fn retag_applies_to_marked_rows() {
+        let conn = db::init_in_memory().unwrap();
+        for source in ["a", "b", "c"] {
+            db::add_transaction(&conn, source, 1.0, TransactionType::Debit, &Tag("other".into()), "2026-02-01").unwrap();
+        }
+
+        let mut app = app_for(&conn);
+        app.tags = vec![Tag("other".into()), Tag("subscriptions".into())];
+        app.toggle_mark_selected();
+        app.selected = 1;
+        app.toggle_mark_selected();
+
+        app.begin_retag();
+        assert_eq!(app.mode, Mode::Retag);
+        app.retag_index = 1;
+        app.apply_retag(&conn);
+
+        let retagged = app
+            .transactions
+            .iter()
+            .filter(|t| t.tag.as_str() == "subscriptions")
+            .count();
+        assert_eq!(retagged, 2);
+        assert!(app.selected_ids.is_empty());
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_history() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = app_for(&conn);
+        app.tags = vec![Tag("other".into()), Tag("food".into())];
+
+        app.form.source = "Lunch".into();
+        app.form.amount = "12".into();
+        app.form.date = "2026-02-01".into();
+        app.commit_form(&conn);
+
+        app.begin_edit_selected();
+        app.form.amount = "15".into();
+        app.commit_form(&conn);
+
+        app.retag_index = 1;
+        app.apply_retag(&conn);
+
+        let id = app.transactions[0].id;
+        app.delete_transaction(&conn, id);
+        assert!(app.transactions.is_empty());
+        assert_eq!(app.history.undo.len(), 4);
+
+        app.undo(&conn);
+        assert_eq!(app.transactions[0].id, id);
+        assert_eq!(app.transactions[0].tag.as_str(), "food");
+        app.undo(&conn);
+        assert_eq!(app.transactions[0].tag.as_str(), "other");
+        app.undo(&conn);
+        assert_eq!(app.transactions[0].amount, 12.0);
+        app.undo(&conn);
+        assert!(app.transactions.is_empty());
+        app.undo(&conn);
+        assert_eq!(app.history.redo.len(), 4);
+
+        app.redo(&conn);
+        app.redo(&conn);
+        assert_eq!(app.transactions[0].id, id);
+        assert_eq!(app.transactions[0].amount, 15.0);
+
+        // A new change forgets what was left to redo
+        app.retag_index = 1;
+        app.apply_retag(&conn);
+        assert!(app.history.redo.is_empty());
+    }
+
+    #[test]
+    fn undo_leaves_other_writes_and_deleted_rows_alone() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = base_app();
+        app.tags = vec![Tag("other".into()), Tag("food".into())];
+
+        app.form.source = "Lunch".into();
+        app.form.amount = "12".into();
+        app.form.date = "2026-02-01".into();
+        app.commit_form(&conn);
+        app.begin_edit_selected();
+        app.form.amount = "15".into();
+        app.commit_form(&conn);
+        app.retag_index = 1;
+        app.apply_retag(&conn);
+
+        // Clearing isn't recorded, so undoing the edit must keep it
+        let id = app.transactions[0].id;
+        db::set_cleared(&conn, &[id], true).unwrap();
+        app.undo(&conn);
+        app.undo(&conn);
+        assert_eq!(app.transactions[0].amount, 12.0);
+        assert_eq!(app.transactions[0].tag.as_str(), "other");
+        assert!(app.transactions[0].cleared);
+
+        // A row removed outside the history stays removed
+        db::delete_transaction(&conn, id).unwrap();
+        app.redo(&conn);
+        app.redo(&conn);
+        assert!(app.transactions.is_empty());
+    }
+
+    #[test]
+    fn undo_history_is_capped() {
+        let mut history = UndoHistory::default();
+        for i in 0..UNDO_LIMIT + 5 {
+            history.record(Change::Add(Transaction { id: i as i32, ..Default::default() }));
+        }
+
+        assert_eq!(history.undo.len(), UNDO_LIMIT);
+        assert!(matches!(&history.undo[0], Change::Add(tx) if tx.id == 5));
+    }
+
+    #[test]
+    fn scale_applies_to_filtered_rows_and_undoes() {
+        let conn = db::init_in_memory().unwrap();
+        db::add_transaction(&conn, "rent", 1000.0, TransactionType::Debit, &Tag("home".into()), "2026-02-01").unwrap();
+        db::add_transaction(&conn, "lunch", 10.0, TransactionType::Debit, &Tag("food".into()), "2026-02-02").unwrap();
+        db::add_transaction(&conn, "dinner", 20.0, TransactionType::Debit, &Tag("food".into()), "2026-02-03").unwrap();
+
+        let mut app = app_for(&conn);
+        app.tags = vec![Tag("home".into()), Tag("food".into())];
+        app.filter.tag_index = Some(1);
+        app.filter.active = true;
+
+        app.begin_scale();
+        assert_eq!(app.mode, Mode::ScaleAmounts);
+        app.scale_input = "abc".into();
+        app.confirm_scale();
+        assert!(matches!(app.popup, Some(PopupKind::Info { .. })));
+
+        app.scale_input = "10%".into();
+        app.confirm_scale();
+        let Some(PopupKind::Confirm { action: PopupAction::ScaleAmounts(ids, factor), .. }) = app.popup.clone() else {
+            panic!("expected a scale confirmation");
+        };
+        assert_eq!(ids.len(), 2);
+
+        app.close_popup();
+        app.scale_amounts(&conn, &ids, factor);
+        let amount = |app: &App, source: &str| app.transactions.iter().find(|t| t.source == source).unwrap().amount;
+        assert_eq!(amount(&app, "lunch"), 11.0);
+        assert_eq!(amount(&app, "dinner"), 22.0);
+        assert_eq!(amount(&app, "rent"), 1000.0);
+
+        app.undo(&conn);
+        assert_eq!(amount(&app, "lunch"), 10.0);
+        assert_eq!(amount(&app, "dinner"), 20.0);
+
+        // Redo writes only amounts, and only to rows that still exist
+        let lunch = app.transactions.iter().find(|t| t.source == "lunch").unwrap().id;
+        db::delete_transaction(&conn, lunch).unwrap();
+        app.redo(&conn);
+        assert_eq!(app.transactions.len(), 2);
+        assert_eq!(amount(&app, "dinner"), 22.0);
+    }
+
+    #[test]
+    fn source_report_lists_biggest_spend_first() {
+        let conn = db::init_in_memory().unwrap();
+        for (source, amount) in [("Cafe", 4.0), ("Landlord", 900.0), ("Cafe", 3.5)] {
+            db::add_transaction(&conn, source, amount, TransactionType::Debit, &Tag("other".into()), "2026-02-01").unwrap();
+        }
+
+        let mut app = app_for(&conn);
+        app.open_source_report(&conn);
+        let Some(PopupKind::Info { message, .. }) = &app.popup else {
+            panic!("expected the report popup");
+        };
+        let rows: Vec<&str> = message.lines().skip(4).collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("Landlord") && rows[0].contains("    1  "));
+        assert!(rows[1].starts_with("Cafe") && rows[1].contains("    2  "));
+    }
+
+    #[test]
+    fn destructive_confirms_use_the_danger_tone() {
+        let mut app = base_app();
+        app.open_confirm_popup("Confirm Delete", "Delete?".into(), PopupAction::DeleteTransaction(1));
+        assert_eq!(app.popup.as_ref().unwrap().tone(), PopupTone::Danger);
+
+        app.open_confirm_popup("Quit", "Quit?".into(), PopupAction::Quit);
+        assert_eq!(app.popup.as_ref().unwrap().tone(), PopupTone::Neutral);
+
+        app.open_info_popup("Export Complete", "Done".into());
+        assert_eq!(app.popup.as_ref().unwrap().tone(), PopupTone::Info);
+    }
+
+    #[test]
+    fn quick_add_repeats_the_last_added_row() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = app_for(&conn);
+
+        // Nothing to repeat yet: a blank form
+        app.begin_quick_add(&conn);
+        assert_eq!(app.mode, Mode::Adding);
+        assert!(app.form.source.is_empty());
+        assert_eq!(app.form.active, crate::form::Field::Source);
+
+        db::add_transaction(&conn, "Coffee", 3.5, TransactionType::Debit, &Tag("food".into()), "2026-01-10").unwrap();
+        db::add_transaction(&conn, "Rent", 900.0, TransactionType::Debit, &Tag("home".into()), "2025-12-01").unwrap();
+        app.refresh(&conn);
+        app.tags = vec![Tag("food".into()), Tag("home".into())];
+
+        app.begin_quick_add(&conn);
+        assert_eq!(app.mode, Mode::QuickAdd);
+        assert_eq!(app.form.source, "Rent");
+        assert_eq!(app.form.tag_index, 1);
+        assert!(app.form.amount.is_empty());
+
+        // Only the amount is typed; a bad one stays in the prompt
+        let press = |app: &mut App, key| crate::handlers::handle_key(app, key, &conn);
+        press(&mut app, crossterm::event::KeyCode::Enter);
+        assert_eq!(app.mode, Mode::QuickAdd);
+        assert!(app.form.error.is_some());
+
+        for c in "950".chars() {
+            press(&mut app, crossterm::event::KeyCode::Char(c));
+        }
+        press(&mut app, crossterm::event::KeyCode::Enter);
+        assert_eq!(app.mode, Mode::Normal);
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert!(app.transactions.iter().any(|t| t.source == "Rent" && t.amount == 950.0 && t.date == today));
+    }
+
+    #[test]
+    fn save_asks_before_inserting_duplicate() {
+        let conn = db::init_in_memory().unwrap();
+        db::add_transaction(&conn, "Coffee", 4.5, TransactionType::Debit, &Tag("food".into()), "2026-02-01").unwrap();
+
+        let mut app = app_for(&conn);
+        app.form.source = "Coffee".into();
+        app.form.amount = "4.50".into();
+        app.form.date = "2026-02-01".into();
+
+        assert!(!app.save_transaction(&conn));
+        assert_eq!(app.mode, Mode::Popup);
+        assert_eq!(app.transactions.len(), 1);
+
+        // Declining goes back to the form with what was typed
+        crate::handlers::handle_key(&mut app, crossterm::event::KeyCode::Char('n'), &conn);
+        assert_eq!(app.mode, Mode::Adding);
+        assert_eq!(app.form.source, "Coffee");
+
+        app.commit_form(&conn);
+        assert_eq!(app.transactions.len(), 2);
     }
-}
 
-// ---------------------------------------------------------------------------
-// tests for tab navigation helpers
-// ---------------------------------------------------------------------------
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn save_asks_before_editing_with_confirm_edit() {
+        let conn = db::init_in_memory().unwrap();
+        db::add_transaction(&conn, "Coffee", 4.5, TransactionType::Debit, &Tag("food".into()), "2026-02-01").unwrap();
 
-    fn base_app() -> App {
+        let mut app = app_for(&conn);
+        let id = app.transactions[0].id;
+        app.editing = Some(id);
+        app.form.source = "Tea".into();
+        app.form.amount = "3".into();
+        app.form.date = "2026-02-01".into();
+
+        app.confirm_edit = true;
+        assert!(!app.save_transaction(&conn));
+        assert!(matches!(app.popup, Some(PopupKind::Confirm { action: PopupAction::SaveEdit, .. })));
+        assert_eq!(app.transactions[0].source, "Coffee");
+
+        // New transactions are never asked about
+        app.close_popup();
+        app.editing = None;
+        app.form.source = "Juice".into();
+        assert!(app.save_transaction(&conn));
+        assert_eq!(app.transactions.len(), 2);
+    }
+
+    #[test]
+    fn a_prorated_rule_prorates_the_row_the_form_saves() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = base_app();
+        let today = chrono::Local::now().date_naive();
+        app.form.source = "gym".into();
+        app.form.amount = "300".into();
+        app.form.date = today.format("%Y-%m-%d").to_string();
+        app.form.recurring = true;
+        app.form.recurring_interval = RecurringInterval::Monthly;
+        app.form.prorate_first = true;
+
+        app.commit_form(&conn);
+        // The saved row already charged this month
+        db::insert_recurring_transactions(&conn).unwrap();
+        app.refresh(&conn);
+        assert_eq!(app.transactions.len(), 1);
+        assert_eq!(app.transactions[0].amount, db::prorated_amount(300.0, today));
+
+        let rule = &db::get_recurring_entries(&conn).unwrap()[0];
+        assert_eq!(rule.amount, 300.0);
+        assert!(rule.prorate_first);
+        assert_eq!(rule.last_inserted_date, today.format("%Y-%m").to_string());
+    }
+
+    #[test]
+    fn list_view_rebuilds_only_when_its_inputs_change() {
+        let conn = db::init_in_memory().unwrap();
+        db::add_transaction(&conn, "pay", 100.0, TransactionType::Credit, &Tag("salary".into()), "2026-02-01").unwrap();
+        db::add_transaction(&conn, "lunch", 10.0, TransactionType::Debit, &Tag("food".into()), "2026-02-01").unwrap();
+        db::add_transaction(&conn, "dinner", 20.0, TransactionType::Debit, &Tag("food".into()), "2026-02-02").unwrap();
+
+        let mut app = app_for(&conn);
+        app.sync_list_view();
+        assert_eq!(app.list_view.transactions.len(), 3);
+        assert_eq!(app.list_view.day_totals["2026-02-01"], crate::money::to_units(90.0));
+        // Newest first: a divider above the 2nd and the 1st
+        assert_eq!(app.list_view.grouped_rows, vec![1, 3, 4]);
+        let dinner = app.list_view.transactions[0].id;
+        assert_eq!(app.list_view.balances[&dinner], 70.0);
+        // Sized by the widest amount, wherever it is in the list
+        let pay = app.list_view.transactions.iter().find(|tx| tx.source == "pay").unwrap();
+        let widest = crate::ui::amount_text(pay, &app.money(), &app.theme.glyphs).chars().count();
+        assert_eq!(app.list_view.amount_width, widest);
+
+        // Untouched inputs keep the built view
+        app.transactions.clear();
+        app.sync_list_view();
+        assert_eq!(app.list_view.transactions.len(), 3);
+
+        // A reload, a filter or a sort change rebuilds it
+        app.refresh(&conn);
+        app.filter.search = "lunch".into();
+        app.filter.active = true;
+        app.sync_list_view();
+        assert_eq!(app.list_view.transactions.len(), 1);
+        assert!(app.list_view.amount_width < widest);
+
+        app.filter.clear();
+        app.sort_desc = false;
+        app.sync_list_view();
+        assert_eq!(app.list_view.transactions[2].source, "dinner");
+    }
+
+    #[test]
+    fn sort_by_amount_and_direction() {
+        let mut app = base_app();
+        let tx = |id: i32, amount: f64, date: &str| Transaction {
+            id,
+            source: format!("tx{}", id),
+            amount,
+            date: date.into(),
+            ..Default::default()
+        };
+        app.transactions = vec![
+            tx(1, 20.0, "2024-03-10"),
+            tx(2, 50.0, "2024-03-01"),
+            tx(3, 5.0, "2024-02-20"),
+        ];
+        app.sort_key = SortKey::Date;
+        app.sort_desc = true;
+
+        let ids = |app: &App| app.get_filtered_transactions().iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(&app), vec![1, 2, 3]);
+
+        app.cycle_sort_key();
+        assert_eq!(app.sort_key, SortKey::Amount);
+        assert_eq!(ids(&app), vec![2, 1, 3]);
+
+        app.toggle_sort_direction();
+        assert_eq!(ids(&app), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn sort_ties_break_by_date_then_id() {
+        let mut app = base_app();
+        let tx = |id: i32, source: &str, date: &str| Transaction {
+            id,
+            source: source.into(),
+            amount: 10.0,
+            date: date.into(),
+            ..Default::default()
+        };
+        app.transactions = vec![
+            tx(4, "rent", "2024-03-01"),
+            tx(3, "Coffee", "2024-03-01"),
+            tx(2, "coffee", "2024-03-05"),
+            tx(1, "coffee", "2024-03-01"),
+        ];
+        let ids = |app: &App| app.get_filtered_transactions().iter().map(|t| t.id).collect::<Vec<_>>();
+
+        app.sort_key = SortKey::Amount;
+        assert_eq!(ids(&app), vec![2, 4, 3, 1]);
+        app.toggle_sort_direction();
+        assert_eq!(ids(&app), vec![2, 4, 3, 1]);
+
+        app.sort_key = SortKey::Source;
+        app.sort_desc = false;
+        assert_eq!(ids(&app), vec![2, 3, 1, 4]);
+
+        app.ties_newest_first = false;
+        assert_eq!(ids(&app), vec![1, 3, 2, 4]);
+    }
+
+    #[test]
+    fn sort_key_round_trips_and_rejects_unknown() {
+        for key in [SortKey::Date, SortKey::Amount, SortKey::Source, SortKey::Tag] {
+            assert_eq!(key.as_str().parse(), Ok(key));
+        }
+        assert_eq!("colour".parse::<SortKey>(), Err(()));
+    }
+
+    #[test]
+    fn day_first_date_is_corrected_on_second_save() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = app_for(&conn);
+        app.form.source = "Lunch".into();
+        app.form.amount = "12".into();
+        app.form.date = "15/06/2024".into();
+
+        assert!(!app.save_transaction(&conn));
+        assert!(app.form.error.as_deref().unwrap().contains("2024-06-15"));
+        assert!(app.transactions.is_empty());
+
+        assert!(app.save_transaction(&conn));
+        assert_eq!(app.transactions[0].date, "2024-06-15");
+
+        // Slashes in year-first order are simply normalized
+        app.form.source = "Dinner".into();
+        app.form.amount = "20".into();
+        app.form.date = "2024/06/16".into();
+        assert!(app.save_transaction(&conn));
+        assert_eq!(app.transactions[0].date, "2024-06-16");
+    }
+
+    #[test]
+    fn missing_receipt_saves_with_warning() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = app_for(&conn);
+        app.form.source = "Lunch".into();
+        app.form.amount = "12".into();
+        app.form.receipt_path = "/definitely/not/here.jpg".into();
+
+        assert!(app.save_transaction(&conn));
+        assert_eq!(app.transactions[0].receipt_path, "/definitely/not/here.jpg");
+        assert!(matches!(app.popup, Some(PopupKind::Info { .. })));
+    }
+
+    #[test]
+    fn search_matches_note_hashtags() {
         let conn = db::init_in_memory().unwrap();
-        App::new(&conn)
+        let mut app = app_for(&conn);
+        for (source, note) in [
+            ("Pharmacy", "paid cash #Refundable, #health"),
+            ("Dentist", "#health checkup"),
+            ("Groceries", ""),
+        ] {
+            app.form.source = source.into();
+            app.form.amount = "10".into();
+            app.form.note = note.into();
+            assert!(app.save_transaction(&conn));
+        }
+
+        let sources = |app: &App| {
+            let mut s: Vec<String> =
+                app.get_filtered_transactions().into_iter().map(|t| t.source).collect();
+            s.sort();
+            s
+        };
+        app.filter.active = true;
+        app.filter.search = "#health".into();
+        assert_eq!(sources(&app), vec!["Dentist", "Pharmacy"]);
+        app.filter.search = "#health #refundable".into();
+        assert_eq!(sources(&app), vec!["Pharmacy"]);
+        app.filter.search = "#health checkup".into();
+        assert_eq!(sources(&app), vec!["Dentist"]);
+        app.filter.search = "#refund".into();
+        assert!(sources(&app).is_empty());
     }
 
     #[test]
@@ -424,6 +3377,7 @@ mod tests {
             kind: TransactionType::Debit,
             tag: Tag("food".into()),
             date: "2024-02-10".into(),
+            ..Default::default()
         };
         let tx2 = Transaction {
             id: 2,
@@ -432,6 +3386,7 @@ mod tests {
             kind: TransactionType::Credit,
             tag: Tag("salary".into()),
             date: "2024-02-15".into(),
+            ..Default::default()
         };
         let tx3 = Transaction {
             id: 3,
@@ -440,6 +3395,7 @@ mod tests {
             kind: TransactionType::Debit,
             tag: Tag("ops".into()),
             date: "2024-03-01".into(),
+            ..Default::default()
         };
         
         app.transactions = vec![tx1, tx2, tx3];
@@ -471,4 +3427,499 @@ mod tests {
         app.filter.active = false;
         assert_eq!(app.get_filtered_transactions().len(), 3);
     }
+
+    #[test]
+    fn popup_scroll_is_bounded_by_message() {
+        let mut app = base_app();
+        app.open_info_popup("Report", "one\ntwo\nthree".into());
+        assert_eq!(app.popup_scroll, 0);
+
+        app.scroll_popup_up();
+        assert_eq!(app.popup_scroll, 0);
+
+        for _ in 0..5 {
+            app.scroll_popup_down();
+        }
+        assert_eq!(app.popup_scroll, 2);
+
+        app.scroll_popup_up();
+        assert_eq!(app.popup_scroll, 1);
+
+        // Reopening resets the offset
+        app.open_info_popup("Report", "short".into());
+        assert_eq!(app.popup_scroll, 0);
+    }
+
+    #[test]
+    fn offered_file_belongs_to_its_popup() {
+        let mut app = base_app();
+        app.open_info_popup("Export Complete", "Exported".into());
+        app.popup_file = Some("export.csv".into());
+
+        // A later popup or closing this one drops the offer
+        app.open_info_popup("Other", "text".into());
+        assert!(app.popup_file.is_none());
+
+        app.popup_file = Some("export.csv".into());
+        app.close_popup();
+        assert!(app.popup_file.is_none());
+        app.open_popup_file();
+        assert!(app.popup.is_none());
+    }
+
+    #[test]
+    fn jump_to_other_kind_wraps_around() {
+        use crate::models::{Transaction, TransactionType, Tag};
+
+        let mut app = base_app();
+        let tx = |id: i32, kind: TransactionType| Transaction {
+            id,
+            source: format!("tx{}", id),
+            amount: 1.0,
+            kind,
+            tag: Tag("food".into()),
+            date: format!("2024-03-{:02}", 20 - id),
+            ..Default::default()
+        };
+        app.transactions = vec![
+            tx(1, TransactionType::Debit),
+            tx(2, TransactionType::Credit),
+            tx(3, TransactionType::Debit),
+            tx(4, TransactionType::Debit),
+        ];
+
+        app.selected = 2;
+        app.jump_to_other_kind(true);
+        assert_eq!(app.selected, 1);
+
+        // From the credit, forward finds the next debit
+        app.jump_to_other_kind(true);
+        assert_eq!(app.selected, 2);
+
+        app.selected = 0;
+        app.jump_to_other_kind(false);
+        assert_eq!(app.selected, 1);
+
+        // A view with a single kind leaves the selection alone
+        app.transactions.retain(|t| t.kind == TransactionType::Debit);
+        app.selected = 1;
+        app.jump_to_other_kind(true);
+        assert_eq!(app.selected, 1);
+    }
+
+    #[test]
+    fn jump_to_date_selects_on_or_before() {
+        use crate::models::{Transaction, TransactionType, Tag};
+
+        let mut app = base_app();
+        let tx = |id: i32, date: &str| Transaction {
+            id,
+            source: format!("tx{}", id),
+            amount: 1.0,
+            kind: TransactionType::Debit,
+            tag: Tag("food".into()),
+            date: date.into(),
+            ..Default::default()
+        };
+        // Newest first, like get_transactions
+        app.transactions = vec![
+            tx(1, "2024-03-10"),
+            tx(2, "2024-03-01"),
+            tx(3, "2024-02-20"),
+            tx(4, "2024-02-01"),
+        ];
+
+        app.jump_to_date("2024-03-01");
+        assert_eq!(app.selected, 1);
+
+        app.jump_to_date("2024-02-25");
+        assert_eq!(app.selected, 2);
+        assert!(app.popup.is_none());
+
+        // Before every transaction: closest is the oldest, with a notice
+        app.jump_to_date("2023-12-31");
+        assert_eq!(app.selected, 3);
+        assert!(app.popup.is_some());
+    }
+
+    #[test]
+    fn totals_use_base_currency() {
+        use crate::models::{Transaction, TransactionType};
+
+        let mut app = base_app();
+        app.exchange_rates.insert("INR".into(), 0.5);
+        app.transactions = vec![
+            Transaction { amount: 100.0, kind: TransactionType::Debit, currency: "INR".into(), ..Default::default() },
+            Transaction { amount: 10.0, kind: TransactionType::Debit, ..Default::default() },
+        ];
+
+        let base = app.transactions_in_base();
+        assert_eq!(crate::stats::calculate_spent(&base), 60.0);
+        assert!(base.iter().all(|tx| tx.currency.is_empty()));
+    }
+
+    #[test]
+    fn marked_totals_split_by_kind() {
+        use crate::models::Transaction;
+
+        let mut app = base_app();
+        app.transactions = vec![
+            Transaction { id: 1, amount: 20.0, kind: TransactionType::Debit, date: "2024-03-03".into(), ..Default::default() },
+            Transaction { id: 2, amount: 5.5, kind: TransactionType::Debit, date: "2024-03-02".into(), ..Default::default() },
+            Transaction { id: 3, amount: 100.0, kind: TransactionType::Credit, date: "2024-03-01".into(), ..Default::default() },
+        ];
+
+        app.selected = 0;
+        app.toggle_mark_selected();
+        app.selected = 2;
+        app.toggle_mark_selected();
+        assert_eq!(app.marked_totals(), (100.0, 20.0));
+
+        // Toggling again unmarks
+        app.toggle_mark_selected();
+        assert_eq!(app.marked_totals(), (0.0, 20.0));
+
+        app.clear_marks();
+        assert_eq!(app.marked_totals(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn large_debit_threshold() {
+        use crate::models::Transaction;
+
+        let mut app = base_app();
+        let big = Transaction { amount: 500.0, kind: TransactionType::Debit, ..Default::default() };
+        let small = Transaction { amount: 50.0, kind: TransactionType::Debit, ..Default::default() };
+        let income = Transaction { amount: 500.0, kind: TransactionType::Credit, ..Default::default() };
+
+        app.highlight_threshold = None;
+        assert!(!app.is_large_debit(&big));
+
+        app.highlight_threshold = Some(100.0);
+        assert!(app.is_large_debit(&big));
+        assert!(!app.is_large_debit(&small));
+        assert!(!app.is_large_debit(&income));
+    }
+
+    #[test]
+    fn budget_bands_follow_monthly_spend() {
+        let mut app = base_app();
+        let food = Tag::from_str("food");
+        let tx = |amount: f64, kind: TransactionType, tag: &Tag, date: &str| Transaction {
+            amount,
+            kind,
+            tag: tag.clone(),
+            date: date.into(),
+            ..Default::default()
+        };
+        app.transactions = vec![
+            tx(50.0, TransactionType::Debit, &food, "2026-02-03"),
+            tx(170.0, TransactionType::Debit, &food, "2026-03-02"),
+            tx(500.0, TransactionType::Credit, &food, "2026-03-05"),
+            tx(60.0, TransactionType::Debit, &food, "2026-04-09"),
+            tx(160.0, TransactionType::Debit, &food, "2026-04-10"),
+            tx(900.0, TransactionType::Debit, &Tag::from_str("rent"), "2026-04-01"),
+        ];
+        app.tag_budgets = HashMap::from([(food.clone(), 200.0)]);
+        app.budget_warning_percent = 80.0;
+
+        let bands = app.budget_bands();
+        let band = |month: &str| bands.get(&(month.to_string(), food.clone())).copied();
+        assert_eq!(band("2026-02"), Some(BudgetBand::Under));
+        assert_eq!(band("2026-03"), Some(BudgetBand::Near));
+        assert_eq!(band("2026-04"), Some(BudgetBand::Over));
+        // Tags without a budget aren't banded
+        assert_eq!(bands.len(), 3);
+    }
+
+    #[test]
+    fn refresh_marks_dirty() {
+        let conn = db::init_in_memory().unwrap();
+        let mut app = app_for(&conn);
+        app.dirty = false;
+
+        app.refresh(&conn);
+        assert!(app.dirty);
+    }
+
+    #[test]
+    fn recurring_preview_waits_for_confirm() {
+        use crate::models::{RecurringInterval, Tag, TransactionType};
+
+        let conn = db::init_in_memory().unwrap();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        db::add_recurring_entry(&conn, &RecurringEntry::new("rent", 500.0, TransactionType::Debit, Tag::from_str("bills"), RecurringInterval::Monthly, &today)).unwrap();
+
+        let mut app = app_for(&conn);
+        app.auto_confirm_recurring = false;
+        app.process_recurring(&conn);
+
+        // Nothing inserted yet, a confirm popup is showing instead
+        assert!(db::get_transactions(&conn).unwrap().is_empty());
+        assert!(matches!(
+            app.popup,
+            Some(PopupKind::Confirm { action: PopupAction::InsertRecurring, .. })
+        ));
+        // The due list gets the wide popup
+        assert_eq!(app.popup.as_ref().map(PopupKind::size), Some(PopupSize::Large));
+
+        app.auto_confirm_recurring = true;
+        app.close_popup();
+        app.process_recurring(&conn);
+        assert_eq!(app.transactions.len(), 1);
+    }
+
+    #[test]
+    fn broken_recurring_state_is_reported_before_inserting() {
+        use crate::models::{RecurringInterval, Tag, TransactionType};
+
+        let conn = db::init_in_memory().unwrap();
+        let id = db::add_recurring_entry(&conn, &RecurringEntry::new("rent", 500.0, TransactionType::Debit, Tag::from_str("bills"), RecurringInterval::Monthly, "2026-01-01")).unwrap();
+        conn.execute("UPDATE recurring_entries SET last_inserted_date = '2999-01' WHERE id = ?1", [id]).unwrap();
+
+        let mut app = app_for(&conn);
+        app.new_day(&conn);
+        match &app.popup {
+            Some(PopupKind::Confirm { action: PopupAction::ResetRecurringState(ids), message, .. }) => {
+                assert_eq!(ids, &[id as i32]);
+                assert!(message.contains("in the future"));
+            }
+            _ => panic!("expected the reset prompt"),
+        }
+
+        db::reset_recurring_state(&conn, &[id as i32]).unwrap();
+        app.close_popup();
+        assert!(!app.check_recurring_state(&conn));
+        assert!(db::get_recurring_entries(&conn).unwrap()[0].last_inserted_date.is_empty());
+    }
+
+    #[test]
+    fn editing_a_recurring_rule_updates_it_in_place() {
+        use crate::models::{RecurringInterval, Tag, TransactionType};
+
+        let conn = db::init_in_memory().unwrap();
+        db::add_recurring_entry(&conn, &RecurringEntry::new("rent", 500.0, TransactionType::Debit, Tag::from_str("bills"), RecurringInterval::Monthly, "2026-01-01")).unwrap();
+        let id = db::get_recurring_entries(&conn).unwrap()[0].id;
+        db::toggle_recurring_entry(&conn, id, false).unwrap();
+
+        let mut app = app_for(&conn);
+        app.begin_edit_recurring();
+        assert_eq!(app.mode, Mode::Adding);
+        assert_eq!(app.current_tab(), 2);
+        assert_eq!(app.form.source, "rent");
+
+        app.form.amount = "550".into();
+        app.form.recurring_interval = RecurringInterval::Weekly;
+        assert!(app.save_transaction(&conn));
+
+        // Same rule, new details, still paused, and no transaction added
+        let entries = db::get_recurring_entries(&conn).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].amount, 550.0);
+        assert_eq!(entries[0].interval, RecurringInterval::Weekly);
+        assert!(!entries[0].active);
+        assert!(app.transactions.is_empty());
+        assert_eq!(app.editing_recurring, None);
+    }
+
+    #[test]
+    fn large_batch_delete_needs_the_word_typed() {
+        use crate::handlers::handle_key;
+        use crate::models::{Tag, TransactionType};
+        use crossterm::event::KeyCode;
+
+        let conn = db::init_in_memory().unwrap();
+        for source in ["a", "b", "c"] {
+            db::add_transaction(&conn, source, 1.0, TransactionType::Debit, &Tag::from_str("food"), "2026-01-01").unwrap();
+        }
+        let mut app = base_app();
+        app.refresh(&conn);
+        app.selected_ids = app.transactions.iter().map(|t| t.id).collect();
+
+        // Below the threshold it's the usual y/n
+        app.typed_confirm_threshold = 4;
+        app.confirm_delete();
+        assert!(matches!(
+            app.popup,
+            Some(PopupKind::Confirm { action: PopupAction::DeleteTransactions(ref ids), .. }) if ids.len() == 3
+        ));
+        app.close_popup();
+
+        app.typed_confirm_threshold = 3;
+        app.confirm_delete();
+        assert!(matches!(app.popup, Some(PopupKind::TypedConfirm { .. })));
+
+        // y and a wrong word do nothing; the exact word deletes
+        for key in [KeyCode::Char('y'), KeyCode::Enter, KeyCode::Backspace] {
+            handle_key(&mut app, key, &conn);
+        }
+        for c in "DELETEX".chars() {
+            handle_key(&mut app, KeyCode::Char(c), &conn);
+        }
+        handle_key(&mut app, KeyCode::Enter, &conn);
+        assert_eq!(app.transactions.len(), 3);
+
+        handle_key(&mut app, KeyCode::Backspace, &conn);
+        handle_key(&mut app, KeyCode::Enter, &conn);
+        assert!(app.popup.is_none());
+        assert!(app.transactions.is_empty());
+        assert!(app.selected_ids.is_empty());
+    }
+
+    #[test]
+    fn single_delete_popup_formats_the_amount_like_the_list() {
+        let mut app = base_app();
+        app.transactions = vec![Transaction {
+            id: 1,
+            source: "Hotel".into(),
+            amount: 1234.5,
+            currency: "EUR".into(),
+            ..Default::default()
+        }];
+
+        app.confirm_delete();
+        assert!(app.popup.as_ref().unwrap().message().contains("(EUR 1234.50)"));
+
+        app.privacy = true;
+        app.confirm_delete();
+        assert!(!app.popup.as_ref().unwrap().message().contains("1234"));
+    }
+
+    #[test]
+    fn q_is_typed_into_search_instead_of_quitting() {
+        use crate::handlers::handle_key;
+        use crossterm::event::KeyCode;
+
+        let conn = db::init_in_memory().unwrap();
+        let mut app = base_app();
+        app.begin_search();
+
+        for c in "qantas".chars() {
+            assert!(!handle_key(&mut app, KeyCode::Char(c), &conn));
+        }
+        assert_eq!(app.mode, Mode::Search);
+        assert_eq!(app.filter.search, "qantas");
+
+        handle_key(&mut app, KeyCode::Enter, &conn);
+        assert!(handle_key(&mut app, KeyCode::Char('q'), &conn));
+    }
+
+    #[test]
+    fn deleting_a_recurring_row_offers_to_pause_the_rule() {
+        use crate::models::{RecurringInterval, Tag, TransactionType};
+
+        let conn = db::init_in_memory().unwrap();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        db::add_recurring_entry(&conn, &RecurringEntry::new("rent", 500.0, TransactionType::Debit, Tag::from_str("bills"), RecurringInterval::Monthly, &today)).unwrap();
+        db::insert_recurring_transactions(&conn).unwrap();
+        db::add_transaction(&conn, "coffee", 3.0, TransactionType::Debit, &Tag::from_str("food"), &today).unwrap();
+
+        let mut app = app_for(&conn);
+        let find = |app: &App, source: &str| app.transactions.iter().find(|t| t.source == source).unwrap().id;
+
+        // A hand-entered row is just deleted
+        app.delete_transaction(&conn, find(&app, "coffee"));
+        assert!(app.popup.is_none());
+
+        app.delete_transaction(&conn, find(&app, "rent"));
+        assert!(app.transactions.is_empty());
+        let Some(PopupKind::Confirm { action: PopupAction::PauseRecurring(rec_id), .. }) = app.popup else {
+            panic!("expected a pause prompt");
+        };
+        assert_eq!(rec_id, app.recurring_entries[0].id);
+    }
+
+    #[test]
+    fn recurring_sort_cycles_through_orders() {
+        use crate::models::{RecurringInterval, Tag, TransactionType};
+
+        let conn = db::init_in_memory().unwrap();
+        for (source, amount) in [("rent", 500.0), ("Gym", 40.0), ("netflix", 15.0)] {
+            db::add_recurring_entry(&conn, &RecurringEntry::new(source, amount, TransactionType::Debit, Tag::from_str("bills"), RecurringInterval::Monthly, "2026-01-01")).unwrap();
+        }
+
+        let mut app = app_for(&conn);
+        app.recurring_sort = RecurringSort::Newest;
+        app.refresh(&conn);
+        let sources = |app: &App| app.recurring_entries.iter().map(|r| r.source.clone()).collect::<Vec<_>>();
+        assert_eq!(sources(&app), ["netflix", "Gym", "rent"]);
+
+        app.cycle_recurring_sort();
+        assert_eq!(app.recurring_sort, RecurringSort::Source);
+        assert_eq!(sources(&app), ["Gym", "netflix", "rent"]);
+
+        app.cycle_recurring_sort();
+        assert_eq!(sources(&app), ["rent", "Gym", "netflix"]);
+
+        // Survives a reload from the database
+        app.refresh(&conn);
+        assert_eq!(sources(&app), ["rent", "Gym", "netflix"]);
+
+        app.cycle_recurring_sort();
+        assert_eq!(app.recurring_sort, RecurringSort::Newest);
+    }
+
+    #[test]
+    fn idle_timeout_wakes_after_midnight() {
+        let mut app = base_app();
+        let evening = chrono::NaiveDate::from_ymd_opt(2026, 3, 10)
+            .unwrap()
+            .and_hms_opt(23, 59, 0)
+            .unwrap();
+
+        app.poll_interval_ms = 0;
+        assert_eq!(app.idle_timeout(evening), std::time::Duration::from_secs(61));
+
+        app.poll_interval_ms = 250;
+        assert_eq!(app.idle_timeout(evening), std::time::Duration::from_millis(250));
+    }
+
+    #[test]
+    fn balance_preview_replaces_the_edited_row() {
+        let conn = db::init_in_memory().unwrap();
+        db::add_transaction(&conn, "pay", 100.0, TransactionType::Credit, &Tag::from_str("salary"), "2026-02-01").unwrap();
+        db::add_transaction(&conn, "rent", 40.0, TransactionType::Debit, &Tag::from_str("bills"), "2026-02-02").unwrap();
+        let mut app = app_for(&conn);
+        app.opening_balance = 0.0;
+
+        // Not a number yet
+        app.form.amount = "-".into();
+        assert_eq!(app.balance_preview(), None);
+
+        // A new expense comes off the current balance of 60
+        app.form.amount = "15".into();
+        app.form.kind = TransactionType::Debit;
+        assert_eq!(app.balance_preview(), Some(45.0));
+
+        // Editing rent down to 15 gives 25 back
+        app.editing = app.transactions.iter().find(|t| t.source == "rent").map(|t| t.id);
+        assert_eq!(app.balance_preview(), Some(85.0));
+    }
+
+    #[test]
+    fn date_groups_need_date_order() {
+        let mut app = base_app();
+        app.group_by_date = true;
+        assert!(app.shows_date_groups());
+
+        app.cycle_sort_key();
+        assert!(!app.shows_date_groups());
+
+        app.sort_key = SortKey::Date;
+        app.toggle_date_groups();
+        assert!(!app.shows_date_groups());
+    }
+
+    #[test]
+    fn confirm_popup_default_choice() {
+        let mut app = base_app();
+        app.open_confirm_popup("Confirm Delete", "Delete?".into(), PopupAction::DeleteTransaction(1));
+        assert_eq!(app.popup_choice, ConfirmChoice::No);
+
+        app.open_confirm_popup("Quit", "Quit?".into(), PopupAction::Quit);
+        assert_eq!(app.popup_choice, ConfirmChoice::Yes);
+
+        app.popup_choice = app.popup_choice.toggle();
+        assert_eq!(app.popup_choice, ConfirmChoice::No);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,388 @@
+use std::io;
+
+use rusqlite::Connection;
+
+use crate::{
+    app::build_tag_list,
+    config::{load_config, Config},
+    db,
+    form::TransactionForm,
+    models::{Tag, Transaction, TransactionType},
+    money::Money,
+    stats,
+};
+
+pub const USAGE: &str = "Usage:
+  fitui                     start the interface
+  fitui --read-only         start the interface with editing disabled
+  fitui --profile NAME ...  use NAME's own database and config with any of these
+  fitui --balance [--json]  print earned, spent and balance, then exit
+  fitui add --source NAME --amount N [--kind debit|credit] [--tag TAG] [--date DATE]
+            [--currency CODE]
+                            record a transaction, then exit";
+
+/// Something to do from the command line instead of starting the TUI.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Balance { json: bool },
+    Add(AddArgs),
+    // Starts the interface rather than replacing it; main checks for it
+    ReadOnly,
+}
+
+/// Fields for `fitui add`, as typed. Checked against the config in `run`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AddArgs {
+    pub source: String,
+    pub amount: String,
+    pub kind: Option<String>,
+    pub tag: Option<String>,
+    pub date: Option<String>,
+    pub currency: Option<String>,
+}
+
+/// Remove `--profile NAME` from the arguments, wherever it appears, and
+/// return the name. Names are kept to letters, digits, `-` and `_` since
+/// they become part of file names.
+pub fn take_profile(args: &mut Vec<String>) -> Result<Option<String>, String> {
+    let Some(pos) = args.iter().position(|a| a == "--profile") else {
+        return Ok(None);
+    };
+    if args.get(pos + 1).is_none_or(|next| next.starts_with('-')) {
+        return Err("--profile needs a name".into());
+    }
+    let name = args.remove(pos + 1);
+    args.remove(pos);
+
+    let valid = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if name.is_empty() || !valid {
+        return Err(format!("profile names use letters, digits, - and _ only, not '{}'", name));
+    }
+    Ok(Some(name))
+}
+
+/// Parse the arguments after the program name. `Ok(None)` means no
+/// arguments, i.e. start the TUI as usual.
+pub fn parse_args(args: &[String]) -> Result<Option<Command>, String> {
+    let Some(first) = args.first() else {
+        return Ok(None);
+    };
+
+    match first.as_str() {
+        "--balance" => {
+            let mut json = false;
+            for arg in &args[1..] {
+                match arg.as_str() {
+                    "--json" => json = true,
+                    other => return Err(format!("unexpected argument '{}'", other)),
+                }
+            }
+            Ok(Some(Command::Balance { json }))
+        }
+        "add" => parse_add(&args[1..]).map(|add| Some(Command::Add(add))),
+        "--read-only" => match args.get(1) {
+            Some(other) => Err(format!("unexpected argument '{}'", other)),
+            None => Ok(Some(Command::ReadOnly)),
+        },
+        other => Err(format!("unknown argument '{}'", other)),
+    }
+}
+
+fn parse_add(args: &[String]) -> Result<AddArgs, String> {
+    let mut add = AddArgs::default();
+    let mut iter = args.iter();
+
+    while let Some(flag) = iter.next() {
+        let value = iter
+            .next()
+            .ok_or_else(|| format!("{} needs a value", flag))?
+            .clone();
+        match flag.as_str() {
+            "--source" => add.source = value,
+            "--amount" => add.amount = value,
+            "--kind" => add.kind = Some(value),
+            "--tag" => add.tag = Some(value),
+            "--date" => add.date = Some(value),
+            "--currency" => add.currency = Some(value),
+            other => return Err(format!("unexpected argument '{}'", other)),
+        }
+    }
+
+    if add.amount.is_empty() {
+        return Err("add needs --amount".into());
+    }
+    Ok(add)
+}
+
+/// Check `add` against the same rules as the form and build the row to
+/// insert.
+pub fn build_transaction(add: &AddArgs, config: &Config, today: chrono::NaiveDate) -> Result<Transaction, String> {
+    let mut form = TransactionForm::new();
+    form.source = add.source.clone();
+    form.amount = add.amount.clone();
+    form.date = add.date.clone().unwrap_or_else(|| today.format("%Y-%m-%d").to_string());
+    if let Some(expanded) = crate::form::expand_relative_date(&form.date, today)
+        .or_else(|| crate::form::correct_date_format(&form.date))
+    {
+        form.date = expanded;
+    }
+    form.validate()?;
+    let currency = add.currency.as_deref().unwrap_or("").trim().to_string();
+    crate::form::validate_currency(&currency, &config.currency, &config.exchange_rates)?;
+
+    let amount: f64 = form
+        .amount
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' isn't a number", form.amount))?;
+    // A negative amount is an expense, same as in the form
+    let kind = match add.kind.as_deref().map(str::to_lowercase).as_deref() {
+        None if amount < 0.0 => TransactionType::Debit,
+        None | Some("debit") => TransactionType::Debit,
+        Some("credit") if amount < 0.0 => {
+            return Err("a negative amount can't be a credit".into());
+        }
+        Some("credit") => TransactionType::Credit,
+        Some(other) => return Err(format!("kind must be debit or credit, not '{}'", other)),
+    };
+
+    let fallback = Tag::parse_or(&config.fallback_tag, "other");
+    let tags = build_tag_list(&config.tags, &fallback);
+    let tag = Tag::parse_or(add.tag.as_deref().unwrap_or(""), fallback.as_str());
+    if !tags.contains(&tag) {
+        let names: Vec<&str> = tags.iter().map(|t| t.as_str()).collect();
+        return Err(format!("unknown tag '{}' (configured: {})", tag.as_str(), names.join(", ")));
+    }
+
+    Ok(Transaction {
+        source: form.source.trim().to_string(),
+        amount: amount.abs(),
+        kind,
+        tag,
+        date: form.date,
+        currency,
+        ..Default::default()
+    })
+}
+
+pub fn run(command: Command, conn: &Connection, profile: Option<&str>) -> io::Result<()> {
+    let config = load_config(profile);
+
+    match command {
+        Command::Balance { json } => {
+            let transactions = db::get_transactions(conn).unwrap_or_default();
+            let totals = Totals::new(&transactions, &config);
+            if json {
+                println!("{}", totals.to_json(&money(&config)));
+            } else {
+                print!("{}", totals.to_text(&money(&config)));
+            }
+        }
+        Command::Add(add) => {
+            let today = chrono::Local::now().date_naive();
+            let tx = match build_transaction(&add, &config, today) {
+                Ok(tx) => tx,
+                Err(err) => {
+                    eprintln!("fitui add: {}", err);
+                    std::process::exit(2);
+                }
+            };
+
+            db::insert_transaction(conn, &tx).map_err(io::Error::other)?;
+            println!("Added: {}", money(&config).describe(&tx));
+        }
+        Command::ReadOnly => {}
+    }
+
+    Ok(())
+}
+
+fn money(config: &Config) -> Money {
+    Money {
+        decimals: config.decimal_places,
+        rounding: config.rounding(),
+        ..Money::new(&config.currency)
+    }
+}
+
+/// Headline figures in the base currency, matching the header in the TUI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Totals {
+    pub earned: f64,
+    pub spent: f64,
+    pub balance: f64,
+}
+
+impl Totals {
+    pub fn new(transactions: &[Transaction], config: &Config) -> Self {
+        let in_base: Vec<Transaction> = transactions
+            .iter()
+            .map(|tx| Transaction {
+                amount: tx.base_amount(&config.exchange_rates),
+                ..tx.clone()
+            })
+            .collect();
+
+        let earned = stats::calculate_earned(&in_base);
+        let spent = stats::calculate_spent(&in_base);
+
+        Self {
+            earned,
+            spent,
+            balance: config.opening_balance + earned - spent,
+        }
+    }
+
+    fn to_text(self, money: &Money) -> String {
+        format!(
+            "Earned:  {}\nSpent:   {}\nBalance: {}\n",
+            money.format(self.earned),
+            money.format(self.spent),
+            money.format(self.balance)
+        )
+    }
+
+    fn to_json(self, money: &Money) -> String {
+        format!(
+            "{{\"currency\":\"{}\",\"earned\":{},\"spent\":{},\"balance\":{}}}",
+            json_escape(&money.symbol),
+            money.number(self.earned),
+            money.number(self.spent),
+            money.number(self.balance)
+        )
+    }
+}
+
+fn json_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_balance_flags() {
+        assert_eq!(parse_args(&[]), Ok(None));
+        assert_eq!(
+            parse_args(&args(&["--balance"])),
+            Ok(Some(Command::Balance { json: false }))
+        );
+        assert_eq!(
+            parse_args(&args(&["--balance", "--json"])),
+            Ok(Some(Command::Balance { json: true }))
+        );
+        assert_eq!(parse_args(&args(&["--read-only"])), Ok(Some(Command::ReadOnly)));
+        assert!(parse_args(&args(&["--read-only", "--json"])).is_err());
+        assert!(parse_args(&args(&["--bogus"])).is_err());
+        assert!(parse_args(&args(&["--balance", "--xml"])).is_err());
+    }
+
+    #[test]
+    fn profile_is_taken_from_anywhere() {
+        let mut argv = args(&["--balance", "--profile", "freelance", "--json"]);
+        assert_eq!(take_profile(&mut argv), Ok(Some("freelance".into())));
+        assert_eq!(parse_args(&argv), Ok(Some(Command::Balance { json: true })));
+
+        let mut argv = args(&["--read-only"]);
+        assert_eq!(take_profile(&mut argv), Ok(None));
+        assert!(take_profile(&mut args(&["--profile"])).is_err());
+        assert!(take_profile(&mut args(&["--profile", "--read-only"])).is_err());
+        assert!(take_profile(&mut args(&["--profile", "../home"])).is_err());
+    }
+
+    #[test]
+    fn add_builds_a_checked_transaction() {
+        let config = Config::default();
+        let today = NaiveDate::from_ymd_opt(2024, 6, 10).unwrap();
+        let parsed = parse_args(&args(&[
+            "add", "--source", "Groceries", "--amount", "12.5", "--tag", "food", "--date", "yesterday",
+        ]))
+        .unwrap();
+        let Some(Command::Add(add)) = parsed else {
+            panic!("expected an add command");
+        };
+
+        let tx = build_transaction(&add, &config, today).unwrap();
+        assert_eq!(tx.source, "Groceries");
+        assert_eq!(tx.amount, 12.5);
+        assert_eq!(tx.kind, TransactionType::Debit);
+        assert_eq!(tx.tag, Tag::from_str("food"));
+        assert_eq!(tx.date, "2024-06-09");
+        assert_eq!(Money::new("$").describe(&tx), "2024-06-09  Groceries  -$12.50  #food");
+
+        // No tag means the fallback tag
+        let untagged = AddArgs { tag: None, ..add.clone() };
+        assert_eq!(build_transaction(&untagged, &config, today).unwrap().tag, Tag::from_str("other"));
+    }
+
+    #[test]
+    fn add_rejects_bad_input() {
+        let config = Config::default();
+        let today = NaiveDate::from_ymd_opt(2024, 6, 10).unwrap();
+        let good = AddArgs {
+            source: "Pay".into(),
+            amount: "100".into(),
+            kind: Some("credit".into()),
+            ..Default::default()
+        };
+        assert!(build_transaction(&good, &config, today).is_ok());
+
+        // A currency is fine once it has a rate
+        let with_rate = Config { exchange_rates: [("EUR".to_string(), 1.1)].into_iter().collect(), ..Config::default() };
+        let euros = AddArgs { currency: Some("EUR".into()), ..good.clone() };
+        assert_eq!(build_transaction(&euros, &with_rate, today).unwrap().currency, "EUR");
+
+        let bad = [
+            AddArgs { source: "  ".into(), ..good.clone() },
+            AddArgs { amount: "ten".into(), ..good.clone() },
+            AddArgs { amount: "-5".into(), ..good.clone() },
+            AddArgs { kind: Some("transfer".into()), ..good.clone() },
+            AddArgs { tag: Some("nope".into()), ..good.clone() },
+            AddArgs { date: Some("06/01/2024".into()), ..good.clone() },
+            AddArgs { currency: Some("EUR".into()), ..good.clone() },
+        ];
+        for add in bad {
+            assert!(build_transaction(&add, &config, today).is_err(), "{:?}", add);
+        }
+
+        assert!(parse_args(&args(&["add", "--source", "x"])).is_err());
+        assert!(parse_args(&args(&["add", "--amount"])).is_err());
+    }
+
+    #[test]
+    fn totals_include_opening_balance_and_rates() {
+        let config = Config {
+            opening_balance: 100.0,
+            exchange_rates: [("EUR".to_string(), 2.0)].into_iter().collect(),
+            ..Default::default()
+        };
+        let transactions = vec![
+            Transaction {
+                amount: 50.0,
+                kind: TransactionType::Credit,
+                tag: Tag::from_str("salary"),
+                currency: "EUR".into(),
+                ..Default::default()
+            },
+            Transaction {
+                amount: 30.0,
+                kind: TransactionType::Debit,
+                tag: Tag::from_str("food"),
+                ..Default::default()
+            },
+        ];
+
+        let totals = Totals::new(&transactions, &config);
+        assert_eq!(totals, Totals { earned: 100.0, spent: 30.0, balance: 170.0 });
+        assert_eq!(
+            totals.to_json(&Money::new("$")),
+            "{\"currency\":\"$\",\"earned\":100.00,\"spent\":30.00,\"balance\":170.00}"
+        );
+    }
+}
@@ -1,7 +1,10 @@
+use chrono::Weekday;
 use serde::{Deserialize, Serialize};
 use directories::ProjectDirs;
 use std::{fs, path::PathBuf};
 
+use crate::locale::Locale;
+use crate::money::Rounding;
 use crate::theme::ThemeConfig;
 use std::collections::HashMap;
 
@@ -14,6 +17,289 @@ pub struct Config {
     pub theme: String,
     #[serde(default)]
     pub custom_themes: HashMap<String, ThemeConfig>,
+    // Longest the UI sleeps without input, in ms; 0 waits for input (or
+    // midnight, for recurring entries)
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    #[serde(default)]
+    pub auto_confirm_recurring: bool,
+    // Ask before saving changes to an existing transaction
+    #[serde(default)]
+    pub confirm_edit: bool,
+    // Units of the base `currency` per one unit of each listed currency
+    #[serde(default)]
+    pub exchange_rates: HashMap<String, f64>,
+    // Debits above this amount (in the base currency) are highlighted
+    #[serde(default)]
+    pub highlight_threshold: Option<f64>,
+    // Monthly spending budget per tag, in the base currency
+    #[serde(default)]
+    pub tag_budgets: HashMap<String, f64>,
+    // Percentage of a tag's budget after which its amounts turn amber
+    #[serde(default = "default_budget_warning_percent")]
+    pub budget_warning_percent: f64,
+    // The balance is flagged once it drops below this (base currency)
+    #[serde(default)]
+    pub low_balance_warning: Option<f64>,
+    // First day of the week for weekly stats, e.g. "monday" or "sunday"
+    #[serde(default = "default_week_start")]
+    pub week_start: String,
+    // Order of rows that tie on the sort column: "newest" or "oldest" first
+    #[serde(default = "default_sort_tie_breaker")]
+    pub sort_tie_breaker: String,
+    // View shown on startup: "normal" (transaction list) or "stats"
+    #[serde(default = "default_startup_mode")]
+    pub startup_mode: String,
+    // Savings rate percentages for the Stats view: below the low one is
+    // flagged, at or above the healthy one is shown as good
+    #[serde(default)]
+    pub savings_rate_low: f64,
+    #[serde(default = "default_savings_rate_healthy")]
+    pub savings_rate_healthy: f64,
+    // How many months the Stats view charts and lists (1–60)
+    #[serde(default = "default_stats_months")]
+    pub stats_months: usize,
+    // Balance before the first recorded transaction, in the base currency
+    #[serde(default)]
+    pub opening_balance: f64,
+    // Digits shown after the decimal point (0–4), e.g. 0 for JPY
+    #[serde(default = "default_decimal_places")]
+    pub decimal_places: usize,
+    // How displayed amounts round ties: "half_up" or "half_even"
+    #[serde(default = "default_rounding")]
+    pub rounding: String,
+    // Language for month names in charts and dates, e.g. "de" or "pt_BR"
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    // Catch-all tag used when no other tag applies
+    #[serde(default = "default_fallback_tag")]
+    pub fallback_tag: String,
+    // Tag pre-selected in the form for new transactions; the first tag if unset
+    #[serde(default)]
+    pub default_tag: Option<String>,
+    // false draws inline in the normal screen buffer (also FITUI_NO_ALT_SCREEN=1)
+    #[serde(default = "default_alternate_screen")]
+    pub alternate_screen: bool,
+    // How much ↑/↓ and PgUp/PgDn change the Amount field in the form
+    #[serde(default = "default_amount_step")]
+    pub amount_step: f64,
+    #[serde(default = "default_amount_step_large")]
+    pub amount_step_large: f64,
+    // Deleting this many marked rows or more asks for `typed_confirm_word`
+    // to be typed instead of y/n; 0 never asks
+    #[serde(default)]
+    pub typed_confirm_threshold: usize,
+    #[serde(default = "default_typed_confirm_word")]
+    pub typed_confirm_word: String,
+    // Marker drawn before the selected row; may be empty
+    #[serde(default = "default_highlight_symbol")]
+    pub highlight_symbol: String,
+    // Title of the transaction list, before any filter or sort indicators
+    #[serde(default = "default_list_title")]
+    pub list_title: String,
+    // Shown in place of "No transactions yet. Press a to add one."
+    #[serde(default)]
+    pub empty_message: Option<String>,
+    // Plain ASCII instead of emoji, arrows and box drawing
+    #[serde(default)]
+    pub ascii_mode: bool,
+    // Show recent dates as "today", "yesterday" or "3d ago"
+    #[serde(default)]
+    pub relative_dates: bool,
+    // Shade every other transaction row with the theme's row_alt color
+    #[serde(default = "default_stripe_rows")]
+    pub stripe_rows: bool,
+    // Transaction list columns, left to right, with percentage widths
+    #[serde(default = "default_columns")]
+    pub columns: Vec<ColumnConfig>,
+    // Summary panels above the list, left to right
+    #[serde(default = "default_header_panels")]
+    pub header_panels: Vec<String>,
+    // Savings goals tracked in the Stats view
+    #[serde(default)]
+    pub goals: Vec<GoalConfig>,
+    // Named filters applied with the number keys 1-9, in order
+    #[serde(default)]
+    pub filter_presets: Vec<FilterPresetConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ColumnConfig {
+    pub name: String,
+    pub width: u16,
+}
+
+/// A savings goal. Progress is the overall balance, or with `tag` the net
+/// amount recorded under that tag (e.g. transfers tagged "savings").
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct GoalConfig {
+    pub name: String,
+    pub amount: f64,
+    // YYYY-MM-DD
+    #[serde(default)]
+    pub target_date: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// A named filter for the number keys. `range` is worked out on the day
+/// it's applied ("today", "this_week", "this_month", "last_30_days" or
+/// "this_year"); otherwise `start_date`/`end_date` are used as given.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct FilterPresetConfig {
+    pub name: String,
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub range: Option<String>,
+    // YYYY-MM-DD
+    #[serde(default)]
+    pub start_date: Option<String>,
+    #[serde(default)]
+    pub end_date: Option<String>,
+    #[serde(default)]
+    pub search: Option<String>,
+}
+
+// Relative ranges a filter preset can name
+pub const PRESET_RANGES: &[&str] = &["today", "this_week", "this_month", "last_30_days", "this_year"];
+
+// One preset per number key
+pub const MAX_FILTER_PRESETS: usize = 9;
+
+/// A column of the main transaction list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListColumn {
+    Date,
+    Source,
+    Amount,
+    Balance,
+    Recur,
+    Tag,
+}
+
+impl ListColumn {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ListColumn::Date => "date",
+            ListColumn::Source => "source",
+            ListColumn::Amount => "amount",
+            ListColumn::Balance => "balance",
+            ListColumn::Recur => "recur",
+            ListColumn::Tag => "tag",
+        }
+    }
+}
+
+impl std::str::FromStr for ListColumn {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "date" => Ok(ListColumn::Date),
+            "source" => Ok(ListColumn::Source),
+            "amount" => Ok(ListColumn::Amount),
+            "balance" => Ok(ListColumn::Balance),
+            "recur" => Ok(ListColumn::Recur),
+            "tag" => Ok(ListColumn::Tag),
+            _ => Err(()),
+        }
+    }
+}
+
+fn default_columns() -> Vec<ColumnConfig> {
+    [
+        (ListColumn::Source, 32),
+        (ListColumn::Amount, 14),
+        (ListColumn::Balance, 14),
+        (ListColumn::Recur, 10),
+        (ListColumn::Tag, 22),
+    ]
+    .into_iter()
+    .map(|(column, width)| ColumnConfig {
+        name: column.as_str().to_string(),
+        width,
+    })
+    .collect()
+}
+
+/// The configured columns with their widths, or the default layout when
+/// the list names an unknown or repeated column, has a zero width, or the
+/// widths add up to more than 100%.
+pub fn list_columns(columns: &[ColumnConfig]) -> Vec<(ListColumn, u16)> {
+    let mut parsed: Vec<(ListColumn, u16)> = Vec::new();
+    for column in columns {
+        match column.name.parse::<ListColumn>() {
+            Ok(c) if column.width > 0 && !parsed.iter().any(|(p, _)| *p == c) => {
+                parsed.push((c, column.width));
+            }
+            _ => return list_columns(&default_columns()),
+        }
+    }
+
+    let total: u16 = parsed.iter().map(|(_, w)| *w).sum();
+    if parsed.is_empty() || total > 100 {
+        return list_columns(&default_columns());
+    }
+    parsed
+}
+
+/// A summary panel in the header above the transaction list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderPanel {
+    Earned,
+    Balance,
+    Spent,
+    SavingsRate,
+}
+
+impl HeaderPanel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HeaderPanel::Earned => "earned",
+            HeaderPanel::Balance => "balance",
+            HeaderPanel::Spent => "spent",
+            HeaderPanel::SavingsRate => "savings_rate",
+        }
+    }
+}
+
+impl std::str::FromStr for HeaderPanel {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "earned" => Ok(HeaderPanel::Earned),
+            "balance" => Ok(HeaderPanel::Balance),
+            "spent" => Ok(HeaderPanel::Spent),
+            "savings_rate" => Ok(HeaderPanel::SavingsRate),
+            _ => Err(()),
+        }
+    }
+}
+
+fn default_header_panels() -> Vec<String> {
+    [HeaderPanel::Earned, HeaderPanel::Balance, HeaderPanel::Spent]
+        .iter()
+        .map(|panel| panel.as_str().to_string())
+        .collect()
+}
+
+/// The configured header panels, or earned/balance/spent when the list is
+/// empty or names an unknown or repeated panel.
+pub fn header_panels(names: &[String]) -> Vec<HeaderPanel> {
+    let mut parsed: Vec<HeaderPanel> = Vec::new();
+    for name in names {
+        match name.parse::<HeaderPanel>() {
+            Ok(panel) if !parsed.contains(&panel) => parsed.push(panel),
+            _ => return header_panels(&default_header_panels()),
+        }
+    }
+
+    if parsed.is_empty() {
+        return header_panels(&default_header_panels());
+    }
+    parsed
 }
 
 fn default_currency() -> String {
@@ -24,6 +310,204 @@ fn default_theme_name() -> String {
     "default".to_string()
 }
 
+fn default_poll_interval_ms() -> u64 {
+    0
+}
+
+fn default_stats_months() -> usize {
+    6
+}
+
+fn default_sort_tie_breaker() -> String {
+    "newest".to_string()
+}
+
+fn default_startup_mode() -> String {
+    "normal".to_string()
+}
+
+fn default_week_start() -> String {
+    "monday".to_string()
+}
+
+fn default_savings_rate_healthy() -> f64 {
+    20.0
+}
+
+fn default_decimal_places() -> usize {
+    2
+}
+
+fn default_budget_warning_percent() -> f64 {
+    80.0
+}
+
+fn default_rounding() -> String {
+    "half_up".to_string()
+}
+
+fn default_locale() -> String {
+    "en".to_string()
+}
+
+fn default_amount_step() -> f64 {
+    1.0
+}
+
+fn default_amount_step_large() -> f64 {
+    10.0
+}
+
+fn default_typed_confirm_word() -> String {
+    "DELETE".to_string()
+}
+
+fn default_list_title() -> String {
+    "Transactions".to_string()
+}
+
+fn default_highlight_symbol() -> String {
+    "▶ ".to_string()
+}
+
+fn default_alternate_screen() -> bool {
+    true
+}
+
+fn default_stripe_rows() -> bool {
+    true
+}
+
+fn default_fallback_tag() -> String {
+    "other".to_string()
+}
+
+impl Config {
+    /// Clamp values that are only meaningful within a range.
+    pub fn sanitize(&mut self) {
+        self.stats_months = self.stats_months.clamp(1, 60);
+        self.decimal_places = self.decimal_places.min(4);
+        if self.amount_step.is_nan() || self.amount_step <= 0.0 {
+            self.amount_step = default_amount_step();
+        }
+        if self.amount_step_large.is_nan() || self.amount_step_large <= 0.0 {
+            self.amount_step_large = default_amount_step_large();
+        }
+        // A newline or tab in the marker would break the table layout
+        self.highlight_symbol.retain(|c| !c.is_control());
+        if self.ascii_mode && self.highlight_symbol == default_highlight_symbol() {
+            self.highlight_symbol = "> ".to_string();
+        }
+        // A blank message would leave the list looking broken
+        if self.empty_message.as_ref().is_some_and(|m| m.trim().is_empty()) {
+            self.empty_message = None;
+        }
+        // The title sits in a one-line border, and an empty one reads as missing
+        self.list_title.retain(|c| !c.is_control());
+        self.list_title = self.list_title.trim().to_string();
+        if self.list_title.is_empty() {
+            self.list_title = default_list_title();
+        }
+        // The word is typed on one line, so keep it to one trimmed word
+        self.typed_confirm_word = self.typed_confirm_word.trim().to_string();
+        if self.typed_confirm_word.is_empty() || self.typed_confirm_word.chars().any(char::is_whitespace) {
+            self.typed_confirm_word = default_typed_confirm_word();
+        }
+        if self.fallback_tag.trim().is_empty() {
+            self.fallback_tag = default_fallback_tag();
+        }
+        if !self.savings_rate_low.is_finite() {
+            self.savings_rate_low = 0.0;
+        }
+        if !self.savings_rate_healthy.is_finite() {
+            self.savings_rate_healthy = default_savings_rate_healthy();
+        }
+        if self.savings_rate_low > self.savings_rate_healthy {
+            std::mem::swap(&mut self.savings_rate_low, &mut self.savings_rate_healthy);
+        }
+        if self.week_start.trim().parse::<Weekday>().is_err() {
+            self.week_start = default_week_start();
+        }
+        self.sort_tie_breaker = self.sort_tie_breaker.trim().to_ascii_lowercase();
+        if !matches!(self.sort_tie_breaker.as_str(), "newest" | "oldest") {
+            self.sort_tie_breaker = default_sort_tie_breaker();
+        }
+        self.startup_mode = self.startup_mode.trim().to_ascii_lowercase();
+        if !matches!(self.startup_mode.as_str(), "normal" | "stats") {
+            self.startup_mode = default_startup_mode();
+        }
+        self.tag_budgets.retain(|tag, amount| !tag.trim().is_empty() && amount.is_finite() && *amount > 0.0);
+        if !self.budget_warning_percent.is_finite() {
+            self.budget_warning_percent = default_budget_warning_percent();
+        }
+        self.budget_warning_percent = self.budget_warning_percent.clamp(0.0, 100.0);
+        self.rounding = self.rounding.trim().to_ascii_lowercase();
+        if self.rounding.parse::<Rounding>().is_err() {
+            self.rounding = default_rounding();
+        }
+        self.locale = self.locale.trim().to_string();
+        if self.locale.parse::<Locale>().is_err() {
+            self.locale = default_locale();
+        }
+        // A goal needs a name and something to reach
+        self.goals.retain(|g| !g.name.trim().is_empty() && g.amount.is_finite() && g.amount > 0.0);
+        for goal in &mut self.goals {
+            let valid_date = goal.target_date.as_deref().is_some_and(|d| {
+                chrono::NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").is_ok()
+            });
+            if !valid_date {
+                goal.target_date = None;
+            }
+            if goal.tag.as_ref().is_some_and(|t| t.trim().is_empty()) {
+                goal.tag = None;
+            }
+        }
+        self.filter_presets.retain(|p| !p.name.trim().is_empty());
+        self.filter_presets.truncate(MAX_FILTER_PRESETS);
+        for preset in &mut self.filter_presets {
+            let blank = |v: &Option<String>| v.as_ref().is_some_and(|s| s.trim().is_empty());
+            let bad_date = |v: &Option<String>| {
+                v.as_ref().is_some_and(|d| {
+                    chrono::NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").is_err()
+                })
+            };
+            if blank(&preset.tag) {
+                preset.tag = None;
+            }
+            if blank(&preset.search) {
+                preset.search = None;
+            }
+            if bad_date(&preset.start_date) {
+                preset.start_date = None;
+            }
+            if bad_date(&preset.end_date) {
+                preset.end_date = None;
+            }
+            preset.range = preset
+                .range
+                .take()
+                .map(|r| r.trim().to_ascii_lowercase())
+                .filter(|r| PRESET_RANGES.contains(&r.as_str()));
+        }
+    }
+
+    /// The configured display rounding; half-up unless `rounding` names another.
+    pub fn rounding(&self) -> Rounding {
+        self.rounding.parse().unwrap_or_default()
+    }
+
+    /// The configured month-name language; English unless `locale` names
+    /// a built-in one.
+    pub fn locale(&self) -> Locale {
+        self.locale.parse().unwrap_or_default()
+    }
+
+    /// The configured week start; Monday unless `week_start` names a day.
+    pub fn week_start(&self) -> Weekday {
+        self.week_start.trim().parse().unwrap_or(Weekday::Mon)
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         let mut custom_themes = HashMap::new();
@@ -41,6 +525,9 @@ impl Default for Config {
                 surface: "#343746".to_string(),     // current line/surface
                 row_alt: "#2d2f3b".to_string(),     // midpoint
                 foreground: "#f8f8f2".to_string(),  // fg
+                danger: None,
+                info: None,
+                warning: None,
             },
         );
 
@@ -57,6 +544,9 @@ impl Default for Config {
                 surface: "#434c5e".to_string(),     // polar night (nord2)
                 row_alt: "#353c4a".to_string(),     // midpoint
                 foreground: "#d8dee9".to_string(),  // snow storm (nord4)
+                danger: None,
+                info: None,
+                warning: None,
             },
         );
 
@@ -73,6 +563,9 @@ impl Default for Config {
                 surface: "#3c3836".to_string(),     // bg1
                 row_alt: "#32302f".to_string(),     // midpoint
                 foreground: "#ebdbb2".to_string(),  // fg0
+                danger: None,
+                info: None,
+                warning: None,
             },
         );
 
@@ -88,22 +581,79 @@ impl Default for Config {
             currency: default_currency(),
             theme: default_theme_name(),
             custom_themes,
+            poll_interval_ms: default_poll_interval_ms(),
+            auto_confirm_recurring: false,
+            confirm_edit: false,
+            exchange_rates: HashMap::new(),
+            highlight_threshold: None,
+            tag_budgets: HashMap::new(),
+            budget_warning_percent: default_budget_warning_percent(),
+            low_balance_warning: None,
+            week_start: default_week_start(),
+            sort_tie_breaker: default_sort_tie_breaker(),
+            startup_mode: default_startup_mode(),
+            savings_rate_low: 0.0,
+            savings_rate_healthy: default_savings_rate_healthy(),
+            stats_months: default_stats_months(),
+            opening_balance: 0.0,
+            fallback_tag: default_fallback_tag(),
+            default_tag: None,
+            alternate_screen: default_alternate_screen(),
+            highlight_symbol: default_highlight_symbol(),
+            list_title: default_list_title(),
+            decimal_places: default_decimal_places(),
+            rounding: default_rounding(),
+            locale: default_locale(),
+            amount_step: default_amount_step(),
+            amount_step_large: default_amount_step_large(),
+            typed_confirm_threshold: 0,
+            typed_confirm_word: default_typed_confirm_word(),
+            empty_message: None,
+            ascii_mode: false,
+            relative_dates: false,
+            stripe_rows: default_stripe_rows(),
+            columns: default_columns(),
+            header_panels: default_header_panels(),
+            goals: Vec::new(),
+            filter_presets: Vec::new(),
         }
     }
 }
 
-fn config_path() -> PathBuf {
+/// `stem.ext`, or `stem-NAME.ext` under profile NAME, which gets its own
+/// database, config and UI state.
+pub fn profile_file_name(stem: &str, ext: &str, profile: Option<&str>) -> String {
+    match profile {
+        Some(name) => format!("{}-{}.{}", stem, name, ext),
+        None => format!("{}.{}", stem, ext),
+    }
+}
+
+fn config_dir() -> PathBuf {
     let proj_dirs =
         ProjectDirs::from("com", "ayan", "fitui").expect("Could not find config directory");
 
     let config_dir = proj_dirs.config_dir();
     fs::create_dir_all(config_dir).expect("Failed to create config directory");
 
-    config_dir.join("config.yaml")
+    config_dir.to_path_buf()
+}
+
+fn config_path(profile: Option<&str>) -> PathBuf {
+    config_dir().join(profile_file_name("config", "yaml", profile))
 }
 
-pub fn load_config() -> Config {
-    let path = config_path();
+pub fn load_config(profile: Option<&str>) -> Config {
+    let path = config_path(profile);
+
+    // A new profile starts from the main config, so tags and theme carry over
+    let main_path = config_dir().join("config.yaml");
+    if !path.exists() && profile.is_some() && main_path.exists() {
+        match fs::copy(&main_path, &path) {
+            Ok(_) => eprintln!("Created profile config at: {:?}", path),
+            Err(err) => eprintln!("Could not copy {:?} for the profile: {}", main_path, err),
+        }
+    }
 
     // Auto-create default config if missing
     if !path.exists() {
@@ -114,13 +664,14 @@ pub fn load_config() -> Config {
 
         fs::write(&path, yaml).expect("Failed to write default config.yaml");
 
-        println!("Created default config at: {:?}", path);
+        eprintln!("Created default config at: {:?}", path);
 
         return default;
     }
 
     let text = fs::read_to_string(&path).expect("Failed to read config.yaml");
     let mut config: Config = serde_yaml::from_str(&text).expect("Invalid YAML format");
+    config.sanitize();
 
     // Auto-migrate older configs that don't have theme options visible
     if !text.contains("theme:") {
@@ -135,3 +686,217 @@ pub fn load_config() -> Config {
 
     config
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_clamps_stats_months() {
+        let mut config = Config {
+            stats_months: 0,
+            ..Default::default()
+        };
+
+        config.sanitize();
+        assert_eq!(config.stats_months, 1);
+
+        config.stats_months = 500;
+        config.sanitize();
+        assert_eq!(config.stats_months, 60);
+    }
+
+    #[test]
+    fn sanitize_resets_unknown_week_start() {
+        let mut config = Config {
+            week_start: "Sunday".into(),
+            ..Default::default()
+        };
+        config.sanitize();
+        assert_eq!(config.week_start(), Weekday::Sun);
+
+        config.week_start = "someday".into();
+        config.sanitize();
+        assert_eq!(config.week_start, "monday");
+        assert_eq!(config.week_start(), Weekday::Mon);
+    }
+
+    #[test]
+    fn sanitize_resets_unknown_startup_mode() {
+        let mut config = Config {
+            startup_mode: " Stats ".into(),
+            ..Default::default()
+        };
+        config.sanitize();
+        assert_eq!(config.startup_mode, "stats");
+
+        config.startup_mode = "recurring".into();
+        config.sanitize();
+        assert_eq!(config.startup_mode, "normal");
+    }
+
+    #[test]
+    fn sanitize_orders_savings_thresholds() {
+        let mut config = Config {
+            savings_rate_low: 30.0,
+            savings_rate_healthy: 10.0,
+            ..Default::default()
+        };
+        config.sanitize();
+        assert_eq!((config.savings_rate_low, config.savings_rate_healthy), (10.0, 30.0));
+
+        config.savings_rate_healthy = f64::NAN;
+        config.sanitize();
+        assert_eq!(config.savings_rate_healthy, 20.0);
+    }
+
+    #[test]
+    fn sanitize_cleans_filter_presets() {
+        let preset = FilterPresetConfig {
+            name: "Week".into(),
+            range: Some("fortnight".into()),
+            start_date: Some("2026-13-01".into()),
+            search: Some("  ".into()),
+            ..Default::default()
+        };
+        let mut config = Config {
+            filter_presets: vec![preset; 12],
+            ..Config::default()
+        };
+        config.filter_presets[0].name = " ".into();
+        config.filter_presets[1].range = Some("This_Week".into());
+        config.sanitize();
+
+        assert_eq!(config.filter_presets.len(), MAX_FILTER_PRESETS);
+        let first = &config.filter_presets[0];
+        assert_eq!(first.range.as_deref(), Some("this_week"));
+        assert_eq!(first.start_date, None);
+        assert_eq!(first.search, None);
+        assert_eq!(config.filter_presets[1].range, None);
+    }
+
+    #[test]
+    fn header_panels_fall_back_when_invalid() {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let defaults = vec![HeaderPanel::Earned, HeaderPanel::Balance, HeaderPanel::Spent];
+
+        assert_eq!(header_panels(&names(&["Balance"])), vec![HeaderPanel::Balance]);
+        assert_eq!(
+            header_panels(&names(&["balance", "savings_rate"])),
+            vec![HeaderPanel::Balance, HeaderPanel::SavingsRate]
+        );
+        assert_eq!(header_panels(&[]), defaults);
+        assert_eq!(header_panels(&names(&["balance", "balance"])), defaults);
+        assert_eq!(header_panels(&names(&["net_worth"])), defaults);
+    }
+
+    #[test]
+    fn profile_file_names() {
+        assert_eq!(profile_file_name("budget", "db", None), "budget.db");
+        assert_eq!(profile_file_name("budget", "db", Some("freelance")), "budget-freelance.db");
+    }
+
+    #[test]
+    fn sanitize_drops_unusable_goals() {
+        let goal = |name: &str, amount: f64, target: &str| GoalConfig {
+            name: name.into(),
+            amount,
+            target_date: Some(target.into()),
+            tag: Some(" ".into()),
+        };
+        let mut config = Config {
+            goals: vec![
+                goal("fund", 500.0, "2027-01-31"),
+                goal("", 500.0, "2027-01-31"),
+                goal("trip", 0.0, "2027-01-31"),
+                goal("car", 9000.0, "someday"),
+            ],
+            ..Default::default()
+        };
+        config.sanitize();
+
+        assert_eq!(config.goals.len(), 2);
+        assert_eq!(config.goals[0].target_date.as_deref(), Some("2027-01-31"));
+        assert_eq!(config.goals[1].target_date, None);
+        assert!(config.goals.iter().all(|g| g.tag.is_none()));
+    }
+
+    #[test]
+    fn sanitize_drops_blank_empty_message() {
+        let mut config = Config {
+            empty_message: Some("   ".into()),
+            ..Default::default()
+        };
+
+        config.sanitize();
+        assert_eq!(config.empty_message, None);
+    }
+
+    #[test]
+    fn sanitize_restores_blank_fallback_tag() {
+        let mut config = Config {
+            fallback_tag: "  ".into(),
+            ..Default::default()
+        };
+
+        config.sanitize();
+        assert_eq!(config.fallback_tag, "other");
+    }
+
+    #[test]
+    fn sanitize_strips_control_chars_from_highlight_symbol() {
+        let mut config = Config {
+            highlight_symbol: ">>\n\t ".into(),
+            ..Default::default()
+        };
+
+        config.sanitize();
+        assert_eq!(config.highlight_symbol, ">> ");
+
+        let omitted: Config = serde_yaml::from_str("tags: [food]").unwrap();
+        assert_eq!(omitted.highlight_symbol, "▶ ");
+    }
+
+    #[test]
+    fn sanitize_trims_list_title_and_restores_blank() {
+        let mut config = Config {
+            list_title: "  My\tMoney ".into(),
+            ..Default::default()
+        };
+        config.sanitize();
+        assert_eq!(config.list_title, "MyMoney");
+
+        config.list_title = " \n ".into();
+        config.sanitize();
+        assert_eq!(config.list_title, "Transactions");
+    }
+
+    #[test]
+    fn sanitize_resets_non_positive_amount_steps() {
+        let mut config = Config {
+            amount_step: 0.0,
+            amount_step_large: -5.0,
+            ..Default::default()
+        };
+
+        config.sanitize();
+        assert_eq!(config.amount_step, 1.0);
+        assert_eq!(config.amount_step_large, 10.0);
+    }
+
+    #[test]
+    fn list_columns_fall_back_when_invalid() {
+        let column = |name: &str, width| ColumnConfig { name: name.into(), width };
+        let defaults = list_columns(&default_columns());
+        assert_eq!(defaults.len(), 5);
+
+        let custom = list_columns(&[column("Source", 60), column("amount", 30)]);
+        assert_eq!(custom, vec![(ListColumn::Source, 60), (ListColumn::Amount, 30)]);
+
+        assert_eq!(list_columns(&[column("type", 20)]), defaults);
+        assert_eq!(list_columns(&[column("tag", 20), column("tag", 20)]), defaults);
+        assert_eq!(list_columns(&[column("source", 80), column("tag", 40)]), defaults);
+        assert_eq!(list_columns(&[column("source", 0)]), defaults);
+        assert_eq!(list_columns(&[]), defaults);
+    }
+}
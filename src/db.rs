@@ -1,18 +1,20 @@
 #![allow(dead_code)]
-use rusqlite::{Connection, Result};
+use rusqlite::{Connection, OptionalExtension, Result};
 use std::collections::HashMap;
 use std::fs;
 use chrono::Datelike;
 
 use directories::ProjectDirs;
 
-use crate::models::{RecurringEntry, RecurringInterval, Tag, Transaction, TransactionType};
+use crate::models::{parse_note_tags, RecurringEntry, RecurringInterval, Tag, Transaction, TransactionType};
+use crate::money::{from_units, sum_amounts, to_units, Rounding, UNITS_PER_MAJOR};
 
 /// Initialize the database from a provided path. Useful for tests (`:memory:`) or custom locations.
 pub fn init_db_with_path(path: &std::path::Path) -> Result<Connection> {
-    println!("Database location: {:?}", path);
+    eprintln!("Database location: {:?}", path); // stderr keeps CLI output clean
 
     let conn = Connection::open(path)?;
+    enable_wal(&conn, path);
 
     // Create schema on first run if it doesn't exist yet
     conn.execute(
@@ -20,9 +22,25 @@ pub fn init_db_with_path(path: &std::path::Path) -> Result<Connection> {
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             source TEXT NOT NULL,
             amount REAL NOT NULL,
+            amount_units INTEGER NOT NULL DEFAULT 0,
             kind TEXT NOT NULL,
             tag TEXT NOT NULL,
-            date TEXT NOT NULL
+            date TEXT NOT NULL,
+            currency TEXT NOT NULL DEFAULT '',
+            created_by_recurring_id INTEGER,
+            receipt_path TEXT NOT NULL DEFAULT '',
+            cleared INTEGER NOT NULL DEFAULT 0,
+            note TEXT NOT NULL DEFAULT ''
+        )",
+        [],
+    )?;
+
+    // Hashtags found in each transaction's note, for searching
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS note_tags (
+            transaction_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (transaction_id, tag)
         )",
         [],
     )?;
@@ -33,36 +51,78 @@ pub fn init_db_with_path(path: &std::path::Path) -> Result<Connection> {
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             source TEXT NOT NULL,
             amount REAL NOT NULL,
+            amount_units INTEGER NOT NULL DEFAULT 0,
             kind TEXT NOT NULL,
             tag TEXT NOT NULL,
             interval TEXT NOT NULL DEFAULT 'monthly',
             original_date TEXT NOT NULL,
             last_inserted_date TEXT NOT NULL DEFAULT '',
-            active INTEGER NOT NULL DEFAULT 1
+            active INTEGER NOT NULL DEFAULT 1,
+            prorate_first INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+
+    // One balance per month, kept independently of the transactions table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS balance_snapshots (
+            month TEXT PRIMARY KEY,
+            balance REAL NOT NULL
+        )",
+        [],
+    )?;
+
+    // Months already closed with the month-end rollover
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS rollovers (
+            month TEXT PRIMARY KEY
         )",
         [],
     )?;
 
-    // Migrate existing recurring_entries table if it has old schema
+    // Migrate existing tables if they have an old schema
+    migrate_transactions_schema(&conn)?;
     migrate_recurring_entries_schema(&conn)?;
+    migrate_amount_units(&conn, "transactions");
+    migrate_amount_units(&conn, "recurring_entries");
 
     Ok(conn)
 }
 
+/// Switch to write-ahead logging for better crash safety and concurrent
+/// reads. Some filesystems (e.g. network mounts) can't do WAL; SQLite then
+/// keeps its default journal and we just carry on with that.
+fn enable_wal(conn: &Connection, path: &std::path::Path) {
+    // In-memory databases have no journal file to speak of
+    if path == std::path::Path::new(":memory:") {
+        return;
+    }
+
+    let mode: Result<String> = conn.query_row("PRAGMA journal_mode=WAL", [], |row| row.get(0));
+    match mode {
+        Ok(mode) if mode.eq_ignore_ascii_case("wal") => {
+            let _ = conn.execute_batch("PRAGMA synchronous=NORMAL");
+        }
+        Ok(mode) => eprintln!("WAL mode unavailable, using '{}' journal instead", mode),
+        Err(err) => eprintln!("Could not enable WAL mode: {}", err),
+    }
+}
+
 /// Initialize DB in-memory for tests.
 pub fn init_in_memory() -> Result<Connection> {
     init_db_with_path(std::path::Path::new(":memory:"))
 }
 
-pub fn init_db() -> Result<Connection> {
-    let db_path = if cfg!(debug_assertions) {
-        // Debug build: store DB locally inside the project folder
+/// Directory holding the database and any exported files.
+pub fn data_dir() -> std::path::PathBuf {
+    if cfg!(debug_assertions) {
+        // Debug build: store data locally inside the project folder
         let local_dir = std::path::Path::new("./data");
         fs::create_dir_all(local_dir).expect("Failed to create local debug data directory");
 
-        local_dir.join("budget.db")
+        local_dir.to_path_buf()
     } else {
-        // Release build: store DB in OS-standard application data directory
+        // Release build: store data in OS-standard application data directory
         let proj_dirs =
             ProjectDirs::from("com", "ayan", "fitui")
                 .expect("Could not determine data directory");
@@ -70,10 +130,111 @@ pub fn init_db() -> Result<Connection> {
         let data_dir = proj_dirs.data_dir();
         fs::create_dir_all(data_dir).expect("Failed to create data directory");
 
-        data_dir.join("budget.db")
-    };
+        data_dir.to_path_buf()
+    }
+}
+
+pub fn init_db(profile: Option<&str>) -> Result<Connection> {
+    // Each --profile keeps its own database next to the default one
+    init_db_with_path(&data_dir().join(crate::config::profile_file_name("budget", "db", profile)))
+}
+
+/// Add columns introduced after the original transactions schema
+pub fn migrate_transactions_schema(conn: &Connection) -> Result<()> {
+    // Check and add currency column if missing
+    let has_currency = conn
+        .prepare("SELECT currency FROM transactions LIMIT 1")
+        .map(|_| true)
+        .unwrap_or(false);
+
+    if !has_currency {
+        let _ = conn.execute(
+            "ALTER TABLE transactions ADD COLUMN currency TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+    }
+
+    // Check and add created_by_recurring_id column if missing
+    let has_recurring_link = conn
+        .prepare("SELECT created_by_recurring_id FROM transactions LIMIT 1")
+        .map(|_| true)
+        .unwrap_or(false);
+
+    if !has_recurring_link {
+        let _ = conn.execute(
+            "ALTER TABLE transactions ADD COLUMN created_by_recurring_id INTEGER",
+            [],
+        );
+    }
+
+    // Check and add receipt_path column if missing
+    let has_receipt = conn
+        .prepare("SELECT receipt_path FROM transactions LIMIT 1")
+        .map(|_| true)
+        .unwrap_or(false);
+
+    if !has_receipt {
+        let _ = conn.execute(
+            "ALTER TABLE transactions ADD COLUMN receipt_path TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+    }
+
+    // Check and add cleared column if missing
+    let has_cleared = conn
+        .prepare("SELECT cleared FROM transactions LIMIT 1")
+        .map(|_| true)
+        .unwrap_or(false);
+
+    if !has_cleared {
+        let _ = conn.execute(
+            "ALTER TABLE transactions ADD COLUMN cleared INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+    }
+
+    // Check and add note column if missing
+    let has_note = conn
+        .prepare("SELECT note FROM transactions LIMIT 1")
+        .map(|_| true)
+        .unwrap_or(false);
+
+    if !has_note {
+        let _ = conn.execute(
+            "ALTER TABLE transactions ADD COLUMN note TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+    }
+
+    Ok(())
+}
+
+/// Add the integer `amount_units` column (see `money::UNITS_PER_MAJOR`) and
+/// fill it from the old REAL `amount`. The REAL column stays and is still
+/// written, so an older FiTui can open the same file.
+fn migrate_amount_units(conn: &Connection, table: &str) {
+    let has_units = conn
+        .prepare(&format!("SELECT amount_units FROM {} LIMIT 1", table))
+        .map(|_| true)
+        .unwrap_or(false);
+
+    if !has_units {
+        let _ = conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN amount_units INTEGER NOT NULL DEFAULT 0", table),
+            [],
+        );
+    }
 
-    init_db_with_path(&db_path)
+    // Also on every open: rows an older FiTui inserted since only have the
+    // REAL amount, and would otherwise be summed as zero
+    let _ = conn.execute(
+        &format!(
+            "UPDATE {} SET amount_units = CAST(ROUND(amount * {}) AS INTEGER)
+             WHERE amount_units = 0 AND amount != 0",
+            table, UNITS_PER_MAJOR
+        ),
+        [],
+    );
 }
 
 /// Migrate old recurring_entries table to new schema with interval and original_date columns
@@ -149,21 +310,34 @@ pub fn migrate_recurring_entries_schema(conn: &Connection) -> Result<()> {
         );
     }
 
+    // Check and add prorate_first column if missing
+    let has_prorate_first = conn
+        .prepare("SELECT prorate_first FROM recurring_entries LIMIT 1")
+        .map(|_| true)
+        .unwrap_or(false);
+
+    if !has_prorate_first {
+        let _ = conn.execute(
+            "ALTER TABLE recurring_entries ADD COLUMN prorate_first INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+    }
+
     Ok(())
 }
 
 pub fn get_transactions(conn: &Connection) -> Result<Vec<Transaction>> {
     let mut stmt = conn.prepare(
-        "SELECT id, source, amount, kind, tag, date
+        "SELECT id, source, amount_units, kind, tag, date, currency, created_by_recurring_id, receipt_path, cleared, note
          FROM transactions
-         ORDER BY date DESC",
+         ORDER BY date DESC, id DESC",
     )?;
 
     let rows = stmt.query_map([], |row| {
         Ok(Transaction {
             id: row.get(0)?,
             source: row.get(1)?,
-            amount: row.get(2)?,
+            amount: from_units(row.get(2)?),
 
             // Stored as string in DB, converted back into enum
             kind: TransactionType::from_str(&row.get::<_, String>(3)?),
@@ -172,6 +346,11 @@ pub fn get_transactions(conn: &Connection) -> Result<Vec<Transaction>> {
             tag: Tag::from_str(&row.get::<_, String>(4)?),
 
             date: row.get(5)?,
+            currency: row.get(6)?,
+            created_by_recurring_id: row.get(7)?,
+            receipt_path: row.get(8)?,
+            cleared: row.get(9)?,
+            note: row.get(10)?,
         })
     })?;
 
@@ -191,253 +370,1086 @@ pub fn add_transaction(
     tag: &Tag,
     date: &str,
 ) -> Result<()> {
-    conn.execute(
-        "INSERT INTO transactions (source, amount, kind, tag, date)
-         VALUES (?1, ?2, ?3, ?4, ?5)",
-        (source, amount, kind.as_str(), tag.as_str(), date),
+    insert_transaction(
+        conn,
+        &Transaction {
+            source: source.trim().to_string(),
+            amount,
+            kind,
+            tag: tag.clone(),
+            date: date.to_string(),
+            ..Default::default()
+        },
     )?;
 
     Ok(())
 }
 
-pub fn delete_transaction(conn: &Connection, id: i32) -> Result<()> {
-    conn.execute("DELETE FROM transactions WHERE id = ?1", [id])?;
-    Ok(())
+/// Insert every column of `tx` (its `id` is ignored). Returns the new row id.
+pub fn insert_transaction(conn: &Connection, tx: &Transaction) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO transactions (source, amount, kind, tag, date, currency, created_by_recurring_id, receipt_path, cleared, amount_units, note)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        (
+            &tx.source,
+            tx.amount,
+            tx.kind.as_str(),
+            tx.tag.as_str(),
+            &tx.date,
+            &tx.currency,
+            tx.created_by_recurring_id,
+            &tx.receipt_path,
+            tx.cleared,
+            to_units(tx.amount),
+            &tx.note,
+        ),
+    )?;
+
+    let id = conn.last_insert_rowid();
+    index_note_tags(conn, id, &tx.note)?;
+    Ok(id)
 }
 
-pub fn update_transaction(
-    conn: &Connection,
-    id: i32,
-    source: &str,
-    amount: f64,
-    kind: TransactionType,
-    tag: &Tag,
-    date: &str,
-) -> Result<()> {
+/// Put rows back under their original ids, e.g. when a delete is undone.
+/// A row that still exists is overwritten.
+pub fn restore_transactions(conn: &Connection, rows: &[Transaction]) -> Result<()> {
+    let db_tx = conn.unchecked_transaction()?;
+    for tx in rows {
+        db_tx.execute(
+            "INSERT OR REPLACE INTO transactions (id, source, amount, kind, tag, date, currency, created_by_recurring_id, receipt_path, cleared, amount_units, note)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            (
+                tx.id,
+                &tx.source,
+                tx.amount,
+                tx.kind.as_str(),
+                tx.tag.as_str(),
+                &tx.date,
+                &tx.currency,
+                tx.created_by_recurring_id,
+                &tx.receipt_path,
+                tx.cleared,
+                to_units(tx.amount),
+                &tx.note,
+            ),
+        )?;
+        index_note_tags(&db_tx, tx.id.into(), &tx.note)?;
+    }
+
+    db_tx.commit()
+}
+
+/// Move each row from its `from` values to its `to` values, writing only the
+/// columns that differ between the two. Rows deleted since are skipped, and
+/// columns changed some other way, such as `cleared`, are left alone.
+pub fn update_changed_columns(conn: &Connection, rows: &[(&Transaction, &Transaction)]) -> Result<()> {
+    use rusqlite::types::Value;
+
+    let db_tx = conn.unchecked_transaction()?;
+    for (from, to) in rows {
+        let mut columns: Vec<(&str, Value)> = Vec::new();
+        if from.source != to.source {
+            columns.push(("source", to.source.clone().into()));
+        }
+        if from.amount != to.amount {
+            columns.push(("amount", to.amount.into()));
+            columns.push(("amount_units", to_units(to.amount).into()));
+        }
+        if from.kind != to.kind {
+            columns.push(("kind", to.kind.as_str().to_string().into()));
+        }
+        if from.tag != to.tag {
+            columns.push(("tag", to.tag.as_str().to_string().into()));
+        }
+        if from.date != to.date {
+            columns.push(("date", to.date.clone().into()));
+        }
+        if from.currency != to.currency {
+            columns.push(("currency", to.currency.clone().into()));
+        }
+        if from.receipt_path != to.receipt_path {
+            columns.push(("receipt_path", to.receipt_path.clone().into()));
+        }
+        if from.note != to.note {
+            columns.push(("note", to.note.clone().into()));
+        }
+        if columns.is_empty() {
+            continue;
+        }
+
+        let set = columns
+            .iter()
+            .map(|(column, _)| format!("{} = ?", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut values: Vec<Value> = columns.into_iter().map(|(_, value)| value).collect();
+        values.push(i64::from(to.id).into());
+
+        let changed = db_tx.execute(
+            &format!("UPDATE transactions SET {} WHERE id = ?", set),
+            rusqlite::params_from_iter(values),
+        )?;
+        if changed > 0 && from.note != to.note {
+            index_note_tags(&db_tx, to.id.into(), &to.note)?;
+        }
+    }
+
+    db_tx.commit()
+}
+
+/// Overwrite every column of the stored row with id `tx.id`.
+pub fn replace_transaction(conn: &Connection, tx: &Transaction) -> Result<()> {
     conn.execute(
-        "UPDATE transactions SET source = ?1, amount = ?2, kind = ?3, tag = ?4, date = ?5 WHERE id = ?6",
-        (source, amount, kind.as_str(), tag.as_str(), date, id),
+        "UPDATE transactions
+         SET source = ?1, amount = ?2, kind = ?3, tag = ?4, date = ?5, currency = ?6,
+             created_by_recurring_id = ?7, receipt_path = ?8, cleared = ?9, amount_units = ?11,
+             note = ?12
+         WHERE id = ?10",
+        (
+            &tx.source,
+            tx.amount,
+            tx.kind.as_str(),
+            tx.tag.as_str(),
+            &tx.date,
+            &tx.currency,
+            tx.created_by_recurring_id,
+            &tx.receipt_path,
+            tx.cleared,
+            tx.id,
+            to_units(tx.amount),
+            &tx.note,
+        ),
     )?;
+    index_note_tags(conn, tx.id.into(), &tx.note)?;
 
     Ok(())
 }
 
-pub fn total_earned(conn: &Connection) -> Result<f64> {
-    conn.query_row(
-        "SELECT COALESCE(SUM(amount), 0)
-         FROM transactions
-         WHERE kind = 'credit'",
-        [],
-        |row| row.get(0),
-    )
+pub fn delete_transaction(conn: &Connection, id: i32) -> Result<()> {
+    conn.execute("DELETE FROM transactions WHERE id = ?1", [id])?;
+    prune_note_tags(conn)?;
+    Ok(())
 }
 
-pub fn total_spent(conn: &Connection) -> Result<f64> {
-    conn.query_row(
-        "SELECT COALESCE(SUM(amount), 0)
-         FROM transactions
-         WHERE kind = 'debit'",
-        [],
-        |row| row.get(0),
-    )
+/// Delete every transaction in `ids`. Returns the number of rows removed.
+pub fn delete_transactions(conn: &Connection, ids: &[i32]) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut removed = 0;
+    {
+        let mut stmt = tx.prepare("DELETE FROM transactions WHERE id = ?1")?;
+        for id in ids {
+            removed += stmt.execute([id])?;
+        }
+    }
+    tx.commit()?;
+    prune_note_tags(conn)?;
+
+    Ok(removed)
 }
 
-pub fn spent_per_tag(conn: &Connection) -> Result<HashMap<Tag, f64>> {
-    // Aggregate total spending grouped by tag
-    let mut stmt = conn.prepare(
-        "SELECT tag, COALESCE(SUM(amount), 0)
-         FROM transactions
-         WHERE kind = 'debit'
-         GROUP BY tag",
+/// Delete every transaction spawned by the recurring rule `rec_id`.
+/// Returns the number of rows removed.
+pub fn delete_transactions_by_recurring(conn: &Connection, rec_id: i32) -> Result<usize> {
+    let removed = conn.execute(
+        "DELETE FROM transactions WHERE created_by_recurring_id = ?1",
+        [rec_id],
     )?;
+    prune_note_tags(conn)?;
 
-    let rows = stmt.query_map([], |row| {
-        let tag_str: String = row.get(0)?;
-        let total: f64 = row.get(1)?;
-
-        Ok((Tag::from_str(&tag_str), total))
-    })?;
+    Ok(removed)
+}
 
-    let mut map = HashMap::new();
-    for r in rows {
-        let (tag, total) = r?;
-        map.insert(tag, total);
+/// Replace the stored hashtags of transaction `id` with those in `note`.
+fn index_note_tags(conn: &Connection, id: i64, note: &str) -> Result<()> {
+    conn.execute("DELETE FROM note_tags WHERE transaction_id = ?1", [id])?;
+    let mut stmt =
+        conn.prepare("INSERT OR IGNORE INTO note_tags (transaction_id, tag) VALUES (?1, ?2)")?;
+    for tag in parse_note_tags(note) {
+        stmt.execute((id, tag))?;
     }
 
-    Ok(map)
+    Ok(())
 }
-// Recurring entry functions
-pub fn get_recurring_entries(conn: &Connection) -> Result<Vec<RecurringEntry>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, source, amount, kind, tag, interval, original_date, last_inserted_date, active
-         FROM recurring_entries
-         ORDER BY id DESC",
+
+/// Drop hashtags whose transaction no longer exists.
+fn prune_note_tags(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "DELETE FROM note_tags WHERE transaction_id NOT IN (SELECT id FROM transactions)",
+        [],
     )?;
+    Ok(())
+}
 
-    let rows = stmt.query_map([], |row| {
-        Ok(RecurringEntry {
-            id: row.get(0)?,
-            source: row.get(1)?,
-            amount: row.get(2)?,
-            kind: TransactionType::from_str(&row.get::<_, String>(3)?),
-            tag: Tag::from_str(&row.get::<_, String>(4)?),
-            interval: RecurringInterval::from_str(&row.get::<_, String>(5)?),
-            original_date: row.get(6)?,
-            last_inserted_date: row.get(7)?,
-            active: row.get::<_, i32>(8)? != 0,
-        })
-    })?;
+/// Note hashtags keyed by transaction id.
+pub fn note_tags(conn: &Connection) -> Result<HashMap<i32, Vec<String>>> {
+    let mut stmt = conn.prepare("SELECT transaction_id, tag FROM note_tags ORDER BY tag")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, String>(1)?)))?;
 
-    let mut entries = Vec::new();
-    for entry in rows {
-        entries.push(entry?);
+    let mut tags: HashMap<i32, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (id, tag) = row?;
+        tags.entry(id).or_default().push(tag);
     }
 
-    Ok(entries)
+    Ok(tags)
 }
 
-pub fn add_recurring_entry(
+/// Whether a transaction with this source, amount, currency and date is
+/// already stored.
+pub fn transaction_exists(
     conn: &Connection,
     source: &str,
     amount: f64,
-    kind: TransactionType,
-    tag: &Tag,
-    interval: &RecurringInterval,
-    original_date: &str,
-) -> Result<()> {
-    conn.execute(
-        "INSERT INTO recurring_entries (source, amount, kind, tag, interval, original_date, last_inserted_date, active)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-        (
-            source,
-            amount,
-            kind.as_str(),
-            tag.as_str(),
-            interval.as_str(),
-            original_date,
-            "", // Empty string indicates it hasn't been inserted yet
-            1,
-        ),
-    )?;
+    currency: &str,
+    date: &str,
+) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(
+            SELECT 1 FROM transactions
+            WHERE source = ?1 AND ABS(amount_units - ?2) < ?4 AND date = ?3 AND currency = ?5
+        )",
+        // Within half a cent, as the form only shows two places
+        (source, to_units(amount), date, to_units(0.005), currency),
+        |row| row.get(0),
+    )
+}
 
-    Ok(())
+/// Multiply the amount of every transaction in `ids` by `factor`, e.g. 1.05
+/// for +5%, rounding each to `decimals` places. Returns the number of rows
+/// changed.
+pub fn scale_amounts(
+    conn: &Connection,
+    ids: &[i32],
+    factor: f64,
+    decimals: usize,
+    rounding: Rounding,
+) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut changed = 0;
+    {
+        let mut select = tx.prepare("SELECT amount_units FROM transactions WHERE id = ?1")?;
+        let mut update = tx.prepare("UPDATE transactions SET amount = ?1, amount_units = ?2 WHERE id = ?3")?;
+        for id in ids {
+            let Some(units) = select.query_row([id], |row| row.get::<_, i64>(0)).optional()? else {
+                continue;
+            };
+            let amount = rounding.scale(from_units(units), factor, decimals);
+            changed += update.execute((amount, to_units(amount), id))?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(changed)
 }
 
-pub fn delete_recurring_entry(conn: &Connection, id: i32) -> Result<()> {
-    conn.execute("DELETE FROM recurring_entries WHERE id = ?1", [id])?;
-    Ok(())
+/// Move every transaction in `ids` to `tag`. Returns the number of rows changed.
+pub fn retag_transactions(conn: &Connection, ids: &[i32], tag: &Tag) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut changed = 0;
+    {
+        let mut stmt = tx.prepare("UPDATE transactions SET tag = ?1 WHERE id = ?2")?;
+        for id in ids {
+            changed += stmt.execute((tag.as_str(), id))?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(changed)
 }
 
-pub fn toggle_recurring_entry(conn: &Connection, id: i32, active: bool) -> Result<()> {
-    conn.execute(
-        "UPDATE recurring_entries SET active = ?1 WHERE id = ?2",
-        (if active { 1 } else { 0 }, id),
-    )?;
-    Ok(())
+/// Mark every transaction in `ids` as cleared or pending. Returns how many
+/// rows changed.
+pub fn set_cleared(conn: &Connection, ids: &[i32], cleared: bool) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut changed = 0;
+    {
+        let mut stmt = tx.prepare("UPDATE transactions SET cleared = ?1 WHERE id = ?2")?;
+        for id in ids {
+            changed += stmt.execute((cleared, id))?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(changed)
 }
 
-// Auto-insert recurring entries based on their interval
-pub fn insert_recurring_transactions(conn: &Connection) -> Result<()> {
-    let now = chrono::Local::now();
-    let today_str = now.format("%Y-%m-%d").to_string();
-    let current_week = format!("{:04}-W{:02}", now.year(), now.iso_week().week());
-    let current_month = format!("{:04}-{:02}", now.year(), now.month());
+/// Number of transactions spawned by the recurring rule `rec_id`.
+pub fn count_transactions_by_recurring(conn: &Connection, rec_id: i32) -> Result<usize> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM transactions WHERE created_by_recurring_id = ?1",
+        [rec_id],
+        |row| row.get(0),
+    )
+}
 
-    // Get all active recurring entries
+/// Every source used so far, most frequently used first, for form completion.
+pub fn distinct_sources(conn: &Connection) -> Result<Vec<String>> {
     let mut stmt = conn.prepare(
-        "SELECT id, source, amount, kind, tag, interval, original_date, last_inserted_date
-         FROM recurring_entries
-         WHERE active = 1",
+        "SELECT source FROM transactions
+         WHERE TRIM(source) != ''
+         GROUP BY source
+         ORDER BY COUNT(*) DESC, MAX(date) DESC",
     )?;
 
-    let entries: Vec<_> = stmt
-        .query_map([], |row| {
-            Ok((
-                row.get::<_, i32>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, f64>(2)?,
-                row.get::<_, String>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, String>(5)?,
-                row.get::<_, String>(6)?,
-                row.get::<_, String>(7)?,
-            ))
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-
-    // Process each recurring entry
-    for (rec_id, source, amount, kind, tag, interval_str, original_date, last_inserted_date) in entries {
-        let interval = RecurringInterval::from_str(&interval_str);
-        let kind_enum = TransactionType::from_str(&kind);
-        let tag_obj = Tag::from_str(&tag);
-
-        let should_insert = match interval {
-            RecurringInterval::Daily => {
-                // Insert if we haven't inserted today
-                last_inserted_date != today_str
-            }
-            RecurringInterval::Weekly => {
-                // Extract day of week from original date
-                if let Ok(original_ndt) = chrono::NaiveDate::parse_from_str(&original_date, "%Y-%m-%d") {
-                    let original_dow = original_ndt.weekday();
-                    let today_dow = now.weekday();
-                    
-                    // Check if this is the same day of the week and hasn't been inserted this week
-                    original_dow == today_dow && last_inserted_date != current_week
-                } else {
-                    false
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Sources that differ only by surrounding whitespace or letter case, as
+/// (spelling to keep, stored spellings to rewrite). The most used spelling,
+/// trimmed, is kept.
+pub fn source_variants(conn: &Connection) -> Result<Vec<(String, Vec<String>)>> {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+
+    // Most used first, so the first spelling seen in a group is the canonical one
+    for source in distinct_sources(conn)? {
+        let key = source.trim().to_lowercase();
+        match groups.iter_mut().find(|(canonical, _)| canonical.to_lowercase() == key) {
+            Some((canonical, variants)) => {
+                if *canonical != source {
+                    variants.push(source);
                 }
             }
-            RecurringInterval::Monthly => {
-                // Extract day of month from original date
-                if let Ok(original_ndt) = chrono::NaiveDate::parse_from_str(&original_date, "%Y-%m-%d") {
-                    let original_day = original_ndt.day();
-                    let today_day = now.day();
-                    
-                    // Check if this is the same day of month and hasn't been inserted this month
-                    original_day == today_day && last_inserted_date != current_month
-                } else {
-                    false
-                }
+            None => {
+                let canonical = source.trim().to_string();
+                let variants = if canonical == source { Vec::new() } else { vec![source] };
+                groups.push((canonical, variants));
             }
-        };
-
-        if should_insert {
-            // Insert as a transaction with today's date
-            add_transaction(conn, &source, amount, kind_enum, &tag_obj, &today_str)?;
+        }
+    }
 
-            // Update the last_inserted_date based on interval
-            let new_last_inserted = match interval {
-                RecurringInterval::Daily => today_str.clone(),
-                RecurringInterval::Weekly => current_week.clone(),
-                RecurringInterval::Monthly => current_month.clone(),
-            };
+    groups.retain(|(_, variants)| !variants.is_empty());
+    Ok(groups)
+}
 
-            conn.execute(
-                "UPDATE recurring_entries SET last_inserted_date = ?1 WHERE id = ?2",
-                (new_last_inserted, rec_id),
-            )?;
+/// Rewrite every variant spelling to its canonical source. Returns the
+/// number of rows changed.
+pub fn merge_sources(conn: &Connection, merges: &[(String, Vec<String>)]) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut changed = 0;
+    {
+        let mut stmt = tx.prepare("UPDATE transactions SET source = ?1 WHERE source = ?2")?;
+        for (canonical, variants) in merges {
+            for variant in variants {
+                changed += stmt.execute((canonical, variant))?;
+            }
         }
     }
+    tx.commit()?;
 
-    Ok(())
+    Ok(changed)
 }
 
+/// Record `balance` for `month` (YYYY-MM) unless that month already has a
+/// snapshot. Returns whether a new row was written.
+pub fn record_balance_snapshot(conn: &Connection, month: &str, balance: f64) -> Result<bool> {
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO balance_snapshots (month, balance) VALUES (?1, ?2)",
+        (month, balance),
+    )?;
 
-// Keep the old function name for backwards compatibility
-pub fn insert_recurring_for_month(conn: &Connection, _current_month: &str) -> Result<()> {
-    insert_recurring_transactions(conn)
+    Ok(inserted > 0)
 }
 
+/// Record `balance` for `month`, replacing any earlier snapshot of it.
+pub fn set_balance_snapshot(conn: &Connection, month: &str, balance: f64) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO balance_snapshots (month, balance) VALUES (?1, ?2)",
+        (month, balance),
+    )?;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    Ok(())
+}
 
-    use crate::models::{RecurringInterval, Tag, TransactionType};
+/// Whether `month` has been rolled over.
+pub fn is_rolled_over(conn: &Connection, month: &str) -> Result<bool> {
+    conn.query_row("SELECT EXISTS(SELECT 1 FROM rollovers WHERE month = ?1)", [month], |row| row.get(0))
+}
 
-    fn setup_conn() -> Connection {
-        init_in_memory().expect("failed to init in-memory db")
+/// Mark `month` as rolled over. Returns false if it already was.
+pub fn record_rollover(conn: &Connection, month: &str) -> Result<bool> {
+    let inserted = conn.execute("INSERT OR IGNORE INTO rollovers (month) VALUES (?1)", [month])?;
+    Ok(inserted > 0)
+}
+
+/// All balance snapshots as (month, balance), oldest first.
+pub fn get_balance_snapshots(conn: &Connection) -> Result<Vec<(String, f64)>> {
+    let mut stmt = conn.prepare("SELECT month, balance FROM balance_snapshots ORDER BY month")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+pub fn update_transaction(
+    conn: &Connection,
+    id: i32,
+    source: &str,
+    amount: f64,
+    kind: TransactionType,
+    tag: &Tag,
+    date: &str,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE transactions SET source = ?1, amount = ?2, kind = ?3, tag = ?4, date = ?5, amount_units = ?7 WHERE id = ?6",
+        (source, amount, kind.as_str(), tag.as_str(), date, id, to_units(amount)),
+    )?;
+
+    Ok(())
+}
+
+pub fn total_earned(conn: &Connection) -> Result<f64> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(amount_units), 0)
+         FROM transactions
+         WHERE kind = 'credit'",
+        [],
+        |row| row.get(0).map(from_units),
+    )
+}
+
+pub fn total_spent(conn: &Connection) -> Result<f64> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(amount_units), 0)
+         FROM transactions
+         WHERE kind = 'debit'",
+        [],
+        |row| row.get(0).map(from_units),
+    )
+}
+
+/// A per-currency `SUM(amount_units)` converted to base-currency units, the
+/// way `Transaction::base_amount` converts each row.
+fn base_units(units: i64, currency: &str, rates: &HashMap<String, f64>) -> i64 {
+    let tx = Transaction { amount: from_units(units), currency: currency.to_string(), ..Default::default() };
+    to_units(tx.base_amount(rates))
+}
+
+/// Monthly spending for one tag as (YYYY-MM, total) in the base currency,
+/// oldest first. Months without any spending are missing; see
+/// `stats::zero_fill_months`.
+pub fn spent_per_tag_per_month(
+    conn: &Connection,
+    tag: &Tag,
+    rates: &HashMap<String, f64>,
+) -> Result<Vec<(String, f64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT substr(date, 1, 7) AS month, currency, COALESCE(SUM(amount_units), 0)
+         FROM transactions
+         WHERE kind = 'debit' AND tag = ?1
+         GROUP BY month, currency",
+    )?;
+
+    let mut months: std::collections::BTreeMap<String, i64> = Default::default();
+    let rows = stmt.query_map([tag.as_str()], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+    })?;
+    for row in rows {
+        let (month, currency, units) = row?;
+        *months.entry(month).or_default() += base_units(units, &currency, rates);
+    }
+
+    Ok(months.into_iter().map(|(month, units)| (month, from_units(units))).collect())
+}
+
+/// (earned, spent) in the base currency for every month with transactions,
+/// as (YYYY-MM, earned, spent), oldest first.
+pub fn totals_per_month(conn: &Connection, rates: &HashMap<String, f64>) -> Result<Vec<(String, f64, f64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT substr(date, 1, 7) AS month, currency,
+                COALESCE(SUM(CASE WHEN kind = 'credit' THEN amount_units END), 0),
+                COALESCE(SUM(CASE WHEN kind = 'debit' THEN amount_units END), 0)
+         FROM transactions
+         GROUP BY month, currency",
+    )?;
+
+    let mut months: std::collections::BTreeMap<String, (i64, i64)> = Default::default();
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, i64>(3)?,
+        ))
+    })?;
+    for row in rows {
+        let (month, currency, earned, spent) = row?;
+        let totals = months.entry(month).or_default();
+        totals.0 += base_units(earned, &currency, rates);
+        totals.1 += base_units(spent, &currency, rates);
+    }
+
+    Ok(months
+        .into_iter()
+        .map(|(month, (earned, spent))| (month, from_units(earned), from_units(spent)))
+        .collect())
+}
+
+/// Monthly spending for every tag in the base currency as (YYYY-MM, tag,
+/// total), by month and then tag. Like `spent_per_tag_per_month` for all
+/// tags at once.
+pub fn spent_per_month_by_tag(conn: &Connection, rates: &HashMap<String, f64>) -> Result<Vec<(String, Tag, f64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT substr(date, 1, 7) AS month, tag, currency, COALESCE(SUM(amount_units), 0)
+         FROM transactions
+         WHERE kind = 'debit'
+         GROUP BY month, tag, currency",
+    )?;
+
+    let mut totals: std::collections::BTreeMap<(String, String), i64> = Default::default();
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, i64>(3)?,
+        ))
+    })?;
+    for row in rows {
+        let (month, tag, currency, units) = row?;
+        *totals.entry((month, tag)).or_default() += base_units(units, &currency, rates);
+    }
+
+    Ok(totals
+        .into_iter()
+        .map(|((month, tag), units)| (month, Tag::from_str(&tag), from_units(units)))
+        .collect())
+}
+
+/// All-time (spent, earned) for one tag in the base currency: its debits and
+/// its credits.
+pub fn total_for_tag(conn: &Connection, tag: &Tag, rates: &HashMap<String, f64>) -> Result<(f64, f64)> {
+    let mut stmt = conn.prepare(
+        "SELECT currency,
+                COALESCE(SUM(CASE WHEN kind = 'debit' THEN amount_units END), 0),
+                COALESCE(SUM(CASE WHEN kind = 'credit' THEN amount_units END), 0)
+         FROM transactions
+         WHERE tag = ?1
+         GROUP BY currency",
+    )?;
+
+    let (mut spent, mut earned) = (0, 0);
+    let rows = stmt.query_map([tag.as_str()], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?))
+    })?;
+    for row in rows {
+        let (currency, debits, credits) = row?;
+        spent += base_units(debits, &currency, rates);
+        earned += base_units(credits, &currency, rates);
+    }
+
+    Ok((from_units(spent), from_units(earned)))
+}
+
+/// Credits minus debits in the base currency for each month of `year` that
+/// has transactions, as (month number 1-12, net). Months without any rows
+/// are left out.
+pub fn net_per_month_for_year(
+    conn: &Connection,
+    year: i32,
+    rates: &HashMap<String, f64>,
+) -> Result<Vec<(u32, f64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT CAST(substr(date, 6, 2) AS INTEGER) AS month, currency,
+                SUM(CASE WHEN kind = 'credit' THEN amount_units ELSE -amount_units END)
+         FROM transactions
+         WHERE substr(date, 1, 4) = ?1
+         GROUP BY month, currency",
+    )?;
+
+    let mut months: std::collections::BTreeMap<u32, i64> = Default::default();
+    let rows = stmt.query_map([format!("{:04}", year)], |row| {
+        Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+    })?;
+    for row in rows {
+        let (month, currency, units) = row?;
+        *months.entry(month).or_default() += base_units(units, &currency, rates);
+    }
+
+    Ok(months.into_iter().map(|(month, units)| (month, from_units(units))).collect())
+}
+
+/// Total spending per tag in the base currency.
+pub fn spent_per_tag(conn: &Connection, rates: &HashMap<String, f64>) -> Result<HashMap<Tag, f64>> {
+    let mut stmt = conn.prepare(
+        "SELECT tag, currency, COALESCE(SUM(amount_units), 0)
+         FROM transactions
+         WHERE kind = 'debit'
+         GROUP BY tag, currency",
+    )?;
+
+    let mut units: HashMap<Tag, i64> = HashMap::new();
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+    })?;
+    for row in rows {
+        let (tag, currency, total) = row?;
+        *units.entry(Tag::from_str(&tag)).or_default() += base_units(total, &currency, rates);
+    }
+
+    Ok(units.into_iter().map(|(tag, total)| (tag, from_units(total))).collect())
+}
+
+/// Total spending in the base currency and number of debits per source, as
+/// (source, total, count), largest total first.
+pub fn spend_per_source(conn: &Connection, rates: &HashMap<String, f64>) -> Result<Vec<(String, f64, usize)>> {
+    let mut stmt = conn.prepare(
+        "SELECT source, currency, COALESCE(SUM(amount_units), 0), COUNT(*)
+         FROM transactions
+         WHERE kind = 'debit'
+         GROUP BY source, currency",
+    )?;
+
+    let mut sources: HashMap<String, (i64, usize)> = HashMap::new();
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, i64>(3)? as usize,
+        ))
+    })?;
+    for row in rows {
+        let (source, currency, units, count) = row?;
+        let entry = sources.entry(source).or_default();
+        entry.0 += base_units(units, &currency, rates);
+        entry.1 += count;
+    }
+
+    let mut totals: Vec<(String, i64, usize)> =
+        sources.into_iter().map(|(source, (units, count))| (source, units, count)).collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(totals.into_iter().map(|(source, units, count)| (source, from_units(units), count)).collect())
+}
+
+// CSV export
+
+/// Default location for CSV exports: a dated file next to the database,
+/// e.g. `fitui-export-2024-06-01.csv`.
+pub fn export_path() -> std::path::PathBuf {
+    let today = chrono::Local::now().date_naive();
+    dated_export_path(&data_dir(), today)
+}
+
+/// `fitui-export-<date>.csv` inside `dir`. A second export the same day
+/// gets a `-2`, `-3`, ... suffix instead of overwriting the first.
+pub fn dated_export_path(dir: &std::path::Path, date: chrono::NaiveDate) -> std::path::PathBuf {
+    dated_path(dir, "fitui-export", date)
+}
+
+/// Default location for the chart data export, e.g.
+/// `fitui-chart-data-2024-06-01.csv`.
+pub fn chart_data_path() -> std::path::PathBuf {
+    let today = chrono::Local::now().date_naive();
+    dated_path(&data_dir(), "fitui-chart-data", today)
+}
+
+/// Default location for the per-tag summary, e.g. `fitui-tags-2024-06-01.csv`.
+pub fn tag_summary_path() -> std::path::PathBuf {
+    let today = chrono::Local::now().date_naive();
+    dated_path(&data_dir(), "fitui-tags", today)
+}
+
+fn dated_path(dir: &std::path::Path, prefix: &str, date: chrono::NaiveDate) -> std::path::PathBuf {
+    let stem = format!("{}-{}", prefix, date.format("%Y-%m-%d"));
+
+    let mut path = dir.join(format!("{}.csv", stem));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.csv", stem, n));
+        n += 1;
+    }
+
+    path
+}
+
+/// Export every stored transaction to `path`, amounts with `decimals`
+/// places. Returns the number of rows written.
+pub fn export_transactions_csv(conn: &Connection, path: &std::path::Path, decimals: usize) -> std::io::Result<usize> {
+    let transactions = get_transactions(conn).map_err(std::io::Error::other)?;
+    write_transactions_csv(&transactions, path, decimals)
+}
+
+/// Export an explicit set of transactions (e.g. the currently filtered view).
+pub fn write_transactions_csv(
+    transactions: &[Transaction],
+    path: &std::path::Path,
+    decimals: usize,
+) -> std::io::Result<usize> {
+    let mut out = String::from("id,date,source,amount,kind,tag,currency\n");
+
+    for tx in transactions {
+        out.push_str(&format!(
+            "{},{},{},{:.*},{},{},{}\n",
+            tx.id,
+            csv_field(&tx.date),
+            csv_field(&tx.source),
+            decimals,
+            tx.amount,
+            tx.kind.as_str(),
+            csv_field(tx.tag.as_str()),
+            csv_field(&tx.currency),
+        ));
+    }
+
+    fs::write(path, out)?;
+    Ok(transactions.len())
+}
+
+/// Export total spending per tag, across every stored transaction.
+/// Returns the number of tags written.
+pub fn export_tag_summary_csv(
+    conn: &Connection,
+    rates: &HashMap<String, f64>,
+    path: &std::path::Path,
+    decimals: usize,
+) -> std::io::Result<usize> {
+    let per_tag = spent_per_tag(conn, rates).map_err(std::io::Error::other)?;
+    write_tag_summary_csv(&per_tag, path, decimals)
+}
+
+/// Write `tag,total_spent` rows, largest first, followed by a total row.
+pub fn write_tag_summary_csv(
+    per_tag: &HashMap<Tag, f64>,
+    path: &std::path::Path,
+    decimals: usize,
+) -> std::io::Result<usize> {
+    let mut rows: Vec<(&Tag, &f64)> = per_tag.iter().collect();
+    rows.sort_by(|a, b| b.1.total_cmp(a.1).then_with(|| a.0.as_str().cmp(b.0.as_str())));
+
+    let mut out = String::from("tag,total_spent\n");
+    for (tag, total) in &rows {
+        out.push_str(&format!("{},{:.*}\n", csv_field(tag.as_str()), decimals, total));
+    }
+    let total = sum_amounts(rows.iter().map(|(_, t)| **t));
+    out.push_str(&format!("total,{:.*}\n", decimals, total));
+
+    fs::write(path, out)?;
+    Ok(rows.len())
+}
+
+/// Export the monthly series as tidy `month,metric,value` rows for plotting
+/// tools. Returns the number of rows written.
+pub fn export_chart_data_csv(
+    conn: &Connection,
+    rates: &HashMap<String, f64>,
+    path: &std::path::Path,
+    decimals: usize,
+) -> std::io::Result<usize> {
+    let totals = totals_per_month(conn, rates).map_err(std::io::Error::other)?;
+    let per_tag = spent_per_month_by_tag(conn, rates).map_err(std::io::Error::other)?;
+    write_chart_data_csv(&chart_data_rows(&totals, &per_tag), path, decimals)
+}
+
+/// One (month, metric, value) row per month for `earned`, `spent` and
+/// `net`, then `spent:<tag>` for each tag with spending that month.
+pub fn chart_data_rows(
+    totals: &[(String, f64, f64)],
+    per_tag: &[(String, Tag, f64)],
+) -> Vec<(String, String, f64)> {
+    let mut rows = Vec::new();
+    for (month, earned, spent) in totals {
+        rows.push((month.clone(), "earned".to_string(), *earned));
+        rows.push((month.clone(), "spent".to_string(), *spent));
+        rows.push((month.clone(), "net".to_string(), earned - spent));
+        for (_, tag, total) in per_tag.iter().filter(|(m, _, _)| m == month) {
+            rows.push((month.clone(), format!("spent:{}", tag.as_str()), *total));
+        }
+    }
+    rows
+}
+
+/// Write `month,metric,value` rows to `path`, values with `decimals` places.
+pub fn write_chart_data_csv(
+    rows: &[(String, String, f64)],
+    path: &std::path::Path,
+    decimals: usize,
+) -> std::io::Result<usize> {
+    let mut out = String::from("month,metric,value\n");
+    for (month, metric, value) in rows {
+        out.push_str(&format!("{},{},{:.*}\n", csv_field(month), csv_field(metric), decimals, value));
+    }
+
+    fs::write(path, out)?;
+    Ok(rows.len())
+}
+
+/// Quote a CSV field if it contains a delimiter, quote, or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Recurring entry functions
+pub fn get_recurring_entries(conn: &Connection) -> Result<Vec<RecurringEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, source, amount_units, kind, tag, interval, original_date, last_inserted_date, active, prorate_first
+         FROM recurring_entries
+         ORDER BY id DESC",
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(RecurringEntry {
+            id: row.get(0)?,
+            source: row.get(1)?,
+            amount: from_units(row.get(2)?),
+            kind: TransactionType::from_str(&row.get::<_, String>(3)?),
+            tag: Tag::from_str(&row.get::<_, String>(4)?),
+            interval: RecurringInterval::from_str(&row.get::<_, String>(5)?),
+            original_date: row.get(6)?,
+            last_inserted_date: row.get(7)?,
+            active: row.get::<_, i32>(8)? != 0,
+            prorate_first: row.get::<_, i32>(9)? != 0,
+        })
+    })?;
+
+    let mut entries = Vec::new();
+    for entry in rows {
+        entries.push(entry?);
+    }
+
+    Ok(entries)
+}
+
+/// Store a new rule. A non-empty `last_inserted_date` marks that period as
+/// already charged, so the rule first inserts in the period after it.
+pub fn add_recurring_entry(conn: &Connection, entry: &RecurringEntry) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO recurring_entries (source, amount, kind, tag, interval, original_date, last_inserted_date, active, amount_units, prorate_first)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        (
+            &entry.source,
+            entry.amount,
+            entry.kind.as_str(),
+            entry.tag.as_str(),
+            entry.interval.as_str(),
+            &entry.original_date,
+            &entry.last_inserted_date,
+            entry.active as i32,
+            to_units(entry.amount),
+            entry.prorate_first as i32,
+        ),
+    )?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Overwrite a rule's details. Its active flag and insertion history are
+/// left as they are.
+pub fn update_recurring_entry(conn: &Connection, entry: &RecurringEntry) -> Result<()> {
+    conn.execute(
+        "UPDATE recurring_entries
+         SET source = ?1, amount = ?2, kind = ?3, tag = ?4, interval = ?5, original_date = ?6,
+             amount_units = ?8, prorate_first = ?9
+         WHERE id = ?7",
+        (
+            entry.source.trim(),
+            entry.amount,
+            entry.kind.as_str(),
+            entry.tag.as_str(),
+            entry.interval.as_str(),
+            &entry.original_date,
+            entry.id,
+            to_units(entry.amount),
+            entry.prorate_first as i32,
+        ),
+    )?;
+    Ok(())
+}
+
+pub fn delete_recurring_entry(conn: &Connection, id: i32) -> Result<()> {
+    conn.execute("DELETE FROM recurring_entries WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+pub fn toggle_recurring_entry(conn: &Connection, id: i32, active: bool) -> Result<()> {
+    conn.execute(
+        "UPDATE recurring_entries SET active = ?1 WHERE id = ?2",
+        (if active { 1 } else { 0 }, id),
+    )?;
+    Ok(())
+}
+
+/// Whether a recurring entry should produce a transaction on `now`'s date.
+fn is_recurring_due(entry: &RecurringEntry, now: &chrono::DateTime<chrono::Local>) -> bool {
+    let today_str = now.format("%Y-%m-%d").to_string();
+    let current_week = format!("{:04}-W{:02}", now.year(), now.iso_week().week());
+    let current_month = format!("{:04}-{:02}", now.year(), now.month());
+
+    match entry.interval {
+        RecurringInterval::Daily => {
+            // Insert if we haven't inserted today
+            entry.last_inserted_date != today_str
+        }
+        RecurringInterval::Weekly => {
+            // Extract day of week from original date
+            if let Ok(original_ndt) = chrono::NaiveDate::parse_from_str(&entry.original_date, "%Y-%m-%d") {
+                let original_dow = original_ndt.weekday();
+                let today_dow = now.weekday();
+
+                // Check if this is the same day of the week and hasn't been inserted this week
+                original_dow == today_dow && entry.last_inserted_date != current_week
+            } else {
+                false
+            }
+        }
+        RecurringInterval::Monthly => {
+            // Extract day of month from original date
+            if let Ok(original_ndt) = chrono::NaiveDate::parse_from_str(&entry.original_date, "%Y-%m-%d") {
+                let original_day = original_ndt.day();
+                let today_day = now.day();
+
+                // Check if this is the same day of month and hasn't been inserted this month
+                original_day == today_day && entry.last_inserted_date != current_month
+            } else {
+                false
+            }
+        }
+    }
+}
+
+/// The `last_inserted_date` value an insertion on `date` records for
+/// `interval`.
+pub fn current_period(interval: &RecurringInterval, date: chrono::NaiveDate) -> String {
+    match interval {
+        RecurringInterval::Daily => date.format("%Y-%m-%d").to_string(),
+        RecurringInterval::Weekly => format!("{:04}-W{:02}", date.year(), date.iso_week().week()),
+        RecurringInterval::Monthly => format!("{:04}-{:02}", date.year(), date.month()),
+    }
+}
+
+/// Why a rule's insertion history can't be trusted, or `None` when it's
+/// fine. An empty history just means nothing was inserted yet.
+pub fn recurring_state_problem(
+    entry: &RecurringEntry,
+    now: &chrono::DateTime<chrono::Local>,
+) -> Option<&'static str> {
+    let last = entry.last_inserted_date.as_str();
+    if last.is_empty() {
+        return None;
+    }
+
+    let well_formed = match entry.interval {
+        RecurringInterval::Daily => chrono::NaiveDate::parse_from_str(last, "%Y-%m-%d").is_ok(),
+        RecurringInterval::Weekly => last.len() == 8
+            && last.is_ascii()
+            && last[..4].chars().all(|c| c.is_ascii_digit())
+            && &last[4..6] == "-W"
+            && last[6..].parse::<u32>().is_ok_and(|w| (1..=53).contains(&w)),
+        RecurringInterval::Monthly => {
+            chrono::NaiveDate::parse_from_str(&format!("{}-01", last), "%Y-%m-%d").is_ok()
+        }
+    };
+
+    if !well_formed {
+        Some("malformed")
+    } else if last > current_period(&entry.interval, now.date_naive()).as_str() {
+        // Every format sorts chronologically as text
+        Some("in the future")
+    } else {
+        None
+    }
+}
+
+/// Rules whose last insertion is malformed or later than today, with the
+/// reason for each. Usually a clock change or a hand-edited database.
+pub fn invalid_recurring_entries(conn: &Connection) -> Result<Vec<(RecurringEntry, &'static str)>> {
+    let now = chrono::Local::now();
+    Ok(get_recurring_entries(conn)?
+        .into_iter()
+        .filter_map(|entry| recurring_state_problem(&entry, &now).map(|reason| (entry, reason)))
+        .collect())
+}
+
+/// Forget the insertion history of the given rules, as if they were new.
+pub fn reset_recurring_state(conn: &Connection, ids: &[i32]) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut changed = 0;
+    {
+        let mut stmt = tx.prepare("UPDATE recurring_entries SET last_inserted_date = '' WHERE id = ?1")?;
+        for id in ids {
+            changed += stmt.execute([id])?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(changed)
+}
+
+/// Active recurring entries that would be inserted if
+/// `insert_recurring_transactions` ran now. Nothing is written.
+pub fn due_recurring_entries(conn: &Connection) -> Result<Vec<RecurringEntry>> {
+    let now = chrono::Local::now();
+
+    let mut due: Vec<RecurringEntry> = get_recurring_entries(conn)?
+        .into_iter()
+        .filter(|entry| entry.active && is_recurring_due(entry, &now))
+        .collect();
+
+    // Oldest rule first, matching insertion order
+    due.reverse();
+    Ok(due)
+}
+
+/// `amount` scaled by the share of `date`'s month that is left, counting
+/// `date` itself. Rounded to whole units like every stored amount.
+pub fn prorated_amount(amount: f64, date: chrono::NaiveDate) -> f64 {
+    let first = date.with_day(1).unwrap_or(date);
+    let next_month = first.checked_add_months(chrono::Months::new(1)).unwrap_or(first);
+    let days_in_month = (next_month - first).num_days().max(1);
+    let remaining = days_in_month - i64::from(date.day()) + 1;
+
+    from_units(to_units(amount * remaining as f64 / days_in_month as f64))
+}
+
+// Auto-insert recurring entries based on their interval
+pub fn insert_recurring_transactions(conn: &Connection) -> Result<()> {
+    let now = chrono::Local::now();
+    let today_str = now.format("%Y-%m-%d").to_string();
+
+    for entry in due_recurring_entries(conn)? {
+        // Insert as a transaction with today's date, linked back to its rule
+        insert_transaction(
+            conn,
+            &Transaction {
+                source: entry.source.clone(),
+                amount: entry.amount,
+                kind: entry.kind,
+                tag: entry.tag.clone(),
+                date: today_str.clone(),
+                created_by_recurring_id: Some(entry.id),
+                ..Default::default()
+            },
+        )?;
+
+        // Update the last_inserted_date based on interval
+        let new_last_inserted = current_period(&entry.interval, now.date_naive());
+
+        conn.execute(
+            "UPDATE recurring_entries SET last_inserted_date = ?1 WHERE id = ?2",
+            (new_last_inserted, entry.id),
+        )?;
+    }
+
+    Ok(())
+}
+
+
+// Keep the old function name for backwards compatibility
+pub fn insert_recurring_for_month(conn: &Connection, _current_month: &str) -> Result<()> {
+    insert_recurring_transactions(conn)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::models::{RecurringInterval, Tag, TransactionType};
+
+    fn setup_conn() -> Connection {
+        init_in_memory().expect("failed to init in-memory db")
     }
 
     #[test]
@@ -453,15 +1465,298 @@ mod tests {
         assert_eq!(earned, 100.0);
         assert_eq!(spent, 40.0);
 
-        let per_tag = spent_per_tag(&conn).unwrap();
+        let per_tag = spent_per_tag(&conn, &HashMap::new()).unwrap();
         assert_eq!(per_tag.get(&Tag::from_str("food")).copied().unwrap_or(0.0), 40.0);
+
+        add_transaction(&conn, "cafe", 4.0, TransactionType::Debit, &Tag::from_str("food"), "2026-02-24").unwrap();
+        add_transaction(&conn, "cafe", 3.5, TransactionType::Debit, &Tag::from_str("food"), "2026-02-25").unwrap();
+        assert_eq!(
+            spend_per_source(&conn, &HashMap::new()).unwrap(),
+            vec![("buy".to_string(), 40.0, 1), ("cafe".to_string(), 7.5, 2)]
+        );
+    }
+
+    #[test]
+    fn distinct_sources_most_used_first() {
+        let conn = setup_conn();
+
+        add_transaction(&conn, "Rent", 500.0, TransactionType::Debit, &Tag::from_str("housing"), "2026-02-01").unwrap();
+        add_transaction(&conn, "Groceries", 20.0, TransactionType::Debit, &Tag::from_str("food"), "2026-02-02").unwrap();
+        add_transaction(&conn, "Groceries", 30.0, TransactionType::Debit, &Tag::from_str("food"), "2026-02-09").unwrap();
+
+        assert_eq!(distinct_sources(&conn).unwrap(), vec!["Groceries", "Rent"]);
+    }
+
+    #[test]
+    fn balance_snapshot_recorded_once_per_month() {
+        let conn = setup_conn();
+
+        assert!(record_balance_snapshot(&conn, "2026-02", 100.0).unwrap());
+        assert!(!record_balance_snapshot(&conn, "2026-02", 250.0).unwrap());
+        assert!(record_balance_snapshot(&conn, "2026-01", 50.0).unwrap());
+
+        assert_eq!(
+            get_balance_snapshots(&conn).unwrap(),
+            vec![("2026-01".to_string(), 50.0), ("2026-02".to_string(), 100.0)]
+        );
+
+        set_balance_snapshot(&conn, "2026-02", 250.0).unwrap();
+        assert_eq!(get_balance_snapshots(&conn).unwrap()[1].1, 250.0);
+    }
+
+    #[test]
+    fn source_variants_merge_into_most_used() {
+        let conn = setup_conn();
+        let food = Tag::from_str("food");
+
+        add_transaction(&conn, "Groceries", 1.0, TransactionType::Debit, &food, "2026-02-01").unwrap();
+        add_transaction(&conn, "Groceries", 2.0, TransactionType::Debit, &food, "2026-02-02").unwrap();
+        add_transaction(&conn, "Rent", 3.0, TransactionType::Debit, &food, "2026-02-03").unwrap();
+        // add_transaction trims, so store the stray spellings directly
+        for source in ["groceries", "Groceries  ", " Rent"] {
+            insert_transaction(&conn, &Transaction {
+                source: source.into(),
+                amount: 1.0,
+                tag: food.clone(),
+                date: "2026-02-04".into(),
+                ..Default::default()
+            })
+            .unwrap();
+        }
+
+        let mut merges = source_variants(&conn).unwrap();
+        for (_, variants) in merges.iter_mut() {
+            variants.sort();
+        }
+        assert_eq!(
+            merges,
+            vec![
+                ("Groceries".to_string(), vec!["Groceries  ".to_string(), "groceries".to_string()]),
+                ("Rent".to_string(), vec![" Rent".to_string()]),
+            ]
+        );
+
+        assert_eq!(merge_sources(&conn, &merges).unwrap(), 3);
+        assert!(source_variants(&conn).unwrap().is_empty());
+        assert_eq!(distinct_sources(&conn).unwrap(), vec!["Groceries", "Rent"]);
+    }
+
+    #[test]
+    fn net_per_month_for_year_skips_empty_months() {
+        let conn = setup_conn();
+        let tag = Tag::from_str("other");
+
+        add_transaction(&conn, "pay", 100.0, TransactionType::Credit, &tag, "2026-01-05").unwrap();
+        add_transaction(&conn, "rent", 40.0, TransactionType::Debit, &tag, "2026-01-06").unwrap();
+        add_transaction(&conn, "trip", 70.0, TransactionType::Debit, &tag, "2026-03-01").unwrap();
+        add_transaction(&conn, "old", 5.0, TransactionType::Debit, &tag, "2025-03-01").unwrap();
+
+        assert_eq!(
+            net_per_month_for_year(&conn, 2026, &HashMap::new()).unwrap(),
+            vec![(1, 60.0), (3, -70.0)]
+        );
+    }
+
+    #[test]
+    fn rollover_recorded_once_per_month() {
+        let conn = setup_conn();
+
+        assert!(record_rollover(&conn, "2026-02").unwrap());
+        assert!(!record_rollover(&conn, "2026-02").unwrap());
+        assert!(record_rollover(&conn, "2026-03").unwrap());
+    }
+
+    #[test]
+    fn retag_only_touches_given_ids() {
+        let conn = setup_conn();
+
+        add_transaction(&conn, "a", 1.0, TransactionType::Debit, &Tag::from_str("other"), "2026-02-01").unwrap();
+        add_transaction(&conn, "b", 2.0, TransactionType::Debit, &Tag::from_str("other"), "2026-02-02").unwrap();
+        add_transaction(&conn, "c", 3.0, TransactionType::Debit, &Tag::from_str("other"), "2026-02-03").unwrap();
+
+        let ids: Vec<i32> = get_transactions(&conn)
+            .unwrap()
+            .iter()
+            .filter(|t| t.source != "b")
+            .map(|t| t.id)
+            .collect();
+
+        let changed = retag_transactions(&conn, &ids, &Tag::from_str("subscriptions")).unwrap();
+        assert_eq!(changed, 2);
+
+        for t in get_transactions(&conn).unwrap() {
+            let expected = if t.source == "b" { "other" } else { "subscriptions" };
+            assert_eq!(t.tag.as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn set_cleared_round_trips() {
+        let conn = setup_conn();
+
+        add_transaction(&conn, "a", 1.0, TransactionType::Debit, &Tag::from_str("other"), "2026-02-01").unwrap();
+        add_transaction(&conn, "b", 2.0, TransactionType::Debit, &Tag::from_str("other"), "2026-02-02").unwrap();
+        assert!(get_transactions(&conn).unwrap().iter().all(|t| !t.cleared));
+
+        let a = get_transactions(&conn).unwrap().into_iter().find(|t| t.source == "a").unwrap();
+        assert_eq!(set_cleared(&conn, &[a.id], true).unwrap(), 1);
+
+        for t in get_transactions(&conn).unwrap() {
+            assert_eq!(t.cleared, t.source == "a");
+        }
+
+        set_cleared(&conn, &[a.id], false).unwrap();
+        assert!(get_transactions(&conn).unwrap().iter().all(|t| !t.cleared));
+    }
+
+    #[test]
+    fn total_for_tag_splits_debits_and_credits() {
+        let conn = setup_conn();
+        let food = Tag::from_str("food");
+
+        assert_eq!(total_for_tag(&conn, &food, &HashMap::new()).unwrap(), (0.0, 0.0));
+
+        add_transaction(&conn, "lunch", 12.0, TransactionType::Debit, &food, "2026-01-05").unwrap();
+        add_transaction(&conn, "dinner", 8.0, TransactionType::Debit, &food, "2026-02-05").unwrap();
+        add_transaction(&conn, "refund", 5.0, TransactionType::Credit, &food, "2026-02-06").unwrap();
+        add_transaction(&conn, "train", 30.0, TransactionType::Debit, &Tag::from_str("travel"), "2026-02-07").unwrap();
+
+        assert_eq!(total_for_tag(&conn, &food, &HashMap::new()).unwrap(), (20.0, 5.0));
+    }
+
+    #[test]
+    fn aggregates_convert_foreign_rows_to_base() {
+        let conn = setup_conn();
+        let food = Tag::from_str("food");
+        let rates: HashMap<String, f64> = [("EUR".to_string(), 2.0)].into_iter().collect();
+        let row = |source: &str, amount, kind, currency: &str| Transaction {
+            source: source.into(),
+            amount,
+            kind,
+            tag: food.clone(),
+            date: "2026-01-05".into(),
+            currency: currency.into(),
+            ..Default::default()
+        };
+        insert_transaction(&conn, &row("cafe", 10.0, TransactionType::Debit, "")).unwrap();
+        insert_transaction(&conn, &row("cafe", 10.0, TransactionType::Debit, "EUR")).unwrap();
+        insert_transaction(&conn, &row("refund", 5.0, TransactionType::Credit, "EUR")).unwrap();
+
+        assert_eq!(spent_per_tag(&conn, &rates).unwrap()[&food], 30.0);
+        assert_eq!(total_for_tag(&conn, &food, &rates).unwrap(), (30.0, 10.0));
+        assert_eq!(spent_per_tag_per_month(&conn, &food, &rates).unwrap(), vec![("2026-01".to_string(), 30.0)]);
+        assert_eq!(spent_per_month_by_tag(&conn, &rates).unwrap(), vec![("2026-01".to_string(), food.clone(), 30.0)]);
+        assert_eq!(totals_per_month(&conn, &rates).unwrap(), vec![("2026-01".to_string(), 10.0, 30.0)]);
+        assert_eq!(net_per_month_for_year(&conn, 2026, &rates).unwrap(), vec![(1, -20.0)]);
+        assert_eq!(spend_per_source(&conn, &rates).unwrap(), vec![("cafe".to_string(), 30.0, 2)]);
+
+        // The same figure in another currency is a different transaction
+        assert!(transaction_exists(&conn, "refund", 5.0, "EUR", "2026-01-05").unwrap());
+        assert!(!transaction_exists(&conn, "refund", 5.0, "", "2026-01-05").unwrap());
+    }
+
+    #[test]
+    fn spent_per_tag_per_month_groups_debits() {
+        let conn = setup_conn();
+
+        add_transaction(&conn, "a", 10.0, TransactionType::Debit, &Tag::from_str("food"), "2026-01-03").unwrap();
+        add_transaction(&conn, "b", 15.0, TransactionType::Debit, &Tag::from_str("food"), "2026-01-20").unwrap();
+        add_transaction(&conn, "c", 7.0, TransactionType::Debit, &Tag::from_str("food"), "2026-03-02").unwrap();
+        add_transaction(&conn, "d", 99.0, TransactionType::Debit, &Tag::from_str("rent"), "2026-03-01").unwrap();
+        add_transaction(&conn, "e", 50.0, TransactionType::Credit, &Tag::from_str("food"), "2026-03-05").unwrap();
+
+        let series = spent_per_tag_per_month(&conn, &Tag::from_str("food"), &HashMap::new()).unwrap();
+        assert_eq!(series, vec![("2026-01".to_string(), 25.0), ("2026-03".to_string(), 7.0)]);
+    }
+
+    #[test]
+    fn chart_data_is_tidy_month_metric_value() {
+        let conn = setup_conn();
+
+        add_transaction(&conn, "pay", 1000.0, TransactionType::Credit, &Tag::from_str("salary"), "2026-01-01").unwrap();
+        add_transaction(&conn, "a", 10.0, TransactionType::Debit, &Tag::from_str("food"), "2026-01-03").unwrap();
+        add_transaction(&conn, "b", 90.0, TransactionType::Debit, &Tag::from_str("rent, flat"), "2026-01-04").unwrap();
+        add_transaction(&conn, "c", 7.5, TransactionType::Debit, &Tag::from_str("food"), "2026-02-02").unwrap();
+
+        let path = std::env::temp_dir().join(format!("fitui-chart-{}.csv", std::process::id()));
+        assert_eq!(export_chart_data_csv(&conn, &HashMap::new(), &path, 2).unwrap(), 9);
+        let csv = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            csv,
+            "month,metric,value\n\
+             2026-01,earned,1000.00\n\
+             2026-01,spent,100.00\n\
+             2026-01,net,900.00\n\
+             2026-01,spent:food,10.00\n\
+             2026-01,\"spent:rent, flat\",90.00\n\
+             2026-02,earned,0.00\n\
+             2026-02,spent,7.50\n\
+             2026-02,net,-7.50\n\
+             2026-02,spent:food,7.50\n"
+        );
+    }
+
+    #[test]
+    fn scale_amounts_only_touches_listed_rows() {
+        let conn = setup_conn();
+        add_transaction(&conn, "rent", 1000.0, TransactionType::Debit, &Tag::from_str("home"), "2026-03-01").unwrap();
+        add_transaction(&conn, "tea", 12.34, TransactionType::Debit, &Tag::from_str("food"), "2026-03-02").unwrap();
+        add_transaction(&conn, "left", 50.0, TransactionType::Debit, &Tag::from_str("food"), "2026-03-03").unwrap();
+
+        let rows = get_transactions(&conn).unwrap();
+        let ids: Vec<i32> = rows.iter().filter(|t| t.source != "left").map(|t| t.id).collect();
+        assert_eq!(scale_amounts(&conn, &ids, 1.05, 2, Rounding::HalfUp).unwrap(), 2);
+
+        let amount = |source: &str| {
+            get_transactions(&conn).unwrap().into_iter().find(|t| t.source == source).unwrap().amount
+        };
+        assert_eq!(amount("rent"), 1050.0);
+        // 12.957 kept to the displayed places
+        assert_eq!(amount("tea"), 12.96);
+        assert_eq!(amount("left"), 50.0);
+    }
+
+    #[test]
+    fn rows_from_older_builds_get_their_units_filled() {
+        let conn = setup_conn();
+        // An older FiTui only writes the REAL amount
+        conn.execute(
+            "INSERT INTO transactions (source, amount, kind, tag, date) VALUES ('old', 12.34, 'debit', 'food', '2026-03-01')",
+            [],
+        )
+        .unwrap();
+        add_transaction(&conn, "new", 1.0, TransactionType::Debit, &Tag::from_str("food"), "2026-03-02").unwrap();
+        assert_eq!(total_spent(&conn).unwrap(), 1.0);
+
+        migrate_amount_units(&conn, "transactions");
+        assert_eq!(total_spent(&conn).unwrap(), 13.34);
+    }
+
+    #[test]
+    fn restored_rows_keep_their_ids() {
+        let conn = setup_conn();
+        add_transaction(&conn, "keep", 1.0, TransactionType::Debit, &Tag::from_str("food"), "2026-03-01").unwrap();
+        add_transaction(&conn, "gone", 2.0, TransactionType::Debit, &Tag::from_str("food"), "2026-03-02").unwrap();
+
+        let mut gone = get_transactions(&conn).unwrap().remove(0);
+        gone.note = "#back".into();
+        delete_transaction(&conn, gone.id).unwrap();
+        restore_transactions(&conn, std::slice::from_ref(&gone)).unwrap();
+
+        let rows = get_transactions(&conn).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].id, gone.id);
+        assert_eq!(rows[0].source, "gone");
+        assert_eq!(note_tags(&conn).unwrap()[&gone.id], vec!["back".to_string()]);
     }
 
     #[test]
     fn recurring_roundtrip() {
         let conn = setup_conn();
 
-        add_recurring_entry(&conn, "rent", 500.0, TransactionType::Debit, &Tag::from_str("housing"), &RecurringInterval::Monthly, "2026-02-23").unwrap();
+        add_recurring_entry(&conn, &RecurringEntry::new("rent", 500.0, TransactionType::Debit, Tag::from_str("housing"), RecurringInterval::Monthly, "2026-02-23")).unwrap();
 
         let entries = get_recurring_entries(&conn).unwrap();
         assert_eq!(entries.len(), 1);
@@ -469,4 +1764,190 @@ mod tests {
         assert_eq!(entries[0].amount, 500.0);
         assert_eq!(entries[0].interval, RecurringInterval::Monthly);
     }
+    #[test]
+    fn recurring_state_problems_are_detected() {
+        use chrono::TimeZone;
+
+        let now = chrono::Local.with_ymd_and_hms(2026, 3, 15, 12, 0, 0).unwrap();
+        let entry = |interval, last: &str| RecurringEntry {
+            id: 1,
+            source: "rent".into(),
+            amount: 1.0,
+            kind: TransactionType::Debit,
+            tag: Tag::from_str("bills"),
+            interval,
+            original_date: "2026-01-01".into(),
+            last_inserted_date: last.into(),
+            active: true,
+            prorate_first: false,
+        };
+
+        assert_eq!(recurring_state_problem(&entry(RecurringInterval::Monthly, ""), &now), None);
+        assert_eq!(recurring_state_problem(&entry(RecurringInterval::Monthly, "2026-03"), &now), None);
+        assert_eq!(recurring_state_problem(&entry(RecurringInterval::Monthly, "2026-04"), &now), Some("in the future"));
+        assert_eq!(recurring_state_problem(&entry(RecurringInterval::Monthly, "2026-13"), &now), Some("malformed"));
+        assert_eq!(recurring_state_problem(&entry(RecurringInterval::Weekly, "2026-W11"), &now), None);
+        assert_eq!(recurring_state_problem(&entry(RecurringInterval::Weekly, "2026-W60"), &now), Some("malformed"));
+        assert_eq!(recurring_state_problem(&entry(RecurringInterval::Daily, "2026-03-16"), &now), Some("in the future"));
+        assert_eq!(recurring_state_problem(&entry(RecurringInterval::Daily, "yesterday"), &now), Some("malformed"));
+    }
+
+    #[test]
+    fn prorated_amount_counts_the_days_left() {
+        let date = |s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        // 20 of 30 April days remain, counting the 11th
+        assert_eq!(prorated_amount(300.0, date("2026-04-11")), 200.0);
+        assert_eq!(prorated_amount(300.0, date("2026-04-01")), 300.0);
+        assert_eq!(prorated_amount(280.0, date("2026-02-28")), 10.0);
+    }
+
+    #[test]
+    fn a_rule_added_as_charged_waits_for_the_next_period() {
+        let conn = setup_conn();
+        let today = chrono::Local::now().date_naive();
+        let entry = RecurringEntry {
+            last_inserted_date: current_period(&RecurringInterval::Monthly, today),
+            prorate_first: true,
+            ..RecurringEntry::new("gym", 300.0, TransactionType::Debit, Tag::from_str("health"), RecurringInterval::Monthly, &today.format("%Y-%m-%d").to_string())
+        };
+        add_recurring_entry(&conn, &entry).unwrap();
+        assert!(get_recurring_entries(&conn).unwrap()[0].prorate_first);
+
+        insert_recurring_transactions(&conn).unwrap();
+        assert!(get_transactions(&conn).unwrap().is_empty());
+
+        // Later months, and a reset history, charge the full amount
+        conn.execute("UPDATE recurring_entries SET last_inserted_date = ''", []).unwrap();
+        insert_recurring_transactions(&conn).unwrap();
+        assert_eq!(get_transactions(&conn).unwrap()[0].amount, 300.0);
+    }
+
+
+    #[test]
+    fn note_hashtags_follow_their_transaction() {
+        let conn = setup_conn();
+        assert_eq!(
+            parse_note_tags("#Work lunch, #work #tax-2026! # #x#y"),
+            vec!["work", "tax-2026", "x"]
+        );
+
+        let id = insert_transaction(&conn, &Transaction {
+            source: "cafe".into(),
+            amount: 12.0,
+            note: "client lunch #work #refundable".into(),
+            ..Default::default()
+        }).unwrap() as i32;
+        assert_eq!(note_tags(&conn).unwrap()[&id], vec!["refundable", "work"]);
+
+        let mut tx = get_transactions(&conn).unwrap()[0].clone();
+        assert_eq!(tx.note, "client lunch #work #refundable");
+        tx.note = "personal after all".into();
+        replace_transaction(&conn, &tx).unwrap();
+        assert!(note_tags(&conn).unwrap().is_empty());
+
+        tx.note = "#work".into();
+        replace_transaction(&conn, &tx).unwrap();
+        delete_transaction(&conn, id).unwrap();
+        assert!(note_tags(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn transaction_currency_roundtrip() {
+        let conn = setup_conn();
+
+        let id = insert_transaction(&conn, &Transaction {
+            source: "hotel".into(),
+            amount: 5000.0,
+            kind: TransactionType::Debit,
+            tag: Tag::from_str("travel"),
+            date: "2026-02-23".into(),
+            currency: "INR".into(),
+            ..Default::default()
+        }).unwrap();
+
+        let txs = get_transactions(&conn).unwrap();
+        assert_eq!(txs[0].id as i64, id);
+        assert_eq!(txs[0].currency, "INR");
+
+        let mut tx = txs[0].clone();
+        tx.currency = "USD".into();
+        replace_transaction(&conn, &tx).unwrap();
+        assert_eq!(get_transactions(&conn).unwrap()[0].currency, "USD");
+
+        tx.receipt_path = "/tmp/receipt.jpg".into();
+        replace_transaction(&conn, &tx).unwrap();
+        assert_eq!(get_transactions(&conn).unwrap()[0].receipt_path, "/tmp/receipt.jpg");
+
+        let mut rates = HashMap::new();
+        rates.insert("USD".to_string(), 80.0);
+        assert_eq!(tx.base_amount(&rates), 400000.0);
+    }
+
+    #[test]
+    fn dated_export_path_avoids_overwriting() {
+        let dir = std::env::temp_dir().join(format!("fitui-export-dir-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+
+        let first = dated_export_path(&dir, date);
+        assert_eq!(first.file_name().unwrap(), "fitui-export-2024-06-01.csv");
+
+        fs::write(&first, "").unwrap();
+        let second = dated_export_path(&dir, date);
+        assert_eq!(second.file_name().unwrap(), "fitui-export-2024-06-01-2.csv");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn tag_summary_csv_sorts_and_totals() {
+        let conn = setup_conn();
+
+        add_transaction(&conn, "lunch", 12.0, TransactionType::Debit, &Tag::from_str("food"), "2026-02-20").unwrap();
+        add_transaction(&conn, "dinner", 8.0, TransactionType::Debit, &Tag::from_str("food"), "2026-02-21").unwrap();
+        add_transaction(&conn, "train", 30.0, TransactionType::Debit, &Tag::from_str("travel"), "2026-02-22").unwrap();
+        add_transaction(&conn, "pay", 100.0, TransactionType::Credit, &Tag::from_str("salary"), "2026-02-24").unwrap();
+
+        let path = std::env::temp_dir().join(format!("fitui-tags-{}.csv", std::process::id()));
+        let written = export_tag_summary_csv(&conn, &HashMap::new(), &path, 2).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+
+        assert_eq!(written, 2);
+        assert_eq!(text, "tag,total_spent\ntravel,30.00\nfood,20.00\ntotal,50.00\n");
+
+        // Amounts follow decimal_places
+        export_tag_summary_csv(&conn, &HashMap::new(), &path, 0).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(text, "tag,total_spent\ntravel,30\nfood,20\ntotal,50\n");
+    }
+
+    #[test]
+    fn csv_export_writes_given_rows() {
+        let conn = setup_conn();
+
+        add_transaction(&conn, "Coffee, large", 4.5, TransactionType::Debit, &Tag::from_str("food"), "2026-02-23").unwrap();
+        add_transaction(&conn, "pay", 100.0, TransactionType::Credit, &Tag::from_str("salary"), "2026-02-24").unwrap();
+
+        let path = std::env::temp_dir().join(format!("fitui-export-{}.csv", std::process::id()));
+
+        // Full export
+        let written = export_transactions_csv(&conn, &path, 2).unwrap();
+        assert_eq!(written, 2);
+
+        // Explicit slice export only writes what it's given
+        let txs = get_transactions(&conn).unwrap();
+        let only_food: Vec<_> = txs.into_iter().filter(|t| t.tag.as_str() == "food").collect();
+        let written = write_transactions_csv(&only_food, &path, 2).unwrap();
+        assert_eq!(written, 1);
+
+        let text = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "id,date,source,amount,kind,tag,currency");
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains("\"Coffee, large\""));
+        assert!(lines[1].ends_with("4.50,debit,food,"));
+    }
 }
\ No newline at end of file
@@ -1,14 +1,22 @@
+use std::collections::HashMap;
+
+use chrono::{Days, NaiveDate};
+
 use crate::models::{RecurringInterval, TransactionType};
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum Field {
     Source,
     Amount,
+    Currency,
     Kind,
     Tag,
     Date,
+    Receipt,
+    Note,
     Recurring,
     RecurringInterval,
+    Prorate,
 }
 
 // Canonical visual/focus order for the form fields. Use this as the single
@@ -16,11 +24,15 @@ pub enum Field {
 pub const FIELD_ORDER: &[Field] = &[
     Field::Source,
     Field::Amount,
+    Field::Currency,
     Field::Date,
     Field::Kind,
     Field::Tag,
+    Field::Receipt,
+    Field::Note,
     Field::Recurring,
     Field::RecurringInterval,
+    Field::Prorate,
 ];
 
 impl Field {
@@ -56,15 +68,36 @@ impl Field {
 pub struct TransactionForm {
     pub source: String,
     pub amount: String,
+    // Empty means the configured base currency
+    pub currency: String,
     pub kind: TransactionType,
 
     // Index into the dynamically loaded config tags
     pub tag_index: usize,
+    // Where `tag_index` starts after a reset, from the `default_tag` config
+    pub default_tag_index: usize,
 
     pub date: String,
+    // Optional path to a receipt image
+    pub receipt_path: String,
+    // Free text; #hashtags in it can be searched for
+    pub note: String,
     pub recurring: bool,
     pub recurring_interval: RecurringInterval,
+    // Monthly rules only: charge just the rest of the first month
+    pub prorate_first: bool,
     pub active: Field,
+
+    // Why the last save attempt was rejected, shown until the next edit
+    pub error: Option<String>,
+    // Correction the error offers for a day-first date; taken by the next save
+    pub date_fix: Option<String>,
+}
+
+impl Default for TransactionForm {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TransactionForm {
@@ -72,29 +105,160 @@ impl TransactionForm {
         Self {
             source: String::new(),
             amount: String::new(),
+            currency: String::new(),
             kind: TransactionType::Debit,
             tag_index: 0,
+            default_tag_index: 0,
             date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            receipt_path: String::new(),
+            note: String::new(),
             recurring: false,
             recurring_interval: RecurringInterval::Monthly,
+            prorate_first: false,
             active: Field::Source,
+            error: None,
+            date_fix: None,
         }
     }
 
     pub fn reset(&mut self) {
+        let default_tag_index = self.default_tag_index;
         *self = Self::new();
+        self.default_tag_index = default_tag_index;
+        self.tag_index = default_tag_index;
+    }
+
+    /// Move focus to `field`, normalizing the date when leaving it.
+    pub fn focus(&mut self, field: Field) {
+        if self.active == Field::Date && field != Field::Date {
+            self.normalize_date();
+        }
+        self.active = field;
+    }
+
+    /// Expand relative shortcuts (`today`, `yesterday`, `-3`) in the Date
+    /// field into a concrete `YYYY-MM-DD`, and tidy year-first dates typed
+    /// with other separators (`2024/6/1`). Anything else is left as typed.
+    pub fn normalize_date(&mut self) {
+        let today = chrono::Local::now().date_naive();
+        if let Some(normalized) =
+            expand_relative_date(&self.date, today).or_else(|| correct_date_format(&self.date))
+        {
+            self.date = normalized;
+        }
+    }
+
+    /// Check the fields before saving. Returns the message to show the user
+    /// when something would produce a broken row.
+    pub fn validate(&self) -> Result<(), String> {
+        // Dates are compared as text, so anything but YYYY-MM-DD sorts wrong
+        if NaiveDate::parse_from_str(&self.date, "%Y-%m-%d").is_err() || self.date.len() != 10 {
+            return Err(match day_first_suggestion(&self.date) {
+                Some(fixed) => format!("Dates are YYYY-MM-DD; did you mean {}?", fixed),
+                None => format!("'{}' isn't a YYYY-MM-DD date", self.date),
+            });
+        }
+
+        if self.source.trim().is_empty() {
+            return Err("Source can't be empty".into());
+        }
+
+        Ok(())
+    }
+
+    /// The first `history` entry that is longer than the current source and
+    /// starts with it (case-insensitive, char by char), with the byte index
+    /// where the typed part ends.
+    fn completion_candidate<'a>(&self, history: &'a [String]) -> Option<(&'a str, usize)> {
+        if self.source.is_empty() {
+            return None;
+        }
+
+        history.iter().find_map(|candidate| {
+            let mut chars = candidate.char_indices();
+            for typed in self.source.chars() {
+                let (_, c) = chars.next()?;
+                if !c.to_lowercase().eq(typed.to_lowercase()) {
+                    return None;
+                }
+            }
+            let (split, _) = chars.next()?;
+            Some((candidate.as_str(), split))
+        })
+    }
+
+    /// The untyped rest of the first `history` entry that starts with the
+    /// current source (case-insensitive), offered as an inline completion.
+    pub fn source_completion<'a>(&self, history: &'a [String]) -> Option<&'a str> {
+        self.completion_candidate(history).map(|(candidate, split)| &candidate[split..])
+    }
+
+    /// Replace the source with the completed entry, in its stored case.
+    /// Returns whether anything was accepted so the key can fall through
+    /// otherwise.
+    pub fn accept_source_completion(&mut self, history: &[String]) -> bool {
+        if self.active != Field::Source {
+            return false;
+        }
+
+        match self.completion_candidate(history) {
+            Some((candidate, _)) => {
+                self.source = candidate.to_string();
+                true
+            }
+            None => false,
+        }
     }
 
     pub fn push_char(&mut self, c: char) {
+        self.error = None;
+        self.date_fix = None;
         match self.active {
             Field::Source => self.source.push(c),
-            Field::Amount => self.amount.push(c),
+            Field::Amount => {
+                self.amount.push(c);
+                self.sync_kind_with_amount();
+            }
+            Field::Currency => self.currency.push(c),
             Field::Date => self.date.push(c),
+            Field::Receipt => self.receipt_path.push(c),
+            Field::Note => self.note.push(c),
             _ => {}
         }
     }
 
+    /// A leading `-` on the amount means an expense, so force the kind to
+    /// Debit while it's there. The sign and the type can never disagree.
+    fn sync_kind_with_amount(&mut self) {
+        if self.amount.trim_start().starts_with('-') {
+            self.kind = TransactionType::Debit;
+        }
+    }
+
+    /// Nudge the amount by `delta`, never below zero. A leading `-` (expense)
+    /// is kept.
+    pub fn adjust_amount(&mut self, delta: f64) {
+        let negative = self.amount.trim_start().starts_with('-');
+        let value = (self.amount_value() + delta).max(0.0);
+
+        let digits = if value.fract() == 0.0 {
+            format!("{:.0}", value)
+        } else {
+            format!("{:.2}", value)
+        };
+        self.amount = if negative && value > 0.0 { format!("-{}", digits) } else { digits };
+        self.error = None;
+    }
+
+    /// Parsed amount as stored in the database: always non-negative, the
+    /// direction lives in `kind`.
+    pub fn amount_value(&self) -> f64 {
+        self.amount.trim().parse::<f64>().unwrap_or(0.0).abs()
+    }
+
     pub fn pop_char(&mut self) {
+        self.error = None;
+        self.date_fix = None;
         match self.active {
             Field::Source => {
                 self.source.pop();
@@ -102,9 +266,18 @@ impl TransactionForm {
             Field::Amount => {
                 self.amount.pop();
             }
+            Field::Currency => {
+                self.currency.pop();
+            }
             Field::Date => {
                 self.date.pop();
             }
+            Field::Receipt => {
+                self.receipt_path.pop();
+            }
+            Field::Note => {
+                self.note.pop();
+            }
             _ => {}
         }
     }
@@ -114,12 +287,24 @@ impl TransactionForm {
             TransactionType::Credit => TransactionType::Debit,
             TransactionType::Debit => TransactionType::Credit,
         };
+
+        // Switching to Credit drops a typed minus sign so it can't contradict
+        if self.kind == TransactionType::Credit {
+            let trimmed = self.amount.trim_start();
+            if let Some(rest) = trimmed.strip_prefix('-') {
+                self.amount = rest.to_string();
+            }
+        }
     }
 
     pub fn toggle_recurring(&mut self) {
         self.recurring = !self.recurring;
     }
 
+    pub fn toggle_prorate(&mut self) {
+        self.prorate_first = !self.prorate_first;
+    }
+
     pub fn next_interval(&mut self) {
         self.recurring_interval = self.recurring_interval.next();
     }
@@ -149,10 +334,158 @@ impl TransactionForm {
     }
 }
 
+/// Rewrite a year-first date typed with `/`, `.` or `-` and optional zero
+/// padding (`2024/6/1`, `2024.06.01`) as `YYYY-MM-DD`. `None` unless it
+/// names a real day.
+pub fn correct_date_format(input: &str) -> Option<String> {
+    let parts: Vec<&str> = input.trim().split(['-', '/', '.']).collect();
+    let [year, month, day] = parts.as_slice() else {
+        return None;
+    };
+    if year.len() != 4 || !(1..=2).contains(&month.len()) || !(1..=2).contains(&day.len()) {
+        return None;
+    }
+    let date = NaiveDate::from_ymd_opt(parse_digits(year)?, parse_digits(month)?, parse_digits(day)?)?;
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
+/// The `YYYY-MM-DD` a day-first date such as `15/06/2024` must mean. Only
+/// offered when the first number is too big to be a month, since
+/// `01/06/2024` could be either order.
+pub fn day_first_suggestion(input: &str) -> Option<String> {
+    let parts: Vec<&str> = input.trim().split(['-', '/', '.']).collect();
+    let [day, month, year] = parts.as_slice() else {
+        return None;
+    };
+    if year.len() != 4 || day.len() > 2 || month.len() > 2 {
+        return None;
+    }
+    let day = parse_digits(day)?;
+    if day <= 12 {
+        return None;
+    }
+    let date = NaiveDate::from_ymd_opt(parse_digits(year)?, parse_digits(month)?, day)?;
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
+// Plain digits only; `str::parse` would also take a leading `+`
+fn parse_digits<T: std::str::FromStr>(s: &str) -> Option<T> {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// An amount as the form shows it for editing: at least `decimals`
+/// places, but never fewer than it's stored with, so saving an edit that
+/// leaves the amount alone doesn't round it.
+pub fn amount_for_editing(amount: f64, decimals: usize) -> String {
+    let full = format!("{:.4}", crate::money::from_units(crate::money::to_units(amount)));
+    let (whole, fraction) = full.split_once('.').unwrap_or((&full, ""));
+    let fraction = fraction.trim_end_matches('0');
+    let places = fraction.len().max(decimals);
+
+    if places == 0 {
+        whole.to_string()
+    } else {
+        format!("{}.{:0<places$}", whole, fraction)
+    }
+}
+
+/// Check a transaction's currency code. Blank means the base currency;
+/// anything else needs a configured exchange rate, since an unknown code
+/// would be counted 1:1 in every total.
+pub fn validate_currency(code: &str, base: &str, rates: &HashMap<String, f64>) -> Result<(), String> {
+    let code = code.trim();
+    if code.is_empty() || code == base || rates.contains_key(code) {
+        return Ok(());
+    }
+
+    let mut known: Vec<&str> = rates.keys().map(String::as_str).collect();
+    known.sort_unstable();
+    Err(if known.is_empty() {
+        format!("No exchange rate for '{}'; leave the currency blank", code)
+    } else {
+        format!("No exchange rate for '{}' (configured: {})", code, known.join(", "))
+    })
+}
+
+/// Largest percentage increase [`parse_percent_change`] accepts.
+pub const MAX_PERCENT_CHANGE: f64 = 1000.0;
+
+/// Parse a percentage change such as `5`, `+2.5%` or `-10` into the factor
+/// amounts are multiplied by (1.05, 1.025, 0.9).
+pub fn parse_percent_change(input: &str) -> Result<f64, String> {
+    let trimmed = input.trim();
+    let number = trimmed.strip_suffix('%').unwrap_or(trimmed).trim();
+    let percent: f64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a percentage, e.g. 5 or -10", trimmed))?;
+
+    if !percent.is_finite() || percent == 0.0 {
+        return Err("Enter a non-zero percentage".into());
+    }
+    if percent <= -100.0 {
+        return Err("Amounts can't drop by 100% or more".into());
+    }
+    if percent > MAX_PERCENT_CHANGE {
+        return Err(format!("Amounts can't grow by more than {}%", MAX_PERCENT_CHANGE));
+    }
+
+    Ok(1.0 + percent / 100.0)
+}
+
+/// Turn a relative date shortcut into an ISO date relative to `today`:
+/// `today`, `yesterday`, or `-N` for N days ago. Returns `None` for
+/// anything else, including explicit `YYYY-MM-DD` dates and offsets that
+/// reach past the earliest representable date.
+pub fn expand_relative_date(input: &str, today: NaiveDate) -> Option<String> {
+    let input = input.trim().to_lowercase();
+
+    let days_ago = match input.as_str() {
+        "today" => 0,
+        "yesterday" => 1,
+        _ => {
+            let digits = input.strip_prefix('-')?;
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            digits.parse::<u32>().ok()?
+        }
+    };
+
+    let date = today.checked_sub_days(Days::new(days_ago.into()))?;
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::{RecurringInterval, TransactionType};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn amount_for_editing_keeps_stored_precision() {
+        assert_eq!(amount_for_editing(12.5, 2), "12.50");
+        assert_eq!(amount_for_editing(12.5, 0), "12.5");
+        assert_eq!(amount_for_editing(12.0, 0), "12");
+        assert_eq!(amount_for_editing(3.1234, 2), "3.1234");
+        assert_eq!(amount_for_editing(0.1 + 0.2, 2), "0.30");
+    }
+
+    #[test]
+    fn percent_change_parses_signs_and_suffix() {
+        assert_eq!(parse_percent_change("5"), Ok(1.05));
+        assert_eq!(parse_percent_change(" +2.5% "), Ok(1.025));
+        assert_eq!(parse_percent_change("-10"), Ok(0.9));
+        assert!(parse_percent_change("0").is_err());
+        assert!(parse_percent_change("-100").is_err());
+        assert_eq!(parse_percent_change("1000"), Ok(11.0));
+        assert!(parse_percent_change("1000.5").is_err());
+        assert!(parse_percent_change("99999999999999999999").is_err());
+        assert!(parse_percent_change("ten").is_err());
+        assert!(parse_percent_change("").is_err());
+    }
 
     #[test]
     fn field_next_wraps() {
@@ -165,6 +498,94 @@ mod tests {
         assert_eq!(f, Field::Source);
     }
 
+    #[test]
+    fn field_back_wraps() {
+        assert_eq!(Field::Source.back(), *FIELD_ORDER.last().unwrap());
+        assert_eq!(Field::Amount.back(), Field::Source);
+        // back() undoes next() for every field
+        for &f in FIELD_ORDER {
+            assert_eq!(f.next().back(), f);
+        }
+    }
+
+    #[test]
+    fn adjust_amount_steps_and_keeps_sign() {
+        let mut form = TransactionForm::new();
+        form.adjust_amount(10.0);
+        assert_eq!(form.amount, "10");
+
+        form.amount = "-2.5".into();
+        form.adjust_amount(1.0);
+        assert_eq!(form.amount, "-3.50");
+
+        form.adjust_amount(-100.0);
+        assert_eq!(form.amount, "0");
+    }
+
+    #[test]
+    fn validate_rejects_malformed_dates() {
+        let mut form = TransactionForm { source: "Rent".into(), ..TransactionForm::new() };
+        for bad in ["2024/06/01", "2024-6-1", "2024-02-30", "soon", "15/06/2024"] {
+            form.date = bad.into();
+            assert!(form.validate().is_err(), "{}", bad);
+        }
+        assert!(form.validate().unwrap_err().contains("2024-06-15"));
+
+        form.date = "2024/6/1".into();
+        form.normalize_date();
+        assert_eq!(form.date, "2024-06-01");
+        assert!(form.validate().is_ok());
+    }
+
+    #[test]
+    fn date_corrections() {
+        assert_eq!(correct_date_format("2024.06.01").as_deref(), Some("2024-06-01"));
+        assert_eq!(correct_date_format(" 2024/12/9 ").as_deref(), Some("2024-12-09"));
+        assert_eq!(correct_date_format("2024/13/01"), None);
+        assert_eq!(correct_date_format("2024/+6/01"), None);
+        assert_eq!(correct_date_format("01/06/2024"), None);
+
+        assert_eq!(day_first_suggestion("15/06/2024").as_deref(), Some("2024-06-15"));
+        assert_eq!(day_first_suggestion("31.1.2025").as_deref(), Some("2025-01-31"));
+        assert_eq!(day_first_suggestion("01/06/2024"), None);
+        assert_eq!(day_first_suggestion("31/02/2024"), None);
+    }
+
+    #[test]
+    fn validate_rejects_blank_source() {
+        let mut form = TransactionForm::new();
+        assert!(form.validate().is_err());
+
+        form.source = "   ".into();
+        assert!(form.validate().is_err());
+
+        form.source = "Rent".into();
+        assert!(form.validate().is_ok());
+    }
+
+    #[test]
+    fn source_completion_matches_prefix() {
+        let history = vec!["Groceries".to_string(), "Gym".to_string()];
+        let mut form = TransactionForm::new();
+        assert_eq!(form.source_completion(&history), None);
+
+        form.source = "gro".into();
+        assert_eq!(form.source_completion(&history), Some("ceries"));
+
+        assert!(form.accept_source_completion(&history));
+        assert_eq!(form.source, "Groceries");
+
+        // Nothing left to complete once the whole entry is typed
+        assert!(!form.accept_source_completion(&history));
+
+        // Matching goes by characters, not bytes
+        let history = vec!["Élan Café".to_string()];
+        form.source = "éla".into();
+        assert_eq!(form.source_completion(&history), Some("n Café"));
+        assert!(form.accept_source_completion(&history));
+        assert_eq!(form.source, "Élan Café");
+    }
+
     #[test]
     fn toggle_kind_swaps() {
         let mut form = TransactionForm::new();
@@ -207,6 +628,57 @@ mod tests {
         assert_eq!(form.amount, "1");
     }
 
+    #[test]
+    fn negative_amount_forces_debit() {
+        let mut form = TransactionForm::new();
+        form.kind = TransactionType::Credit;
+        form.active = Field::Amount;
+
+        form.push_char('-');
+        assert_eq!(form.kind, TransactionType::Debit);
+        form.push_char('4');
+        form.push_char('2');
+        assert_eq!(form.amount_value(), 42.0);
+
+        // Toggling to credit strips the sign
+        form.toggle_kind();
+        assert_eq!(form.kind, TransactionType::Credit);
+        assert_eq!(form.amount, "42");
+    }
+
+    #[test]
+    fn currency_needs_an_exchange_rate() {
+        let rates: HashMap<String, f64> = [("EUR".to_string(), 1.1)].into_iter().collect();
+        assert!(validate_currency("", "$", &rates).is_ok());
+        assert!(validate_currency("$", "$", &rates).is_ok());
+        assert!(validate_currency(" EUR ", "$", &rates).is_ok());
+        assert!(validate_currency("GBP", "$", &rates).unwrap_err().contains("EUR"));
+        assert!(validate_currency("EUR", "$", &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn relative_dates_expand() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        assert_eq!(expand_relative_date("today", today).as_deref(), Some("2026-03-02"));
+        assert_eq!(expand_relative_date("Yesterday", today).as_deref(), Some("2026-03-01"));
+        assert_eq!(expand_relative_date("-3", today).as_deref(), Some("2026-02-27"));
+        assert_eq!(expand_relative_date("2026-01-15", today), None);
+        assert_eq!(expand_relative_date("soon", today), None);
+        assert_eq!(expand_relative_date("--5", today), None);
+        assert_eq!(expand_relative_date("-+5", today), None);
+        assert_eq!(expand_relative_date("-99999999", today), None);
+        assert_eq!(expand_relative_date("-999999999999999", today), None);
+    }
+
+    #[test]
+    fn leaving_date_field_normalizes() {
+        let mut form = TransactionForm::new();
+        form.active = Field::Date;
+        form.date = "today".into();
+        form.focus(Field::Kind);
+        assert_eq!(form.date, chrono::Local::now().format("%Y-%m-%d").to_string());
+    }
+
     #[test]
     fn interval_next_prev_cycle() {
         let mut form = TransactionForm::new();
@@ -1,14 +1,40 @@
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use rusqlite::Connection;
 
-use crate::app::{App, Mode, PopupAction, PopupKind};
+use crate::app::{App, ConfirmChoice, Mode, PopupAction, PopupKind};
 use crate::stats;
 
+/// Dispatch a key press, picking out the few chords that use modifiers
+/// before handing the bare key to `handle_key`.
+pub fn handle_key_event(app: &mut App, key: KeyEvent, conn: &Connection) -> bool {
+    if key.modifiers.contains(KeyModifiers::CONTROL)
+        && key.code == KeyCode::Char('r')
+        && app.mode == Mode::Normal
+    {
+        if app.read_only {
+            app.refuse_read_only();
+        } else {
+            app.redo(conn);
+        }
+        return false;
+    }
+
+    handle_key(app, key.code, conn)
+}
+
 pub fn handle_key(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
     // global tab/arrow handling applies when we're in any of the
     // "main" views. Adding/popup mode shouldn't switch tabs.
     match key {
-        KeyCode::Char('q') => return true,
+        // Elsewhere 'q' is typed text, e.g. in a search or the form
+        KeyCode::Char('q')
+            if matches!(
+                app.mode,
+                Mode::Normal | Mode::Stats | Mode::RecurringManagement
+            ) =>
+        {
+            return true;
+        }
 
         KeyCode::Tab | KeyCode::Right
             if matches!(
@@ -28,18 +54,36 @@ pub fn handle_key(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
             app.prev_tab();
             return false;
         }
+
+        // Privacy mode works from any of the main views
+        KeyCode::Char('p')
+            if matches!(
+                app.mode,
+                Mode::Normal | Mode::Stats | Mode::RecurringManagement
+            ) =>
+        {
+            app.toggle_privacy();
+            return false;
+        }
         _ => {}
     }
 
     match app.mode {
         Mode::Normal => handle_normal(app, key, conn),
         Mode::Adding => handle_form(app, key, conn),
-        Mode::Stats => stats::handle_stats(app, key),
+        Mode::Stats => stats::handle_stats(app, key, conn),
+        Mode::TagTrend => stats::handle_tag_trend(app, key, conn),
+        Mode::YearSummary => stats::handle_year_summary(app, key, conn),
 
         // 👇 New popup mode
         Mode::Popup => handle_popup(app, key, conn),
         Mode::RecurringManagement => handle_recurring_management(app, key, conn),
         Mode::Filtering => handle_filter(app, key),
+        Mode::JumpToDate => handle_jump(app, key),
+        Mode::Search => handle_search(app, key),
+        Mode::Retag => handle_retag(app, key, conn),
+        Mode::ScaleAmounts => handle_scale(app, key),
+        Mode::QuickAdd => handle_quick_add(app, key, conn),
     }
 }
 
@@ -48,98 +92,330 @@ pub fn handle_key(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
 //
 
 fn handle_popup(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
+    if matches!(app.popup, Some(PopupKind::TypedConfirm { .. })) {
+        return handle_typed_confirm(app, key, conn);
+    }
+
     match key {
         // Confirm action
         KeyCode::Char('y') => {
-            if let Some(popup) = app.popup.clone() {
-                if let PopupKind::Confirm { action, .. } = popup {
-                    match action {
-                        PopupAction::DeleteTransaction(id) => {
-                            crate::db::delete_transaction(conn, id).unwrap();
-                            app.refresh(conn);
-                        }
+            return confirm_popup(app, conn);
+        }
 
-                        PopupAction::Quit => {
-                            return true;
-                        }
-                    }
-                }
+        // Cancel popup
+        KeyCode::Char('n') | KeyCode::Esc => {
+            decline_popup(app, conn);
+        }
+
+        // Open the file the popup points at, e.g. an export
+        KeyCode::Char('o') if app.popup_file.is_some() => {
+            app.open_popup_file();
+        }
+
+        // Move between Yes/No
+        KeyCode::Left | KeyCode::Right => {
+            app.popup_choice = app.popup_choice.toggle();
+        }
+
+        // Activate the highlighted button (or dismiss an info popup)
+        KeyCode::Enter => {
+            let is_confirm = matches!(app.popup, Some(PopupKind::Confirm { .. }));
+            if is_confirm && app.popup_choice == ConfirmChoice::Yes {
+                return confirm_popup(app, conn);
             }
 
-            app.close_popup();
+            decline_popup(app, conn);
         }
 
-        // Cancel popup
-        KeyCode::Char('n') | KeyCode::Esc => {
-            app.close_popup();
+        // Scroll long messages
+        KeyCode::Up => {
+            app.scroll_popup_up();
         }
 
+        KeyCode::Down => {
+            app.scroll_popup_down();
+        }
+
+        _ => {}
+    }
+
+    false
+}
+
+/// Keys for a popup that wants its confirm word typed: every character
+/// goes into the answer, and Enter only confirms on an exact match.
+fn handle_typed_confirm(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
+    let Some(PopupKind::TypedConfirm { word, typed, .. }) = app.popup.as_mut() else {
+        return false;
+    };
+
+    match key {
+        KeyCode::Esc => app.close_popup(),
+        KeyCode::Char(c) => typed.push(c),
+        KeyCode::Backspace => {
+            typed.pop();
+        }
+        KeyCode::Enter if typed == word => return confirm_popup(app, conn),
         _ => {}
     }
 
     false
 }
 
+/// Close the popup without running its action.
+fn decline_popup(app: &mut App, conn: &Connection) {
+    let deferred_recurring = matches!(
+        app.popup,
+        Some(PopupKind::Confirm { action: PopupAction::ResetRecurringState(_), .. })
+    );
+    let declined_save = matches!(
+        app.popup,
+        Some(PopupKind::Confirm { action: PopupAction::SaveEdit | PopupAction::SaveDuplicate, .. })
+    );
+    app.close_popup();
+
+    // Not saving goes back to the form rather than dropping it
+    if declined_save {
+        app.mode = Mode::Adding;
+    }
+
+    // The startup recurring check was waiting on this answer
+    if deferred_recurring {
+        app.process_recurring(conn);
+    }
+}
+
+/// Run the pending confirm action and close the popup. Returns `true` when
+/// the action asks the app to quit.
+fn confirm_popup(app: &mut App, conn: &Connection) -> bool {
+    if let Some(popup) = app.popup.clone()
+        && let PopupKind::Confirm { action, .. } | PopupKind::TypedConfirm { action, .. } = popup
+    {
+        match action {
+            PopupAction::DeleteTransaction(id) => {
+                // Close first: deleting may ask about the recurring rule
+                app.close_popup();
+                app.delete_transaction(conn, id);
+                return false;
+            }
+
+            PopupAction::DeleteTransactions(ids) => {
+                app.delete_transactions(conn, &ids);
+            }
+
+            PopupAction::PauseRecurring(rec_id) => {
+                crate::db::toggle_recurring_entry(conn, rec_id, false).unwrap();
+                app.refresh(conn);
+            }
+
+            PopupAction::DeleteRecurringTransactions(rec_id) => {
+                crate::db::delete_transactions_by_recurring(conn, rec_id).unwrap();
+                app.refresh(conn);
+            }
+
+            PopupAction::InsertRecurring => {
+                let current_month = chrono::Local::now().format("%Y-%m").to_string();
+                crate::db::insert_recurring_for_month(conn, &current_month).unwrap();
+                app.refresh(conn);
+            }
+
+            PopupAction::SaveDuplicate => {
+                // Close first: saving may open a warning popup of its own
+                app.close_popup();
+                app.commit_form(conn);
+                app.form.reset();
+                return false;
+            }
+
+            PopupAction::SaveEdit => {
+                app.close_popup();
+                app.commit_form(conn);
+                app.form.reset();
+                return false;
+            }
+
+            PopupAction::MonthRollover => {
+                // Close first: the rollover opens its summary popup
+                app.close_popup();
+                app.month_rollover(conn);
+                return false;
+            }
+
+            PopupAction::MergeSources => {
+                app.merge_sources(conn);
+            }
+
+            PopupAction::ScaleAmounts(ids, factor) => {
+                app.scale_amounts(conn, &ids, factor);
+            }
+
+            PopupAction::ResetRecurringState(ids) => {
+                crate::db::reset_recurring_state(conn, &ids).unwrap();
+                app.refresh(conn);
+                // Close first: due entries may ask for confirmation
+                app.close_popup();
+                app.process_recurring(conn);
+                return false;
+            }
+
+            PopupAction::Quit => {
+                return true;
+            }
+        }
+    }
+
+    app.close_popup();
+    false
+}
+
 //
 // ---------------- NORMAL MODE ----------------
 //
 
-fn handle_normal(app: &mut App, key: KeyCode, _conn: &Connection) -> bool {
-    let len = app.get_filtered_transactions().len();
+// Normal-mode keys that write to the database
+fn mutates_data(key: KeyCode) -> bool {
+    matches!(key, KeyCode::Char('a' | 'A' | 'e' | 'd' | 't' | 'u' | '%' | 'C' | 'M' | 'S' | 's'))
+}
+
+fn handle_normal(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
+    if app.read_only && mutates_data(key) {
+        app.refuse_read_only();
+        return false;
+    }
+
+    app.sync_list_view();
+    let len = app.list_view.transactions.len();
 
     match key {
         KeyCode::Char('a') => {
             app.form.reset();
             app.editing = None;
+            app.load_source_history(conn);
             app.mode = Mode::Adding;
         }
 
+        // Another of the last transaction; only the amount to type
+        KeyCode::Char('A') => {
+            app.begin_quick_add(conn);
+        }
+
         KeyCode::Char('f') => {
             app.mode = Mode::Filtering;
         }
 
-        KeyCode::Char('c') => {
-            if app.filter.active {
-                app.filter.active = false;
-                app.filter.start_date.clear();
-                app.filter.end_date.clear();
-                app.filter.tag_index = None;
-                app.selected = 0;
-            }
+        KeyCode::Char('c') if app.filter.active => {
+            app.clear_filters();
         }
 
-        KeyCode::Up => {
-            if app.selected > 0 {
-                app.selected -= 1;
-            }
+        KeyCode::Up if app.selected > 0 => {
+            app.selected -= 1;
         }
 
         KeyCode::Down => {
-            let max_len = std::cmp::min(15, len);
-            if app.selected + 1 < max_len {
-                app.selected += 1;
-            }
+            app.select_next(len);
         }
 
-        // ✅ Delete now opens confirmation popup
+        // Delete the selected row, or the marked ones, after confirming
         KeyCode::Char('d') => {
-            if let Some(tx) = app.selected_transaction() {
-                app.open_confirm_popup(
-                    "Confirm Delete",
-                    format!(
-                        "Delete this transaction?\n\n{}  ({}{})",
-                        tx.source, app.currency, tx.amount
-                    ),
-                    PopupAction::DeleteTransaction(tx.id),
-                );
-            }
+            app.confirm_delete();
         }
 
         KeyCode::Char('e') => {
             // Begin editing the currently selected transaction
+            app.load_source_history(conn);
             app.begin_edit_selected();
         }
 
+        KeyCode::Char('x') => {
+            app.export_view(conn);
+        }
+
+        KeyCode::Char('y') => {
+            app.copy_selected();
+        }
+
+        KeyCode::Char('v') => {
+            app.toggle_date_groups();
+        }
+
+        KeyCode::Char('X') => {
+            app.export_tag_summary(conn);
+        }
+
+        KeyCode::Char(c @ '1'..='9') => {
+            let today = chrono::Local::now().date_naive();
+            app.apply_filter_preset(c as usize - '1' as usize, today);
+        }
+
+        KeyCode::Char('/') => {
+            app.begin_search();
+        }
+
+        // Hop to the next/previous row of the other kind
+        KeyCode::Char('n') => {
+            app.jump_to_other_kind(true);
+        }
+
+        KeyCode::Char('N') => {
+            app.jump_to_other_kind(false);
+        }
+
+        KeyCode::Char('g') => {
+            app.jump_date.clear();
+            app.mode = Mode::JumpToDate;
+        }
+
+        KeyCode::Char(' ') => {
+            app.toggle_mark_selected();
+        }
+
+        KeyCode::Char('t') => {
+            app.begin_retag();
+        }
+
+        KeyCode::Char('r') => {
+            app.open_selected_receipt();
+        }
+
+        KeyCode::Char('u') => {
+            app.undo(conn);
+        }
+
+        KeyCode::Char('%') => {
+            app.begin_scale();
+        }
+
+        KeyCode::Char('C') => {
+            app.toggle_cleared(conn);
+        }
+
+        KeyCode::Char('M') => {
+            app.confirm_month_rollover();
+        }
+
+        KeyCode::Char('S') => {
+            app.begin_source_merge(conn);
+        }
+
+        KeyCode::Char('s') if app.show_onboarding => {
+            app.add_sample_transaction(conn);
+        }
+
+        // Sort order: o picks the column, O flips the direction
+        KeyCode::Char('o') => {
+            app.cycle_sort_key();
+            app.save_ui_state();
+        }
+
+        KeyCode::Char('O') => {
+            app.toggle_sort_direction();
+            app.save_ui_state();
+        }
+
+        KeyCode::Esc => {
+            app.clear_marks();
+        }
+
         _ => {}
     }
 
@@ -151,27 +427,49 @@ fn handle_normal(app: &mut App, key: KeyCode, _conn: &Connection) -> bool {
 //
 
 fn handle_form(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
+    // The form can't be opened read-only, but never save from it either
+    if app.read_only && key == KeyCode::Enter {
+        app.editing = None;
+        app.editing_recurring = None;
+        app.form.reset();
+        app.refuse_read_only();
+        return false;
+    }
+
     match key {
         KeyCode::Esc => {
-            app.mode = Mode::Normal;
+            // Back to wherever the edit started
+            app.mode = if app.editing_recurring.take().is_some() {
+                Mode::RecurringManagement
+            } else {
+                Mode::Normal
+            };
             app.editing = None;
             app.form.reset();
         }
 
+        // Tab/Right on Source accept the suggested completion first
         KeyCode::Tab => {
-            app.form.active = app.form.active.next();
+            let completed = app.form.accept_source_completion(&app.source_history);
+            if !completed {
+                app.form.focus(app.form.active.next());
+            }
         }
 
         KeyCode::BackTab => {
-            app.form.active = app.form.active.back();
+            app.form.focus(app.form.active.back());
         }
 
         // Arrow keys toggle Kind, cycle Tags, or toggle Recurring depending on active field
         KeyCode::Right => match app.form.active {
+            crate::form::Field::Source => {
+                app.form.accept_source_completion(&app.source_history);
+            }
             crate::form::Field::Kind => app.form.toggle_kind(),
             crate::form::Field::Tag => app.form.next_tag(app.tags.len()),
             crate::form::Field::Recurring => app.form.toggle_recurring(),
             crate::form::Field::RecurringInterval => app.form.next_interval(),
+            crate::form::Field::Prorate => app.form.toggle_prorate(),
             _ => {}
         },
 
@@ -180,9 +478,27 @@ fn handle_form(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
             crate::form::Field::Tag => app.form.prev_tag(app.tags.len()),
             crate::form::Field::Recurring => app.form.toggle_recurring(),
             crate::form::Field::RecurringInterval => app.form.prev_interval(),
+            crate::form::Field::Prorate => app.form.toggle_prorate(),
             _ => {}
         },
 
+        // Step the amount up or down without retyping it
+        KeyCode::Up if app.form.active == crate::form::Field::Amount => {
+            app.form.adjust_amount(app.amount_step);
+        }
+
+        KeyCode::Down if app.form.active == crate::form::Field::Amount => {
+            app.form.adjust_amount(-app.amount_step);
+        }
+
+        KeyCode::PageUp if app.form.active == crate::form::Field::Amount => {
+            app.form.adjust_amount(app.amount_step_large);
+        }
+
+        KeyCode::PageDown if app.form.active == crate::form::Field::Amount => {
+            app.form.adjust_amount(-app.amount_step_large);
+        }
+
         KeyCode::Backspace => {
             app.form.pop_char();
         }
@@ -191,10 +507,18 @@ fn handle_form(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
             app.form.push_char(c);
         }
 
+        // Stay in the form when validation fails so the message is seen
         KeyCode::Enter => {
-            app.save_transaction(conn);
+            let from_recurring = app.editing_recurring.is_some();
+            if !app.save_transaction(conn) {
+                return false;
+            }
+
             app.form.reset();
-            app.mode = Mode::Normal;
+            // Saving may have opened a warning popup; leave that showing
+            if app.mode == Mode::Adding {
+                app.mode = if from_recurring { Mode::RecurringManagement } else { Mode::Normal };
+            }
         }
 
         _ => {}
@@ -208,6 +532,11 @@ fn handle_form(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
 //
 
 fn handle_recurring_management(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
+    if app.read_only && matches!(key, KeyCode::Char(' ' | 'e' | 'd' | 'x')) {
+        app.refuse_read_only();
+        return false;
+    }
+
     let len = app.recurring_entries.len();
 
     match key {
@@ -215,41 +544,63 @@ fn handle_recurring_management(app: &mut App, key: KeyCode, conn: &Connection) -
             app.mode = Mode::Normal;
         }
 
-        KeyCode::Up => {
-            if app.selected_recurring > 0 {
-                app.selected_recurring -= 1;
-            }
+        KeyCode::Up if app.selected_recurring > 0 => {
+            app.selected_recurring -= 1;
         }
 
-        KeyCode::Down => {
-            if app.selected_recurring + 1 < len {
-                app.selected_recurring += 1;
-            }
+        KeyCode::Down if app.selected_recurring + 1 < len => {
+            app.selected_recurring += 1;
         }
 
-        KeyCode::Char(' ') => {
-            // Toggle active/inactive for selected recurring entry
-            if !app.recurring_entries.is_empty() {
-                let entry = &app.recurring_entries[app.selected_recurring];
-                let new_active = !entry.active;
-                crate::db::toggle_recurring_entry(conn, entry.id, new_active).unwrap();
-                app.refresh(conn);
+        // Toggle active/inactive for selected recurring entry
+        KeyCode::Char(' ') if !app.recurring_entries.is_empty() => {
+            let entry = &app.recurring_entries[app.selected_recurring];
+            let new_active = !entry.active;
+            crate::db::toggle_recurring_entry(conn, entry.id, new_active).unwrap();
+            app.refresh(conn);
+        }
+
+        KeyCode::Char('e') if !app.recurring_entries.is_empty() => {
+            app.begin_edit_recurring();
+        }
+
+        KeyCode::Char('o') => {
+            app.cycle_recurring_sort();
+        }
+
+        // Delete selected recurring entry
+        KeyCode::Char('d') if !app.recurring_entries.is_empty() => {
+            let entry = &app.recurring_entries[app.selected_recurring];
+            crate::db::delete_recurring_entry(conn, entry.id).unwrap();
+            app.refresh(conn);
+
+            // Clamp selection if needed
+            if app.selected_recurring >= app.recurring_entries.len()
+                && app.selected_recurring > 0
+            {
+                app.selected_recurring -= 1;
             }
         }
 
-        KeyCode::Char('d') => {
-            // Delete selected recurring entry
-            if !app.recurring_entries.is_empty() {
-                let entry = &app.recurring_entries[app.selected_recurring];
-                crate::db::delete_recurring_entry(conn, entry.id).unwrap();
-                app.refresh(conn);
+        // Remove every transaction this rule has inserted so far
+        KeyCode::Char('x') if !app.recurring_entries.is_empty() => {
+            let entry = app.recurring_entries[app.selected_recurring].clone();
+            let count = crate::db::count_transactions_by_recurring(conn, entry.id).unwrap_or(0);
 
-                // Clamp selection if needed
-                if app.selected_recurring >= app.recurring_entries.len()
-                    && app.selected_recurring > 0
-                {
-                    app.selected_recurring -= 1;
-                }
+            if count == 0 {
+                app.open_info_popup(
+                    "Nothing to Delete",
+                    format!("'{}' hasn't inserted any transactions.", entry.source),
+                );
+            } else {
+                app.open_confirm_popup(
+                    "Confirm Delete",
+                    format!(
+                        "Delete {} transactions created by '{}'?",
+                        count, entry.source
+                    ),
+                    PopupAction::DeleteRecurringTransactions(entry.id),
+                );
             }
         }
 
@@ -298,72 +649,164 @@ fn handle_filter(app: &mut App, key: KeyCode) -> bool {
                 };
             }
         }
+        KeyCode::Backspace => match app.filter.active_field {
+            crate::app::FilterField::StartDate => pop_date_char(&mut app.filter.start_date),
+            crate::app::FilterField::EndDate => pop_date_char(&mut app.filter.end_date),
+            _ => {}
+        },
+        KeyCode::Char(c) => match app.filter.active_field {
+            crate::app::FilterField::StartDate => push_date_char(&mut app.filter.start_date, c),
+            crate::app::FilterField::EndDate => push_date_char(&mut app.filter.end_date, c),
+            _ => {}
+        },
+        KeyCode::Enter => {
+            app.filter.active = app.filter.has_criteria();
+            app.selected = 0;
+            app.mode = Mode::Normal;
+        }
+        _ => {}
+    }
+    false
+}
+
+//
+// ---------------- JUMP TO DATE MODE ----------------
+//
+
+fn handle_jump(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Esc => {
+            app.mode = Mode::Normal;
+        }
+        KeyCode::Backspace => pop_date_char(&mut app.jump_date),
+        KeyCode::Char(c) => push_date_char(&mut app.jump_date, c),
+        KeyCode::Enter => {
+            app.mode = Mode::Normal;
+            let date = app.jump_date.clone();
+            app.jump_to_date(&date);
+        }
+        _ => {}
+    }
+    false
+}
+
+fn handle_search(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Esc => app.cancel_search(),
+        KeyCode::Enter => app.commit_search(),
+        KeyCode::Up => app.recall_older_search(),
+        KeyCode::Down => app.recall_newer_search(),
         KeyCode::Backspace => {
-            match app.filter.active_field {
-                crate::app::FilterField::StartDate => {
-                    let s = &mut app.filter.start_date;
-                    if s.ends_with('-') {
-                        s.pop();
-                    }
-                    s.pop();
-                }
-                crate::app::FilterField::EndDate => {
-                    let s = &mut app.filter.end_date;
-                    if s.ends_with('-') {
-                        s.pop();
-                    }
-                    s.pop();
-                }
-                _ => {}
-            }
+            let mut query = app.filter.search.clone();
+            query.pop();
+            app.search.recall = None;
+            app.set_search(query);
         }
         KeyCode::Char(c) => {
-            match app.filter.active_field {
-                crate::app::FilterField::StartDate => {
-                    if c.is_ascii_digit() {
-                        let s = &mut app.filter.start_date;
-                        if s.len() < 10 {
-                            s.push(c);
-                            let len = s.len();
-                            if len == 4 || len == 7 {
-                                s.push('-');
-                            }
-                        }
-                    } else if c == '-' {
-                        let s = &mut app.filter.start_date;
-                        let len = s.len();
-                        if (len == 4 || len == 7) && len < 10 {
-                            s.push(c);
-                        }
-                    }
-                }
-                crate::app::FilterField::EndDate => {
-                    if c.is_ascii_digit() {
-                        let s = &mut app.filter.end_date;
-                        if s.len() < 10 {
-                            s.push(c);
-                            let len = s.len();
-                            if len == 4 || len == 7 {
-                                s.push('-');
-                            }
-                        }
-                    } else if c == '-' {
-                        let s = &mut app.filter.end_date;
-                        let len = s.len();
-                        if (len == 4 || len == 7) && len < 10 {
-                            s.push(c);
-                        }
-                    }
-                }
-                _ => {}
-            }
+            let mut query = app.filter.search.clone();
+            query.push(c);
+            app.search.recall = None;
+            app.set_search(query);
+        }
+        _ => {}
+    }
+    false
+}
+
+fn handle_retag(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
+    let total = app.tags.len();
+
+    match key {
+        KeyCode::Esc => {
+            app.mode = Mode::Normal;
+        }
+        KeyCode::Right | KeyCode::Down if total > 0 => {
+            app.retag_index = (app.retag_index + 1) % total;
+        }
+        KeyCode::Left | KeyCode::Up if total > 0 => {
+            app.retag_index = (app.retag_index + total - 1) % total;
         }
         KeyCode::Enter => {
-            app.filter.active = !app.filter.start_date.is_empty() || !app.filter.end_date.is_empty() || app.filter.tag_index.is_some();
-            app.selected = 0;
+            app.apply_retag(conn);
+        }
+        _ => {}
+    }
+    false
+}
+
+fn handle_scale(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Esc => {
+            app.mode = Mode::Normal;
+        }
+        KeyCode::Backspace => {
+            app.scale_input.pop();
+        }
+        KeyCode::Char(c @ ('0'..='9' | '.' | '+' | '-' | '%')) => {
+            app.scale_input.push(c);
+        }
+        KeyCode::Enter => {
+            app.confirm_scale();
+        }
+        _ => {}
+    }
+    false
+}
+
+fn handle_quick_add(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
+    match key {
+        KeyCode::Esc => {
+            app.form.reset();
             app.mode = Mode::Normal;
         }
+        KeyCode::Backspace => {
+            app.form.pop_char();
+        }
+        KeyCode::Char(c) => {
+            app.form.push_char(c);
+        }
+        // The amount is the one thing typed, so don't save it as zero
+        KeyCode::Enter if app.form.amount_value() == 0.0 => {
+            app.form.error = Some("Type an amount".into());
+        }
+        // Anything else the form rejects stays in the prompt, reason shown
+        KeyCode::Enter => {
+            if !app.save_transaction(conn) {
+                return false;
+            }
+
+            app.form.reset();
+            if app.mode == Mode::QuickAdd {
+                app.mode = Mode::Normal;
+            }
+        }
         _ => {}
     }
     false
 }
+
+/// Type into a `YYYY-MM-DD` field, inserting the hyphens automatically.
+fn push_date_char(s: &mut String, c: char) {
+    if c.is_ascii_digit() {
+        if s.len() < 10 {
+            s.push(c);
+            let len = s.len();
+            if len == 4 || len == 7 {
+                s.push('-');
+            }
+        }
+    } else if c == '-' {
+        let len = s.len();
+        if len == 4 || len == 7 {
+            s.push(c);
+        }
+    }
+}
+
+/// Delete from a `YYYY-MM-DD` field, removing an auto-inserted hyphen too.
+fn pop_date_char(s: &mut String) {
+    if s.ends_with('-') {
+        s.pop();
+    }
+    s.pop();
+}
@@ -1,10 +1,15 @@
 #![allow(non_snake_case)]
 pub mod app;
+pub mod cli;
 pub mod db;
 pub mod form;
 pub mod handlers;
 pub mod models;
+pub mod money;
+pub mod locale;
+pub mod search;
 pub mod stats;
 pub mod theme;
 pub mod ui;
 pub mod config;
+pub mod state;
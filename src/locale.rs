@@ -0,0 +1,94 @@
+/// Language month names are shown in. Only the names change; dates are
+/// still stored and typed as YYYY-MM-DD.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Es,
+    Fr,
+    It,
+    Nl,
+    Pt,
+}
+
+// Three-letter abbreviations, January first, so every locale fits the
+// same chart bar width
+const MONTHS: [[&str; 12]; 7] = [
+    ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"],
+    ["Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez"],
+    ["Ene", "Feb", "Mar", "Abr", "May", "Jun", "Jul", "Ago", "Sep", "Oct", "Nov", "Dic"],
+    ["Jan", "Fév", "Mar", "Avr", "Mai", "Jun", "Jul", "Aoû", "Sep", "Oct", "Nov", "Déc"],
+    ["Gen", "Feb", "Mar", "Apr", "Mag", "Giu", "Lug", "Ago", "Set", "Ott", "Nov", "Dic"],
+    ["Jan", "Feb", "Mrt", "Apr", "Mei", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dec"],
+    ["Jan", "Fev", "Mar", "Abr", "Mai", "Jun", "Jul", "Ago", "Set", "Out", "Nov", "Dez"],
+];
+
+impl std::str::FromStr for Locale {
+    type Err = ();
+
+    /// Parse a language code; a region suffix is ignored, so "de_AT" and
+    /// "pt-BR" work too.
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let lang = s.trim().split(['_', '-']).next().unwrap_or("");
+        match lang.to_ascii_lowercase().as_str() {
+            "en" => Ok(Locale::En),
+            "de" => Ok(Locale::De),
+            "es" => Ok(Locale::Es),
+            "fr" => Ok(Locale::Fr),
+            "it" => Ok(Locale::It),
+            "nl" => Ok(Locale::Nl),
+            "pt" => Ok(Locale::Pt),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Locale {
+    /// Abbreviated name of `month` (1–12); empty for anything else.
+    pub fn month_abbr(self, month: u32) -> &'static str {
+        match month {
+            1..=12 => MONTHS[self as usize][month as usize - 1],
+            _ => "",
+        }
+    }
+
+    /// Short label for a YYYY-MM key, e.g. "Jun '24". Keys that aren't a
+    /// month come back unchanged.
+    pub fn month_label(self, key: &str) -> String {
+        match split_month(key) {
+            Some((year, month)) => format!("{} '{:02}", self.month_abbr(month), year % 100),
+            None => key.to_string(),
+        }
+    }
+}
+
+fn split_month(key: &str) -> Option<(i32, u32)> {
+    let (year, month) = key.split_once('-')?;
+    let month: u32 = month.parse().ok()?;
+    (year.len() == 4 && (1..=12).contains(&month)).then_some((year.parse().ok()?, month))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_parses_language_codes() {
+        assert_eq!("de".parse(), Ok(Locale::De));
+        assert_eq!(" pt-BR ".parse(), Ok(Locale::Pt));
+        assert_eq!("FR_ca".parse(), Ok(Locale::Fr));
+        assert_eq!("xx".parse::<Locale>(), Err(()));
+        assert_eq!(Locale::default(), Locale::En);
+    }
+
+    #[test]
+    fn month_labels_follow_locale() {
+        assert_eq!(Locale::En.month_label("2024-06"), "Jun '24");
+        assert_eq!(Locale::De.month_label("2024-03"), "Mär '24");
+        assert_eq!(Locale::Es.month_label("2026-01"), "Ene '26");
+        assert_eq!(Locale::It.month_abbr(12), "Dic");
+        assert_eq!(Locale::En.month_abbr(13), "");
+        assert_eq!(Locale::En.month_label("2024-W05"), "2024-W05");
+    }
+}
@@ -1,13 +1,18 @@
 #![allow(non_snake_case)]
 mod app;
+mod cli;
 mod db;
 mod form;
 mod handlers;
 mod models;
+mod money;
+mod locale;
+mod search;
 mod stats;
 mod theme;
 mod ui;
 mod config;
+mod state;
 
 use std::io;
 
@@ -25,42 +30,120 @@ use ratatui::prelude::*;
 use app::App;
 
 fn main() -> io::Result<()> {
-    let conn = db::init_db().unwrap();
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let parsed = cli::take_profile(&mut args)
+        .and_then(|profile| Ok((profile, cli::parse_args(&args)?)));
+    let (profile, command) = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("fitui: {}\n\n{}", err, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let conn = db::init_db(profile.as_deref()).unwrap();
+
+    // Command-line actions never touch the terminal
+    let read_only = match command {
+        Some(cli::Command::ReadOnly) => true,
+        Some(command) => return cli::run(command, &conn, profile.as_deref()),
+        None => false,
+    };
 
-    // Insert recurring entries based on their intervals
-    db::insert_recurring_transactions(&conn).unwrap();
+    let mut app = App::new(&conn, profile.as_deref());
+    app.read_only = read_only;
+
+    // Inline mode draws in the normal buffer for terminals that mishandle
+    // the alternate screen
+    let alternate_screen = app.alternate_screen && std::env::var_os("FITUI_NO_ALT_SCREEN").is_none();
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    if alternate_screen {
+        execute!(stdout, EnterAlternateScreen)?;
+    }
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
+    if !alternate_screen {
+        terminal.clear()?;
+    }
+
+    // Insert recurring entries based on their intervals (after a preview
+    // unless auto_confirm_recurring is set)
+    app.new_day(&conn);
+    let mut today = chrono::Local::now().date_naive();
 
-    let mut app = App::new(&conn);
+    let mut snapshot = stats::StatsSnapshot::for_app(&app);
+    app.stats_lines = stats::details_lines(&snapshot, &app.theme, &app.money()).len();
+    let mut needs_draw = true;
 
     loop {
-        let snapshot = stats::StatsSnapshot::new(&app.transactions);
+        // Left open past midnight: recurring entries may have fallen due
+        let now = chrono::Local::now();
+        if now.date_naive() != today {
+            today = now.date_naive();
+            app.new_day(&conn);
+        }
+
+        if app.expire_toast(std::time::Instant::now()) {
+            needs_draw = true;
+        }
 
-        terminal.draw(|f| {
-            ui::draw_ui(f, &app, &snapshot);
-        })?;
+        // Only recompute stats after the transaction list actually changed
+        if app.dirty {
+            snapshot = stats::StatsSnapshot::for_app(&app);
+            app.stats_lines = stats::details_lines(&snapshot, &app.theme, &app.money()).len();
+            app.dirty = false;
+            needs_draw = true;
+        }
 
-        if event::poll(std::time::Duration::from_millis(200))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    let quit = handlers::handle_key(&mut app, key.code, &conn);
+        if needs_draw {
+            app.sync_list_view();
+            terminal.draw(|f| {
+                ui::draw_ui(f, &app, &snapshot);
+            })?;
+            needs_draw = false;
+        }
 
-                    if quit {
-                        break;
-                    }
+        // Sleep until input arrives; nothing else changes the screen before
+        // midnight (or a toast expiring), so there is no short timer
+        if !event::poll(app.idle_timeout(now.naive_local()))? {
+            continue;
+        }
+
+        // Handle every event already queued before drawing again, so held
+        // arrow keys aren't throttled to one step per frame
+        let mut quit = false;
+        loop {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    quit = handlers::handle_key_event(&mut app, key, &conn);
+                    needs_draw = true;
                 }
+                Event::Resize(_, _) => needs_draw = true,
+                _ => {}
+            }
+
+            if quit || !event::poll(std::time::Duration::ZERO)? {
+                break;
             }
         }
+
+        if quit {
+            break;
+        }
     }
 
+    app.save_ui_state();
+
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    if alternate_screen {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    } else {
+        terminal.clear()?;
+        terminal.show_cursor()?;
+    }
 
     Ok(())
 }
@@ -1,7 +1,10 @@
 #![allow(dead_code)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum TransactionType {
     Credit,
+    #[default]
     Debit,
 }
 
@@ -13,6 +16,7 @@ impl TransactionType {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         match s {
             "credit" => TransactionType::Credit,
@@ -21,7 +25,7 @@ impl TransactionType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub struct Tag(pub String);
 
 impl Tag {
@@ -29,12 +33,23 @@ impl Tag {
         &self.0
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         Tag(s.to_string())
     }
+
+    /// Parse a tag name, using `fallback` when it is blank.
+    pub fn parse_or(s: &str, fallback: &str) -> Self {
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            Tag(fallback.to_string())
+        } else {
+            Tag(trimmed.to_string())
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Transaction {
     pub id: i32,
     pub source: String,
@@ -42,6 +57,45 @@ pub struct Transaction {
     pub kind: TransactionType,
     pub tag: Tag,
     pub date: String,
+    pub currency: String, // Empty means the configured base currency
+    pub created_by_recurring_id: Option<i32>, // Recurring rule that inserted this row, if any
+    pub receipt_path: String, // Path to a receipt image; empty when none is attached
+    pub cleared: bool, // Matched against a bank statement
+    pub note: String, // Free text; its #hashtags are indexed in note_tags
+}
+
+/// The `#hashtags` in a note, lowercased and without the `#`, each listed
+/// once in the order they first appear.
+pub fn parse_note_tags(note: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for word in note.split_whitespace() {
+        let Some(rest) = word.strip_prefix('#') else {
+            continue;
+        };
+        // Trailing punctuation ("#refundable,") isn't part of the tag
+        let tag: String = rest
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+            .collect::<String>()
+            .to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+impl Transaction {
+    /// Amount converted to the base currency using `rates` (units of base
+    /// per one unit of the transaction's currency). Unknown currencies are
+    /// left unconverted; `form::validate_currency` keeps new rows from
+    /// having one.
+    pub fn base_amount(&self, rates: &HashMap<String, f64>) -> f64 {
+        if self.currency.is_empty() {
+            return self.amount;
+        }
+        self.amount * rates.get(&self.currency).copied().unwrap_or(1.0)
+    }
 }
 #[derive(Debug, Clone, PartialEq)]
 pub enum RecurringInterval {
@@ -59,6 +113,7 @@ impl RecurringInterval {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         match s {
             "daily" => RecurringInterval::Daily,
@@ -104,4 +159,31 @@ pub struct RecurringEntry {
     pub original_date: String, // Format: "YYYY-MM-DD" - date when recurring entry was created
     pub last_inserted_date: String, // Format: depends on interval (YYYY-MM-DD for daily, YYYY-Www for weekly, YYYY-MM for monthly)
     pub active: bool,
-}
\ No newline at end of file
+    // The first monthly charge was scaled by the days left in that month
+    pub prorate_first: bool,
+}
+
+impl RecurringEntry {
+    /// An active rule that hasn't inserted anything yet.
+    pub fn new(
+        source: &str,
+        amount: f64,
+        kind: TransactionType,
+        tag: Tag,
+        interval: RecurringInterval,
+        original_date: &str,
+    ) -> Self {
+        RecurringEntry {
+            id: 0,
+            source: source.to_string(),
+            amount,
+            kind,
+            tag,
+            interval,
+            original_date: original_date.to_string(),
+            last_inserted_date: String::new(),
+            active: true,
+            prorate_first: false,
+        }
+    }
+}
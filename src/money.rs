@@ -0,0 +1,210 @@
+use crate::models::{Transaction, TransactionType};
+
+/// Amounts are stored and summed as whole units of 1/10000, so totals over
+/// many rows don't drift the way adding floats does. Four places is the
+/// most `decimal_places` can show.
+pub const UNITS_PER_MAJOR: f64 = 10_000.0;
+
+pub fn to_units(amount: f64) -> i64 {
+    (amount * UNITS_PER_MAJOR).round() as i64
+}
+
+pub fn from_units(units: i64) -> f64 {
+    units as f64 / UNITS_PER_MAJOR
+}
+
+/// Add amounts up exactly, going through integer units.
+pub fn sum_amounts(amounts: impl Iterator<Item = f64>) -> f64 {
+    from_units(amounts.map(to_units).sum())
+}
+
+/// How amounts are rounded to the displayed number of places. Stored
+/// values keep their full precision either way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Rounding {
+    // Ties go away from zero (0.125 -> 0.13), as bank statements do
+    #[default]
+    HalfUp,
+    // Ties go to the even digit (0.125 -> 0.12)
+    HalfEven,
+}
+
+impl std::str::FromStr for Rounding {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "half_up" => Ok(Rounding::HalfUp),
+            "half_even" => Ok(Rounding::HalfEven),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Rounding {
+    /// `amount` rounded to `decimals` places (at most four). Works on the
+    /// stored units, so a value like 1.005 rounds as written rather than
+    /// as its nearest float.
+    pub fn round(self, amount: f64, decimals: usize) -> f64 {
+        let step = 10_i64.pow(4 - decimals.min(4) as u32);
+        let units = to_units(amount);
+        let (whole, rest) = (units.abs() / step, units.abs() % step);
+
+        let up = match self {
+            Rounding::HalfUp => rest * 2 >= step,
+            Rounding::HalfEven => rest * 2 > step || (rest * 2 == step && whole % 2 == 1),
+        };
+        let rounded = if up && rest > 0 { whole + 1 } else { whole };
+        from_units(units.signum() * rounded * step)
+    }
+
+    /// `amount` multiplied by `factor` and rounded to `decimals` places,
+    /// as scaling amounts stores it.
+    pub fn scale(self, amount: f64, factor: f64, decimals: usize) -> f64 {
+        self.round(amount * factor, decimals)
+    }
+}
+
+/// What privacy mode shows in place of a number.
+pub const MASK: &str = "•••";
+
+/// Formats amounts for display. Everything the UI prints as money goes
+/// through here, so display options such as privacy mode apply uniformly.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Money {
+    // Base currency symbol from the config, e.g. "$"
+    pub symbol: String,
+    // Privacy mode: mask every amount
+    pub hidden: bool,
+    // Digits after the decimal point, e.g. 0 for JPY
+    pub decimals: usize,
+    // How ties are rounded to `decimals` places
+    pub rounding: Rounding,
+}
+
+impl Money {
+    pub fn new(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            hidden: false,
+            decimals: 2,
+            rounding: Rounding::HalfUp,
+        }
+    }
+
+    /// The bare number, e.g. `12.50`.
+    pub fn number(&self, amount: f64) -> String {
+        if self.hidden {
+            MASK.to_string()
+        } else {
+            let rounded = self.rounding.round(amount, self.decimals);
+            format!("{:.*}", self.decimals, rounded)
+        }
+    }
+
+    /// With the base symbol, e.g. `$12.50`.
+    pub fn format(&self, amount: f64) -> String {
+        format!("{}{}", self.symbol, self.number(amount))
+    }
+
+    /// Like `format` with the number right-aligned to `width` columns.
+    pub fn padded(&self, amount: f64, width: usize) -> String {
+        format!("{}{:>width$}", self.symbol, self.number(amount), width = width)
+    }
+
+    /// For amounts in a currency other than the base, e.g. `INR 500.00`.
+    pub fn with_code(&self, code: &str, amount: f64) -> String {
+        format!("{} {}", code, self.number(amount))
+    }
+
+    /// One-line description of a transaction, e.g.
+    /// `2024-06-01  Groceries  -$12.50  #food`.
+    pub fn describe(&self, tx: &Transaction) -> String {
+        let sign = match tx.kind {
+            TransactionType::Credit => "+",
+            TransactionType::Debit => "-",
+        };
+        let amount = if tx.currency.is_empty() {
+            self.format(tx.amount)
+        } else {
+            self.with_code(&tx.currency, tx.amount)
+        };
+        format!("{}  {}  {}{}  #{}", tx.date, tx.source, sign, amount, tx.tag.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_and_masks() {
+        let mut money = Money::new("$");
+        assert_eq!(money.format(12.5), "$12.50");
+        assert_eq!(money.padded(3.0, 6), "$  3.00");
+        assert_eq!(money.with_code("INR", 500.0), "INR 500.00");
+
+        money.hidden = true;
+        assert_eq!(money.format(12.5), "$•••");
+        assert_eq!(money.with_code("INR", 500.0), "INR •••");
+    }
+
+    #[test]
+    fn describes_a_transaction() {
+        let tx = Transaction {
+            date: "2024-06-01".into(),
+            source: "Hotel".into(),
+            amount: 80.0,
+            tag: crate::models::Tag::from_str("travel"),
+            currency: "EUR".into(),
+            ..Default::default()
+        };
+        assert_eq!(Money::new("$").describe(&tx), "2024-06-01  Hotel  -EUR 80.00  #travel");
+    }
+
+    #[test]
+    fn unit_sums_do_not_drift() {
+        let tenths = std::iter::repeat_n(0.1, 10);
+        assert_ne!(tenths.clone().sum::<f64>(), 1.0);
+        assert_eq!(sum_amounts(tenths), 1.0);
+
+        assert_eq!(to_units(12.5), 125_000);
+        assert_eq!(from_units(to_units(0.0001)), 0.0001);
+        assert_eq!(sum_amounts(std::iter::empty()).to_string(), "0");
+    }
+
+    #[test]
+    fn honours_decimal_places() {
+        let yen = Money { decimals: 0, ..Money::new("¥") };
+        assert_eq!(yen.format(1250.4), "¥1250");
+
+        let fine = Money { decimals: 3, ..Money::new("$") };
+        assert_eq!(fine.format(0.1255), "$0.126");
+    }
+
+    #[test]
+    fn rounds_ties_by_mode() {
+        let up = Money::new("$");
+        assert_eq!(up.format(0.125), "$0.13");
+        assert_eq!(up.format(1.005), "$1.01");
+        assert_eq!(up.format(-0.125), "$-0.13");
+        assert_eq!(up.format(0.1249), "$0.12");
+
+        let even = Money { rounding: Rounding::HalfEven, ..Money::new("$") };
+        assert_eq!(even.format(0.125), "$0.12");
+        assert_eq!(even.format(0.135), "$0.14");
+        assert_eq!(even.format(0.1251), "$0.13");
+
+        let yen = Money { decimals: 0, ..even };
+        assert_eq!(yen.format(2.5), "$2");
+        assert_eq!(Rounding::HalfUp.round(2.5, 0), 3.0);
+        assert_eq!("bankers".parse::<Rounding>(), Err(()));
+    }
+
+    #[test]
+    fn scaling_rounds_to_decimal_places() {
+        assert_eq!(Rounding::HalfUp.scale(12.5, 1.01, 2), 12.63);
+        assert_eq!(Rounding::HalfEven.scale(12.5, 1.01, 2), 12.62);
+        assert_eq!(Rounding::HalfUp.scale(999.0, 1.07, 0), 1069.0);
+    }
+}
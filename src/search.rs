@@ -0,0 +1,189 @@
+use crate::models::Transaction;
+
+/// A parsed search box query. Words with a known qualifier become
+/// filters; everything else is text matched against the source and note.
+///
+/// - `amount:>500`, `amount:<=20`, `amount:12.50` compare the amount
+/// - `tag:food` keeps one tag (ignoring case)
+/// - `date:2024`, `date:2024-06`, `date:2024-06-01` keep dates starting so
+/// - `#word` needs that hashtag in the note
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Query {
+    // Lowercased, words joined by single spaces
+    pub text: String,
+    pub filters: Vec<Filter>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    Amount(Comparison, f64),
+    Tag(String),
+    // YYYY, YYYY-MM or YYYY-MM-DD
+    Date(String),
+    NoteTag(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    /// Split a leading operator off `s`; no operator means equal.
+    fn split(s: &str) -> (Self, &str) {
+        for (op, cmp) in [
+            (">=", Comparison::GreaterOrEqual),
+            ("<=", Comparison::LessOrEqual),
+            (">", Comparison::Greater),
+            ("<", Comparison::Less),
+            ("=", Comparison::Equal),
+        ] {
+            if let Some(rest) = s.strip_prefix(op) {
+                return (cmp, rest);
+            }
+        }
+        (Comparison::Equal, s)
+    }
+
+    fn holds(self, value: f64, target: f64) -> bool {
+        // Within half a cent counts as equal, as in duplicate detection
+        let equal = (value - target).abs() < 0.005;
+        match self {
+            Comparison::Less => value < target && !equal,
+            Comparison::LessOrEqual => value < target || equal,
+            Comparison::Equal => equal,
+            Comparison::GreaterOrEqual => value > target || equal,
+            Comparison::Greater => value > target && !equal,
+        }
+    }
+}
+
+impl Query {
+    /// Parse a query. A qualifier that doesn't make sense (`amount:lots`,
+    /// `date:june`, `colour:red`) is searched for as plain text instead.
+    pub fn parse(query: &str) -> Self {
+        let mut text: Vec<&str> = Vec::new();
+        let mut filters = Vec::new();
+
+        for word in query.split_whitespace() {
+            match parse_filter(word) {
+                Some(filter) => filters.push(filter),
+                None => text.push(word),
+            }
+        }
+
+        Query { text: text.join(" ").to_lowercase(), filters }
+    }
+
+    /// Whether `tx`, whose note hashtags are `note_tags`, passes every filter
+    /// and contains the text in its source or note.
+    pub fn matches(&self, tx: &Transaction, note_tags: Option<&Vec<String>>) -> bool {
+        let filters_pass = self.filters.iter().all(|filter| match filter {
+            Filter::Amount(cmp, target) => cmp.holds(tx.amount, *target),
+            Filter::Tag(tag) => tx.tag.as_str().eq_ignore_ascii_case(tag),
+            Filter::Date(prefix) => tx.date.starts_with(prefix.as_str()),
+            Filter::NoteTag(tag) => note_tags.is_some_and(|tags| tags.contains(tag)),
+        });
+
+        filters_pass
+            && (self.text.is_empty()
+                || tx.source.to_lowercase().contains(&self.text)
+                || tx.note.to_lowercase().contains(&self.text))
+    }
+}
+
+fn parse_filter(word: &str) -> Option<Filter> {
+    if let Some(tag) = word.strip_prefix('#') {
+        return (!tag.is_empty()).then(|| Filter::NoteTag(tag.to_lowercase()));
+    }
+
+    let (key, value) = word.split_once(':')?;
+    match key.to_ascii_lowercase().as_str() {
+        "amount" => {
+            let (cmp, number) = Comparison::split(value);
+            let target: f64 = number.parse().ok()?;
+            target.is_finite().then_some(Filter::Amount(cmp, target))
+        }
+        "tag" if !value.is_empty() => Some(Filter::Tag(value.to_string())),
+        "date" if is_date_prefix(value) => Some(Filter::Date(value.to_string())),
+        _ => None,
+    }
+}
+
+/// YYYY, YYYY-MM or YYYY-MM-DD, digits only between the hyphens.
+fn is_date_prefix(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('-').collect();
+    let lengths = [4, 2, 2];
+    parts.len() <= 3
+        && parts
+            .iter()
+            .zip(lengths)
+            .all(|(part, len)| part.len() == len && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Tag, TransactionType};
+
+    fn tx(source: &str, amount: f64, tag: &str, date: &str) -> Transaction {
+        Transaction {
+            source: source.into(),
+            amount,
+            kind: TransactionType::Debit,
+            tag: Tag(tag.into()),
+            date: date.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn qualifiers_become_filters() {
+        let query = Query::parse("amount:>=500 Tag:Food date:2024-06 #work rent");
+        assert_eq!(
+            query.filters,
+            vec![
+                Filter::Amount(Comparison::GreaterOrEqual, 500.0),
+                Filter::Tag("Food".into()),
+                Filter::Date("2024-06".into()),
+                Filter::NoteTag("work".into()),
+            ]
+        );
+        assert_eq!(query.text, "rent");
+    }
+
+    #[test]
+    fn invalid_qualifiers_are_text() {
+        let query = Query::parse("amount:lots date:june colour:red date:2024-6");
+        assert!(query.filters.is_empty());
+        assert_eq!(query.text, "amount:lots date:june colour:red date:2024-6");
+    }
+
+    #[test]
+    fn filters_and_text_all_have_to_match() {
+        let rent = tx("Rent", 900.0, "home", "2024-06-01");
+        let lunch = tx("Lunch", 12.5, "food", "2024-06-03");
+
+        let matching = |q: &str| {
+            let query = Query::parse(q);
+            [&rent, &lunch]
+                .into_iter()
+                .filter(|t| query.matches(t, None))
+                .map(|t| t.source.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matching("amount:>500"), vec!["Rent"]);
+        assert_eq!(matching("amount:12.50"), vec!["Lunch"]);
+        assert_eq!(matching("amount:<12.5"), Vec::<&str>::new());
+        assert_eq!(matching("tag:FOOD"), vec!["Lunch"]);
+        assert_eq!(matching("date:2024-06"), vec!["Rent", "Lunch"]);
+        assert_eq!(matching("date:2024-06-01"), vec!["Rent"]);
+        assert_eq!(matching("date:2024 lun"), vec!["Lunch"]);
+        assert_eq!(matching("tag:home lunch"), Vec::<&str>::new());
+    }
+}
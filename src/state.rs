@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::db;
+
+/// UI choices remembered between runs. Unlike `config.yaml` this file is
+/// written by the app itself and isn't meant to be edited by hand.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct UiState {
+    #[serde(default = "default_sort_key")]
+    pub sort_key: String,
+    #[serde(default = "default_sort_desc")]
+    pub sort_desc: bool,
+    #[serde(default = "default_recurring_sort")]
+    pub recurring_sort: String,
+    #[serde(default = "default_group_by_date")]
+    pub group_by_date: bool,
+    // Set once the first transaction exists; hides the welcome panel for good
+    #[serde(default)]
+    pub onboarded: bool,
+    // Committed search queries, oldest first
+    #[serde(default)]
+    pub search_history: Vec<String>,
+}
+
+fn default_sort_key() -> String {
+    "date".to_string()
+}
+
+fn default_sort_desc() -> bool {
+    true
+}
+
+fn default_group_by_date() -> bool {
+    true
+}
+
+fn default_recurring_sort() -> String {
+    "newest".to_string()
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            sort_key: default_sort_key(),
+            sort_desc: default_sort_desc(),
+            recurring_sort: default_recurring_sort(),
+            group_by_date: default_group_by_date(),
+            onboarded: false,
+            search_history: Vec::new(),
+        }
+    }
+}
+
+/// Where the UI state is kept for `profile`.
+pub fn state_path(profile: Option<&str>) -> PathBuf {
+    db::data_dir().join(crate::config::profile_file_name("state", "yaml", profile))
+}
+
+/// Read the saved state, falling back to defaults if it's missing or unreadable.
+pub fn load_state(path: &Path) -> UiState {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_yaml::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_state(path: &Path, state: &UiState) {
+    if let Ok(yaml) = serde_yaml::to_string(state) {
+        let _ = fs::write(path, yaml); // Losing the sort order isn't worth an error
+    }
+}
@@ -1,47 +1,231 @@
 use std::collections::{ BTreeMap, HashMap };
-use ratatui::{ prelude::*, widgets::{ BarChart, Block, Paragraph } };
+use ratatui::{
+    prelude::*,
+    widgets::{
+        Axis,
+        BarChart,
+        Block,
+        block::Title,
+        Chart,
+        Dataset,
+        GraphType,
+        Paragraph,
+        Scrollbar,
+        ScrollbarOrientation,
+        ScrollbarState,
+    },
+};
 use crossterm::event::KeyCode;
-
-use crate::{ app::App, models::{ Tag, Transaction, TransactionType }, theme::Theme };
+use chrono::{ Datelike, Months, NaiveDate, Weekday };
+use rusqlite::Connection;
+
+use crate::{
+    app::App,
+    config::GoalConfig,
+    locale::Locale,
+    models::{ Tag, Transaction, TransactionType },
+    money::{ sum_amounts, Money },
+    theme::Theme,
+};
 pub struct StatsSnapshot {
     pub earned: f64,
     pub spent: f64,
+    // Includes `opening_balance`
     pub balance: f64,
+    pub opening_balance: f64,
 
     pub per_tag: HashMap<Tag, f64>,
+    // Income per source, largest first
+    pub earned_per_source: Vec<(String, f64)>,
     pub monthly_history: Vec<(String, f64, f64)>,
+    // Keyed by the first day of each week, newest first
+    pub weekly_history: Vec<(String, f64, f64)>,
+    pub week_start: Weekday,
+    // Language of the month labels
+    pub locale: Locale,
 
     pub tx_count: usize,
     pub largest: Option<Transaction>,
     pub smallest: Option<Transaction>,
     pub top_tags: Vec<(Tag, f64)>,
+
+    // Transactions not yet matched against a statement, and their net amount
+    pub uncleared_count: usize,
+    pub uncleared: f64,
+
+    pub month_comparison: MonthComparison,
+
+    // Recorded (month, balance) snapshots, oldest first
+    pub net_worth: Vec<(String, f64)>,
+
+    // Savings rate percentages as (low, healthy): below low is drawn as a
+    // warning, at or above healthy as good
+    pub savings_thresholds: (f64, f64),
+
+    pub goals: Vec<GoalProgress>,
+}
+
+/// How far along a savings goal is, and when it should be reached at the
+/// recent pace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalProgress {
+    pub name: String,
+    pub saved: f64,
+    pub amount: f64,
+    pub target: Option<NaiveDate>,
+    // `None` once reached, or when nothing is being saved
+    pub projected: Option<NaiveDate>,
+}
+
+impl GoalProgress {
+    pub fn reached(&self) -> bool {
+        self.saved >= self.amount
+    }
+
+    /// Share of the goal saved so far, 0–100.
+    pub fn percent(&self) -> f64 {
+        (self.saved / self.amount * 100.0).clamp(0.0, 100.0)
+    }
+
+    /// Whether the projection misses the target date (or there's no
+    /// projection at all for a dated goal).
+    pub fn behind(&self) -> bool {
+        match (self.target, self.projected) {
+            _ if self.reached() => false,
+            (Some(target), Some(projected)) => projected > target,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+/// Earned and spent this month next to the month before, as
+/// (this month, previous month).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MonthComparison {
+    pub earned: (f64, f64),
+    pub spent: (f64, f64),
+}
+
+impl MonthComparison {
+    /// Compare the month containing `today` with the month before it.
+    pub fn for_month(transactions: &[Transaction], today: NaiveDate) -> Self {
+        let this = today.format("%Y-%m").to_string();
+        let prev = (today.with_day(1).unwrap_or(today) - Months::new(1))
+            .format("%Y-%m")
+            .to_string();
+
+        Self {
+            earned: (
+                total_earned_in_month(transactions, &this),
+                total_earned_in_month(transactions, &prev),
+            ),
+            spent: (
+                total_spent_in_month(transactions, &this),
+                total_spent_in_month(transactions, &prev),
+            ),
+        }
+    }
+
+    /// Earned minus spent, as (this month, previous month).
+    pub fn net(&self) -> (f64, f64) {
+        (self.earned.0 - self.spent.0, self.earned.1 - self.spent.1)
+    }
 }
 
 impl StatsSnapshot {
-    pub fn new(transactions: &[Transaction]) -> Self {
+    /// Snapshot of the app's transactions, converted to the base currency
+    /// and using the configured stats window.
+    pub fn for_app(app: &App) -> Self {
+        let transactions = app.transactions_in_base();
+        let mut snapshot = Self::new(&transactions, app.stats_months, app.week_start);
+        snapshot.net_worth = app.balance_snapshots.clone();
+        snapshot.set_opening_balance(app.opening_balance);
+        snapshot.savings_thresholds = app.savings_thresholds;
+        snapshot.locale = app.locale;
+
+        let today = chrono::Local::now().date_naive();
+        snapshot.goals = app
+            .goals
+            .iter()
+            .map(|goal| {
+                calculate_goal_progress(goal, &transactions, snapshot.balance, app.stats_months, today)
+            })
+            .collect();
+        snapshot
+    }
+
+    pub fn new(transactions: &[Transaction], months: usize, week_start: Weekday) -> Self {
         let earned = calculate_earned(transactions);
         let spent = calculate_spent(transactions);
         let balance = earned - spent;
 
         let per_tag = calculate_spent_per_tag(transactions);
-        let monthly_history = calculate_monthly_history(transactions);
+        let earned_per_source = calculate_earned_per_source(transactions);
+        let monthly_history = calculate_monthly_history(transactions, months);
+        let weekly_history = calculate_weekly_history(transactions, WEEKS_SHOWN, week_start);
 
         let tx_count = transactions.len();
         let largest = get_largest_transaction(transactions);
         let smallest = get_smallest_transaction(transactions);
 
         let top_tags = get_top_tags(&per_tag);
+        let (uncleared_count, uncleared) = calculate_uncleared(transactions);
+        let month_comparison =
+            MonthComparison::for_month(transactions, chrono::Local::now().date_naive());
 
         Self {
             earned,
             spent,
             balance,
             per_tag,
+            earned_per_source,
             monthly_history,
+            weekly_history,
+            week_start,
+            locale: Locale::default(),
             tx_count,
             largest,
             smallest,
             top_tags,
+            uncleared_count,
+            uncleared,
+            month_comparison,
+            net_worth: Vec::new(),
+            opening_balance: 0.0,
+            savings_thresholds: (0.0, 20.0),
+            goals: Vec::new(),
+        }
+    }
+
+    /// Start the balance from `opening` instead of zero. Every balance shown
+    /// (header, running column, stats) reads from here.
+    pub fn set_opening_balance(&mut self, opening: f64) {
+        self.balance += opening - self.opening_balance;
+        self.opening_balance = opening;
+    }
+
+    /// Earned minus spent, as a percentage of earned. `None` without any
+    /// income, where a rate means nothing.
+    pub fn savings_rate(&self) -> Option<f64> {
+        (self.earned > 0.0).then(|| (self.earned - self.spent) / self.earned * 100.0)
+    }
+
+    /// Green once the rate is healthy, red below the low threshold or
+    /// without income, accent in between.
+    pub fn savings_rate_color(&self, theme: &Theme) -> Color {
+        let (low, healthy) = self.savings_thresholds;
+        match self.savings_rate() {
+            Some(rate) if rate >= healthy => theme.credit,
+            Some(rate) if rate >= low => theme.accent,
+            _ => theme.debit,
+        }
+    }
+
+    pub fn savings_rate_text(&self) -> String {
+        match self.savings_rate() {
+            Some(rate) => format!("{:.1}%", rate),
+            None => "n/a".to_string(),
         }
     }
 }
@@ -50,22 +234,103 @@ impl StatsSnapshot {
 // Stats calculation functions
 // ============================================================================
 
+/// Progress toward `goal`. Untagged goals count the whole `balance`;
+/// tagged ones the net amount put under the tag, debits counting as saved.
+/// The projection extends the average of the last `months` months.
+pub fn calculate_goal_progress(
+    goal: &GoalConfig,
+    transactions: &[Transaction],
+    balance: f64,
+    months: usize,
+    today: NaiveDate
+) -> GoalProgress {
+    let tag = goal.tag.as_deref().map(Tag::from_str);
+    let first_month = today.with_day(1).unwrap_or(today) - Months::new(months.saturating_sub(1) as u32);
+    let window_start = first_month.format("%Y-%m-%d").to_string();
+
+    // Net saved per transaction: income grows the balance, while for a
+    // tag the money moved into it is recorded as spending
+    let saved_by = |tx: &Transaction| match (&tag, tx.kind) {
+        (None, TransactionType::Credit) | (Some(_), TransactionType::Debit) => tx.amount,
+        _ => -tx.amount,
+    };
+    let relevant = transactions
+        .iter()
+        .filter(|tx| tag.as_ref().is_none_or(|t| tx.tag == *t));
+
+    let saved = match &tag {
+        Some(_) => relevant.clone().map(saved_by).sum(),
+        None => balance,
+    };
+    let recent: f64 = relevant
+        .filter(|tx| tx.date.as_str() >= window_start.as_str())
+        .map(saved_by)
+        .sum();
+    let per_month = recent / months.max(1) as f64;
+
+    let projected = if saved >= goal.amount || per_month <= 0.0 {
+        None
+    } else {
+        let months_left = ((goal.amount - saved) / per_month).ceil() as u32;
+        today.checked_add_months(Months::new(months_left))
+    };
+
+    GoalProgress {
+        name: goal.name.trim().to_string(),
+        saved,
+        amount: goal.amount,
+        target: goal.target_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok()),
+        projected,
+    }
+}
+
 /// Calculate total earned transactions from app transactions
 pub fn calculate_earned(transactions: &[Transaction]) -> f64 {
-    transactions
+    let amounts = transactions
         .iter()
         .filter(|tx| tx.kind == TransactionType::Credit)
-        .map(|tx| tx.amount)
-        .sum()
+        .map(|tx| tx.amount);
+    sum_amounts(amounts)
 }
 
 /// Calculate total spent transactions from app transactions
 pub fn calculate_spent(transactions: &[Transaction]) -> f64 {
-    transactions
+    let amounts = transactions
         .iter()
         .filter(|tx| tx.kind == TransactionType::Debit)
-        .map(|tx| tx.amount)
-        .sum()
+        .map(|tx| tx.amount);
+    sum_amounts(amounts)
+}
+
+/// Total credits dated in `month` (YYYY-MM)
+pub fn total_earned_in_month(transactions: &[Transaction], month: &str) -> f64 {
+    let amounts = transactions
+        .iter()
+        .filter(|tx| tx.kind == TransactionType::Credit && tx.date.starts_with(month))
+        .map(|tx| tx.amount);
+    sum_amounts(amounts)
+}
+
+/// Total debits dated in `month` (YYYY-MM)
+pub fn total_spent_in_month(transactions: &[Transaction], month: &str) -> f64 {
+    let amounts = transactions
+        .iter()
+        .filter(|tx| tx.kind == TransactionType::Debit && tx.date.starts_with(month))
+        .map(|tx| tx.amount);
+    sum_amounts(amounts)
+}
+
+/// Count and net amount (credits minus debits) of transactions not yet cleared
+pub fn calculate_uncleared(transactions: &[Transaction]) -> (usize, f64) {
+    transactions
+        .iter()
+        .filter(|tx| !tx.cleared)
+        .fold((0, 0.0), |(count, net), tx| match tx.kind {
+            TransactionType::Credit => (count + 1, net + tx.amount),
+            TransactionType::Debit => (count + 1, net - tx.amount),
+        })
 }
 
 /// Build a map of spending per tag from all debit transactions
@@ -77,6 +342,22 @@ pub fn calculate_spent_per_tag(transactions: &[Transaction]) -> HashMap<Tag, f64
     map
 }
 
+/// Income per source from all credit transactions, largest first. Like the
+/// rest of the snapshot, `transactions` are already in the base currency.
+pub fn calculate_earned_per_source(transactions: &[Transaction]) -> Vec<(String, f64)> {
+    let mut map: HashMap<&str, Vec<f64>> = HashMap::new();
+    for tx in transactions.iter().filter(|tx| tx.kind == TransactionType::Credit) {
+        map.entry(tx.source.as_str()).or_default().push(tx.amount);
+    }
+
+    let mut sources: Vec<(String, f64)> = map
+        .into_iter()
+        .map(|(source, amounts)| (source.to_string(), sum_amounts(amounts.into_iter())))
+        .collect();
+    sources.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sources
+}
+
 /// Get the largest transaction by amount
 pub fn get_largest_transaction(transactions: &[Transaction]) -> Option<Transaction> {
     transactions
@@ -104,8 +385,8 @@ pub fn get_top_tags(per_tag: &HashMap<Tag, f64>) -> Vec<(Tag, f64)> {
 }
 
 /// Calculate monthly history grouped by month (YYYY-MM)
-/// Returns up to 6 most recent months with (month, earned, spent) tuples
-pub fn calculate_monthly_history(transactions: &[Transaction]) -> Vec<(String, f64, f64)> {
+/// Returns up to `months` most recent months with (month, earned, spent) tuples
+pub fn calculate_monthly_history(transactions: &[Transaction], months: usize) -> Vec<(String, f64, f64)> {
     let mut monthly_map: BTreeMap<String, (f64, f64)> = BTreeMap::new();
 
     for tx in transactions {
@@ -126,29 +407,91 @@ pub fn calculate_monthly_history(transactions: &[Transaction]) -> Vec<(String, f
     monthly_map
         .into_iter()
         .rev()
-        .take(6)
+        .take(months)
         .map(|(m, (e, s))| (m, e, s))
         .collect()
 }
 
-// ============================================================================
-// Stats UI rendering functions
-// ============================================================================
+// Weeks listed in the Stats view
+const WEEKS_SHOWN: usize = 6;
 
-pub fn draw_stats_view(f: &mut Frame, area: Rect, snapshot: &StatsSnapshot, theme: &Theme, currency: &str) {
-    let earned = snapshot.earned;
-    let spent = snapshot.spent;
-    let balance = snapshot.balance;
+/// First day of the week containing `date`, for weeks beginning on `week_start`.
+pub fn week_start_of(date: NaiveDate, week_start: Weekday) -> NaiveDate {
+    date.week(week_start).first_day()
+}
 
-    let per_tag = &snapshot.per_tag;
-    let monthly_history = &snapshot.monthly_history;
+/// Like `calculate_monthly_history`, but grouped by week. Each week is keyed
+/// by its first day (YYYY-MM-DD); rows with unparseable dates are skipped.
+pub fn calculate_weekly_history(
+    transactions: &[Transaction],
+    weeks: usize,
+    week_start: Weekday
+) -> Vec<(String, f64, f64)> {
+    let mut weekly_map: BTreeMap<NaiveDate, (f64, f64)> = BTreeMap::new();
 
-    let tx_count = snapshot.tx_count;
+    for tx in transactions {
+        let Some(date) = tx.date.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()) else {
+            continue;
+        };
 
-    // Clone only the Options (for now)
-    let largest = snapshot.largest.clone();
-    let smallest = snapshot.smallest.clone();
+        let entry = weekly_map.entry(week_start_of(date, week_start)).or_insert((0.0, 0.0));
+
+        match tx.kind {
+            TransactionType::Credit => {
+                entry.0 += tx.amount;
+            }
+            TransactionType::Debit => {
+                entry.1 += tx.amount;
+            }
+        }
+    }
 
+    weekly_map
+        .into_iter()
+        .rev()
+        .take(weeks)
+        .map(|(w, (e, s))| (w.format("%Y-%m-%d").to_string(), e, s))
+        .collect()
+}
+
+/// Expand a sparse (YYYY-MM, amount) series into the `months` consecutive
+/// months ending at `end_month`, with 0.0 for months that had nothing.
+pub fn zero_fill_months(series: &[(String, f64)], end_month: NaiveDate, months: usize) -> Vec<(String, f64)> {
+    let mut out = Vec::with_capacity(months);
+    let mut month = end_month.with_day(1).unwrap_or(end_month);
+
+    for _ in 0..months {
+        let key = month.format("%Y-%m").to_string();
+        let amount = series
+            .iter()
+            .find(|(m, _)| *m == key)
+            .map(|(_, a)| *a)
+            .unwrap_or(0.0);
+        out.push((key, amount));
+
+        month = match month.checked_sub_months(Months::new(1)) {
+            Some(prev) => prev,
+            None => break,
+        };
+    }
+
+    out.reverse();
+    out
+}
+
+// ============================================================================
+// Stats UI rendering functions
+// ============================================================================
+
+pub fn draw_stats_view(
+    f: &mut Frame,
+    area: Rect,
+    snapshot: &StatsSnapshot,
+    theme: &Theme,
+    money: &Money,
+    scroll: u16
+) {
+    let monthly_history = &snapshot.monthly_history;
     let top_tags = &snapshot.top_tags;
 
     let layout = Layout::default()
@@ -157,14 +500,24 @@ pub fn draw_stats_view(f: &mut Frame, area: Rect, snapshot: &StatsSnapshot, them
         .constraints([Constraint::Min(1), Constraint::Length(3)])
         .split(area);
 
-    // Split main stats area into top charts and bottom breakdown
+    // Split main stats area into top charts, the net worth trend (once
+    // there are at least two snapshots to connect) and bottom breakdown
+    let trend_height = if snapshot.net_worth.len() > 1 { 8 } else { 0 };
     let top_bottom = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(12), Constraint::Min(1)])
+        .constraints([
+            Constraint::Length(12),
+            Constraint::Length(trend_height),
+            Constraint::Min(1),
+        ])
         .split(layout[0]);
 
     let charts_area = top_bottom[0];
-    let breakdown_area = top_bottom[1];
+    let breakdown_area = top_bottom[2];
+
+    if trend_height > 0 {
+        draw_net_worth_chart(f, top_bottom[1], &snapshot.net_worth, snapshot.locale, theme, money);
+    }
 
     // Charts area: left = monthly history, right = top tags
     let cols = Layout::default()
@@ -177,7 +530,7 @@ pub fn draw_stats_view(f: &mut Frame, area: Rect, snapshot: &StatsSnapshot, them
     let mut earned_vals: Vec<u64> = Vec::new();
     let mut spent_vals: Vec<u64> = Vec::new();
     for (m, e, s) in monthly_history.iter().rev() {
-        month_labels.push(m.clone());
+        month_labels.push(snapshot.locale.month_label(m));
         earned_vals.push((*e).round().abs() as u64);
         spent_vals.push((*s).round().abs() as u64);
     }
@@ -203,7 +556,9 @@ pub fn draw_stats_view(f: &mut Frame, area: Rect, snapshot: &StatsSnapshot, them
         .max(max_month.max(1))
         .bar_width(7)
         .bar_gap(1)
-        .bar_style(Style::default().fg(theme.credit));
+        .bar_set(theme.glyphs.bar_set())
+        .bar_style(Style::default().fg(theme.credit))
+        .value_style(bar_value_style(money, theme.credit, theme));
 
     f.render_widget(earned_chart, cols[0]);
 
@@ -228,34 +583,63 @@ pub fn draw_stats_view(f: &mut Frame, area: Rect, snapshot: &StatsSnapshot, them
 
     let tags_chart = BarChart::default()
         .data(&tag_bars)
-        .block(Block::default().title("Top Tags").borders(ratatui::widgets::Borders::ALL))
+        .block(
+            Block::default()
+                .title("Top Tags")
+                .borders(ratatui::widgets::Borders::ALL)
+                .border_set(theme.glyphs.plain_border)
+        )
         .max(max_tag.max(1))
         .bar_width(6)
         .bar_gap(1)
-        .bar_style(Style::default().fg(theme.debit));
+        .bar_set(theme.glyphs.bar_set())
+        .bar_style(Style::default().fg(theme.debit))
+        .value_style(bar_value_style(money, theme.debit, theme));
 
     f.render_widget(tags_chart, cols[1]);
 
-    // Below charts: breakdown paragraph (reuse existing content builder for details)
-    let breakdown_lines = build_stats_content(
-        earned,
-        spent,
-        balance,
-        per_tag,
-        monthly_history,
-        tx_count,
-        largest,
-        smallest,
-        top_tags,
-        theme,
-        currency
-    );
+    // Below charts: breakdown paragraph, scrolled when it doesn't fit
+    let breakdown_lines = details_lines(snapshot, theme, money);
+    let total = breakdown_lines.len();
+    let visible = breakdown_area.height.saturating_sub(2) as usize;
+    let max_offset = total.saturating_sub(visible);
+    let offset = (scroll as usize).min(max_offset);
+
+    let title = if offset < max_offset {
+        format!("Details {} more", theme.glyphs.down)
+    } else {
+        "Details".to_string()
+    };
+    // Savings rate sits in the title so it stays in view while scrolling
+    let savings = Title::from(
+        Line::from(vec![
+            Span::styled(" Savings rate ", theme.muted_text()),
+            Span::styled(
+                snapshot.savings_rate_text(),
+                Style::default().fg(snapshot.savings_rate_color(theme)).add_modifier(Modifier::BOLD)
+            ),
+            Span::raw(" "),
+        ])
+    ).alignment(Alignment::Right);
     let breakdown = Paragraph::new(breakdown_lines)
-        .block(theme.block("Details"))
-        .alignment(Alignment::Left);
+        .block(theme.block(&title).title(savings))
+        .alignment(Alignment::Left)
+        .scroll((offset as u16, 0));
 
     f.render_widget(breakdown, breakdown_area);
 
+    if max_offset > 0 {
+        let mut state = ScrollbarState::new(max_offset + 1).position(offset);
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .thumb_symbol(theme.glyphs.scrollbar_thumb)
+            .track_symbol(Some(theme.glyphs.scrollbar_track))
+            .thumb_style(Style::default().fg(theme.accent))
+            .track_style(Style::default().fg(theme.subtle));
+        f.render_stateful_widget(scrollbar, breakdown_area, &mut state);
+    }
+
     // Enhanced footer
     let footer_block = Block::default()
         .borders(ratatui::widgets::Borders::TOP)
@@ -267,7 +651,17 @@ pub fn draw_stats_view(f: &mut Frame, area: Rect, snapshot: &StatsSnapshot, them
         Line::from(vec![
             Span::styled("  [Esc] Back", Style::default().fg(theme.muted)),
             Span::styled("  ", Style::default()),
-            Span::styled("[Tab/←→] Switch view", Style::default().fg(theme.muted)),
+            Span::styled(format!("[Tab/{}] Switch view", theme.glyphs.left_right), Style::default().fg(theme.muted)),
+            Span::styled("  ", Style::default()),
+            Span::styled(format!("[{}] Scroll", theme.glyphs.up_down), Style::default().fg(theme.muted)),
+            Span::styled("  ", Style::default()),
+            Span::styled("[t] Tag trend", Style::default().fg(theme.muted)),
+            Span::styled("  ", Style::default()),
+            Span::styled("[y] Year", Style::default().fg(theme.muted)),
+            Span::styled("  ", Style::default()),
+            Span::styled("[s] Sources", Style::default().fg(theme.muted)),
+            Span::styled("  ", Style::default()),
+            Span::styled("[x] Export chart data", Style::default().fg(theme.muted)),
         ])
     )
         .block(footer_block)
@@ -276,27 +670,79 @@ pub fn draw_stats_view(f: &mut Frame, area: Rect, snapshot: &StatsSnapshot, them
     f.render_widget(footer, layout[1]);
 }
 
-fn build_stats_content(
-    earned: f64,
-    spent: f64,
-    balance: f64,
-    per_tag: &HashMap<Tag, f64>,
-    monthly_history: &[(String, f64, f64)],
-    tx_count: usize,
-    largest: Option<Transaction>,
-    smallest: Option<Transaction>,
-    top_tags: &[(Tag, f64)],
+/// Bar values are printed inside the bars; in privacy mode they're drawn in
+/// the bar's own colour so they disappear.
+fn bar_value_style(money: &Money, bar: Color, theme: &Theme) -> Style {
+    if money.hidden {
+        Style::default().fg(bar).bg(bar)
+    } else {
+        Style::default().fg(theme.background).bg(bar).add_modifier(Modifier::BOLD)
+    }
+}
+
+fn draw_net_worth_chart(
+    f: &mut Frame,
+    area: Rect,
+    net_worth: &[(String, f64)],
+    locale: Locale,
     theme: &Theme,
-    currency: &str
-) -> Vec<Line<'static>> {
+    money: &Money
+) {
+    let points: Vec<(f64, f64)> = net_worth
+        .iter()
+        .enumerate()
+        .map(|(i, (_, balance))| (i as f64, *balance))
+        .collect();
+
+    let min = points.iter().map(|(_, b)| *b).fold(f64::INFINITY, f64::min).min(0.0);
+    let max = points.iter().map(|(_, b)| *b).fold(f64::NEG_INFINITY, f64::max).max(0.0);
+    let last_x = (points.len() - 1) as f64;
+
+    let first_month = net_worth.first().map(|(m, _)| locale.month_label(m)).unwrap_or_default();
+    let last_month = net_worth.last().map(|(m, _)| locale.month_label(m)).unwrap_or_default();
+
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(theme.accent))
+        .data(&points);
+
+    let chart = Chart::new(vec![dataset])
+        .block(theme.block("Net Worth"))
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.subtle))
+                .bounds([0.0, last_x.max(1.0)])
+                .labels(vec![Span::raw(first_month), Span::raw(last_month)])
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.subtle))
+                .bounds([min, max.max(min + 1.0)])
+                .labels(vec![Span::raw(money.format(min)), Span::raw(money.format(max))])
+        );
+
+    f.render_widget(chart, area);
+}
+
+/// Lines of the scrollable Details panel.
+pub fn details_lines(snapshot: &StatsSnapshot, theme: &Theme, money: &Money) -> Vec<Line<'static>> {
     let mut lines = Vec::new();
 
     lines.push(Line::raw(""));
-    lines.extend(create_overview_section(earned, spent, balance, theme, currency));
+    lines.extend(
+        create_overview_section(snapshot, theme, money)
+    );
+    lines.push(Line::raw(""));
+    lines.extend(create_month_comparison_section(&snapshot.month_comparison, theme, money));
     lines.push(Line::raw(""));
+    if !snapshot.goals.is_empty() {
+        lines.extend(create_goals_section(&snapshot.goals, theme, money));
+        lines.push(Line::raw(""));
+    }
     lines.push(
         Line::styled(
-            "  ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━",
+            format!("  {}", theme.glyphs.heavy_rule.repeat(64)),
             Style::default().fg(theme.subtle)
         )
     );
@@ -306,12 +752,20 @@ fn build_stats_content(
     lines.push(
         Line::styled(
             format!(
-                "  Transactions: {}  |  Total Earned: {}{:.2}  |  Total Spent: {}{:.2}",
-                tx_count,
-                currency,
-                earned,
-                currency,
-                spent
+                "  Transactions: {}  |  Total Earned: {}  |  Total Spent: {}",
+                snapshot.tx_count,
+                money.format(snapshot.earned),
+                money.format(snapshot.spent)
+            ),
+            Style::default().fg(theme.muted)
+        )
+    );
+    lines.push(
+        Line::styled(
+            format!(
+                "  Uncleared: {}  |  Net Pending: {}",
+                snapshot.uncleared_count,
+                money.format(snapshot.uncleared)
             ),
             Style::default().fg(theme.muted)
         )
@@ -321,12 +775,12 @@ fn build_stats_content(
     // Monthly history mini-table
     lines.push(
         Line::styled(
-            "  Last Months (YYYY-MM)  Earned      Spent",
+            "  Last Months            Earned      Spent",
             Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
         )
     );
     lines.push(Line::raw(""));
-    if monthly_history.is_empty() {
+    if snapshot.monthly_history.is_empty() {
         lines.push(
             Line::styled(
                 "     No monthly data available.",
@@ -334,20 +788,60 @@ fn build_stats_content(
             )
         );
     } else {
-        for (m, e, s) in monthly_history {
+        for (m, e, s) in &snapshot.monthly_history {
+            lines.push(
+                Line::from(
+                    vec![
+                        Span::raw("     "),
+                        Span::styled(format!("{:<7}", snapshot.locale.month_label(m)), Style::default().fg(theme.foreground)),
+                        Span::raw("  "),
+                        Span::styled(
+                            money.padded(*e, 9),
+                            Style::default().fg(theme.credit)
+                        ),
+                        Span::raw("  "),
+                        Span::styled(
+                            money.padded(*s, 9),
+                            Style::default().fg(theme.debit)
+                        )
+                    ]
+                )
+            );
+        }
+    }
+
+    lines.push(Line::raw(""));
+
+    // Weekly mini-table, keyed by the day each week starts
+    lines.push(
+        Line::styled(
+            format!("  Last Weeks (from {})  Earned      Spent", snapshot.week_start),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+        )
+    );
+    lines.push(Line::raw(""));
+    if snapshot.weekly_history.is_empty() {
+        lines.push(
+            Line::styled(
+                "     No weekly data available.",
+                Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC)
+            )
+        );
+    } else {
+        for (w, e, s) in &snapshot.weekly_history {
             lines.push(
                 Line::from(
                     vec![
                         Span::raw("     "),
-                        Span::styled(format!("{:<7}", m), Style::default().fg(theme.foreground)),
+                        Span::styled(format!("{:<10}", w), Style::default().fg(theme.foreground)),
                         Span::raw("  "),
                         Span::styled(
-                            format!("{}{:>9.2}", currency, e),
+                            money.padded(*e, 9),
                             Style::default().fg(theme.credit)
                         ),
                         Span::raw("  "),
                         Span::styled(
-                            format!("{}{:>9.2}", currency, s),
+                            money.padded(*s, 9),
                             Style::default().fg(theme.debit)
                         )
                     ]
@@ -366,7 +860,7 @@ fn build_stats_content(
         )
     );
     lines.push(Line::raw(""));
-    if top_tags.is_empty() {
+    if snapshot.top_tags.is_empty() {
         lines.push(
             Line::styled(
                 "     No category data.",
@@ -374,7 +868,7 @@ fn build_stats_content(
             )
         );
     } else {
-        for (i, (tag, amt)) in top_tags.iter().take(5).enumerate() {
+        for (i, (tag, amt)) in snapshot.top_tags.iter().take(5).enumerate() {
             lines.push(
                 Line::from(
                     vec![
@@ -385,7 +879,7 @@ fn build_stats_content(
                         ),
                         Span::raw("  "),
                         Span::styled(
-                            format!("{}{:>9.2}", currency, amt),
+                            money.padded(*amt, 9),
                             Style::default().fg(theme.debit)
                         )
                     ]
@@ -404,17 +898,16 @@ fn build_stats_content(
         )
     );
     lines.push(Line::raw(""));
-    if let Some(tx) = largest {
+    if let Some(tx) = &snapshot.largest {
         lines.push(
             Line::from(
                 vec![
                     Span::raw("     Largest: "),
                     Span::styled(
                         format!(
-                            "{} | {}{:.2} | #{}",
+                            "{} | {} | #{}",
                             tx.source,
-                            currency,
-                            tx.amount,
+                            money.format(tx.amount),
                             tx.tag.as_str()
                         ),
                         Style::default().fg(theme.foreground)
@@ -423,17 +916,16 @@ fn build_stats_content(
             )
         );
     }
-    if let Some(tx) = smallest {
+    if let Some(tx) = &snapshot.smallest {
         lines.push(
             Line::from(
                 vec![
                     Span::raw("     Smallest: "),
                     Span::styled(
                         format!(
-                            "{} | {}{:.2} | #{}",
+                            "{} | {} | #{}",
                             tx.source,
-                            currency,
-                            tx.amount,
+                            money.format(tx.amount),
                             tx.tag.as_str()
                         ),
                         Style::default().fg(theme.foreground)
@@ -446,7 +938,7 @@ fn build_stats_content(
     lines.push(Line::raw(""));
     lines.push(
         Line::styled(
-            "  ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━",
+            format!("  {}", theme.glyphs.heavy_rule.repeat(64)),
             Style::default().fg(theme.subtle)
         )
     );
@@ -454,13 +946,13 @@ fn build_stats_content(
     lines.push(Line::raw(""));
     lines.push(
         Line::styled(
-            "  📊 Spending Breakdown by Category",
+            format!("  {}Spending Breakdown by Category", theme.glyphs.breakdown_icon),
             Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
         )
     );
     lines.push(Line::raw(""));
 
-    if per_tag.is_empty() {
+    if snapshot.per_tag.is_empty() {
         lines.push(
             Line::styled(
                 "     No spending data available yet.",
@@ -468,38 +960,70 @@ fn build_stats_content(
             )
         );
     } else {
-        lines.extend(create_tag_breakdown_section(per_tag, theme, currency));
+        lines.extend(create_tag_breakdown_section(&snapshot.per_tag, theme, money));
+    }
+
+    lines.push(Line::raw(""));
+    lines.push(
+        Line::styled(
+            format!("  {}Income by Source", theme.glyphs.breakdown_icon),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+        )
+    );
+    lines.push(Line::raw(""));
+
+    if snapshot.earned_per_source.is_empty() {
+        lines.push(
+            Line::styled(
+                "     No income recorded yet.",
+                Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC)
+            )
+        );
+    } else {
+        lines.extend(create_income_breakdown_section(&snapshot.earned_per_source, theme, money));
     }
 
     lines.push(Line::raw(""));
     lines
 }
 
-fn create_overview_section(
-    earned: f64,
-    spent: f64,
-    balance: f64,
-    theme: &Theme,
-    currency: &str
-) -> Vec<Line<'static>> {
+fn create_overview_section(snapshot: &StatsSnapshot, theme: &Theme, money: &Money) -> Vec<Line<'static>> {
+    let (earned, spent, balance) = (snapshot.earned, snapshot.spent, snapshot.balance);
+    let opening_balance = snapshot.opening_balance;
     let balance_color = if balance >= 0.0 { theme.credit } else { theme.debit };
-    let savings_rate = if earned > 0.0 {
-        (((earned - spent) / earned) * 100.0).max(0.0)
-    } else {
-        0.0
-    };
 
-    vec![
+    let mut lines = vec![
         Line::styled(
-            "  💰 Financial Overview",
+            format!("  {}Financial Overview", theme.glyphs.overview_icon),
             Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
         ),
-        Line::raw(""),
+        Line::raw("")
+    ];
+
+    // Spell out the opening balance so the Balance line adds up
+    let balance_note = if opening_balance != 0.0 {
+        lines.push(
+            Line::from(
+                vec![
+                    Span::raw("     Opening       : "),
+                    Span::styled(
+                        money.padded(opening_balance, 10),
+                        Style::default().fg(theme.muted)
+                    )
+                ]
+            )
+        );
+        "  (incl. opening)"
+    } else {
+        ""
+    };
+
+    lines.extend(vec![
         Line::from(
             vec![
                 Span::raw("     Total Earned  : "),
                 Span::styled(
-                    format!("{}{:>10.2}", currency, earned),
+                    money.padded(earned, 10),
                     Style::default().fg(theme.credit).add_modifier(Modifier::BOLD)
                 )
             ]
@@ -508,7 +1032,7 @@ fn create_overview_section(
             vec![
                 Span::raw("     Total Spent   : "),
                 Span::styled(
-                    format!("{}{:>10.2}", currency, spent),
+                    money.padded(spent, 10),
                     Style::default().fg(theme.debit).add_modifier(Modifier::BOLD)
                 )
             ]
@@ -517,31 +1041,155 @@ fn create_overview_section(
             vec![
                 Span::raw("     Balance       : "),
                 Span::styled(
-                    format!("{}{:>10.2}", currency, balance),
+                    money.padded(balance, 10),
                     Style::default()
                         .fg(balance_color)
                         .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
-                )
+                ),
+                Span::styled(balance_note, Style::default().fg(theme.muted))
             ]
         ),
         Line::from(
             vec![
                 Span::raw("     Savings Rate  : "),
                 Span::styled(
-                    format!("{:>9.1}%", savings_rate),
-                    Style::default()
-                        .fg(if savings_rate > 20.0 { theme.credit } else { theme.accent })
-                        .add_modifier(Modifier::BOLD)
+                    format!("{:>10}", snapshot.savings_rate_text()),
+                    Style::default().fg(snapshot.savings_rate_color(theme)).add_modifier(Modifier::BOLD)
                 )
             ]
         )
-    ]
+    ]);
+
+    lines
+}
+
+fn create_month_comparison_section(
+    comparison: &MonthComparison,
+    theme: &Theme,
+    money: &Money
+) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::styled(
+            "  This Month vs Last",
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+        ),
+        Line::raw("")
+    ];
+
+    // Earning and saving more is good; spending more is not
+    let rows = [
+        ("     Earned        : ", comparison.earned, true),
+        ("     Spent         : ", comparison.spent, false),
+        ("     Net           : ", comparison.net(), true),
+    ];
+
+    for (label, (this, prev), up_is_good) in rows {
+        let delta = this - prev;
+        let (arrow, color) = if delta == 0.0 {
+            ("=", theme.muted)
+        } else if (delta > 0.0) == up_is_good {
+            (if delta > 0.0 { theme.glyphs.credit } else { theme.glyphs.debit }, theme.credit)
+        } else {
+            (if delta > 0.0 { theme.glyphs.credit } else { theme.glyphs.debit }, theme.debit)
+        };
+
+        lines.push(
+            Line::from(
+                vec![
+                    Span::raw(label),
+                    Span::styled(money.padded(this, 10), Style::default().fg(theme.foreground)),
+                    Span::raw("  "),
+                    Span::styled(
+                        format!("{} {}", arrow, money.format(delta.abs())),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD)
+                    ),
+                    Span::styled(" vs last month", Style::default().fg(theme.muted))
+                ]
+            )
+        );
+    }
+
+    lines
+}
+
+fn create_goals_section(goals: &[GoalProgress], theme: &Theme, money: &Money) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::styled(
+            "  Savings Goals",
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+        ),
+        Line::raw("")
+    ];
+
+    for goal in goals {
+        // Reached goals stand out; ones that won't make their date are red
+        let color = if goal.reached() {
+            theme.credit
+        } else if goal.behind() {
+            theme.debit
+        } else {
+            theme.accent
+        };
+
+        let filled = calculate_bar_width(goal.percent(), 100.0);
+        let status = if goal.reached() {
+            format!("{} reached", theme.glyphs.cleared)
+        } else {
+            match goal.projected {
+                Some(date) => format!("on pace for {}", date.format("%Y-%m")),
+                None => "not saving yet".to_string(),
+            }
+        };
+        let due = goal.target
+            .map(|d| format!("  due {}", d.format("%Y-%m-%d")))
+            .unwrap_or_default();
+
+        let mut name_style = Style::default().fg(theme.foreground);
+        if goal.reached() {
+            name_style = name_style.fg(theme.credit).add_modifier(Modifier::BOLD);
+        }
+
+        lines.push(
+            Line::from(
+                vec![
+                    Span::raw("     "),
+                    Span::styled(format!("{:<14}", goal.name), name_style),
+                    Span::raw(" "),
+                    Span::styled(theme.glyphs.bar_full.repeat(filled), Style::default().fg(color)),
+                    Span::styled(
+                        theme.glyphs.bar_empty.repeat((20usize).saturating_sub(filled)),
+                        Style::default().fg(theme.subtle)
+                    ),
+                    Span::styled(
+                        format!(" {:>5.1}%", goal.percent()),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD)
+                    )
+                ]
+            )
+        );
+        lines.push(
+            Line::from(
+                vec![
+                    Span::raw("       "),
+                    Span::styled(
+                        format!("{} of {}", money.format(goal.saved), money.format(goal.amount)),
+                        Style::default().fg(theme.muted)
+                    ),
+                    Span::styled(due, Style::default().fg(theme.muted)),
+                    Span::raw("  "),
+                    Span::styled(status, Style::default().fg(color))
+                ]
+            )
+        );
+    }
+
+    lines
 }
 
 fn create_tag_breakdown_section(
     per_tag: &HashMap<Tag, f64>,
     theme: &Theme,
-    currency: &str
+    money: &Money
 ) -> Vec<Line<'static>> {
     let mut tag_vec: Vec<_> = per_tag.iter().collect();
     tag_vec.sort_by(|a, b| b.1.partial_cmp(a.1).unwrap());
@@ -557,37 +1205,70 @@ fn create_tag_breakdown_section(
     for (tag, &amount) in tag_vec {
         let percentage = if total_spent > 0.0 { (amount / total_spent) * 100.0 } else { 0.0 };
 
-        lines.push(create_tag_bar(tag.as_str(), amount, percentage, max_spent, theme, currency));
+        let label = format!("#{}", tag.as_str());
+        lines.push(create_tag_bar(&label, amount, percentage, max_spent, theme.debit, theme, money));
+    }
+
+    lines
+}
+
+// Sources listed in the income breakdown; the rest are summed up
+const INCOME_SOURCES_SHOWN: usize = 8;
+
+fn create_income_breakdown_section(
+    earned_per_source: &[(String, f64)],
+    theme: &Theme,
+    money: &Money
+) -> Vec<Line<'static>> {
+    let max_earned = earned_per_source.first().map(|(_, v)| *v).unwrap_or(0.0);
+    let total_earned: f64 = earned_per_source.iter().map(|(_, v)| v).sum();
+
+    let mut lines = Vec::new();
+
+    for (source, amount) in earned_per_source.iter().take(INCOME_SOURCES_SHOWN) {
+        let percentage = if total_earned > 0.0 { (amount / total_earned) * 100.0 } else { 0.0 };
+        let label = crate::ui::truncate_string(source, 13, theme.glyphs.ellipsis);
+        lines.push(create_tag_bar(&label, *amount, percentage, max_earned, theme.credit, theme, money));
+    }
+
+    let rest = &earned_per_source[earned_per_source.len().min(INCOME_SOURCES_SHOWN)..];
+    if !rest.is_empty() {
+        let amount: f64 = rest.iter().map(|(_, v)| v).sum();
+        lines.push(Line::styled(
+            format!("     +{} more sources, {}", rest.len(), money.format(amount)),
+            Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
+        ));
     }
 
     lines
 }
 
 fn create_tag_bar(
-    tag: &str,
+    label: &str,
     amount: f64,
     percentage: f64,
     max_amount: f64,
+    bar_color: Color,
     theme: &Theme,
-    currency: &str
+    money: &Money
 ) -> Line<'static> {
     let bar_width = calculate_bar_width(amount, max_amount);
-    let bar = "█".repeat(bar_width);
-    let empty_bar = "░".repeat((20usize).saturating_sub(bar_width));
+    let bar = theme.glyphs.bar_full.repeat(bar_width);
+    let empty_bar = theme.glyphs.bar_empty.repeat((20usize).saturating_sub(bar_width));
 
     Line::from(
         vec![
             Span::raw("     "),
             Span::styled(
-                format!("#{:<12}", tag),
+                format!("{:<13}", label),
                 Style::default().fg(theme.accent_soft).add_modifier(Modifier::ITALIC)
             ),
             Span::raw(" "),
-            Span::styled(bar, Style::default().fg(theme.debit)),
+            Span::styled(bar, Style::default().fg(bar_color)),
             Span::styled(empty_bar, Style::default().fg(theme.subtle)),
             Span::raw("  "),
             Span::styled(
-                format!("{}{:>9.2}", currency, amount),
+                money.padded(amount, 9),
                 Style::default().fg(theme.foreground).add_modifier(Modifier::BOLD)
             ),
             Span::raw(" "),
@@ -604,11 +1285,39 @@ fn calculate_bar_width(amount: f64, max_amount: f64) -> usize {
 // Stats input handler
 // ============================================================================
 
-pub fn handle_stats(app: &mut App, key: KeyCode) -> bool {
+pub fn handle_stats(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
     match key {
-        KeyCode::Esc => {
-            app.mode = crate::app::Mode::Normal;
-        }
+        KeyCode::Esc => app.mode = crate::app::Mode::Normal,
+        KeyCode::Up => app.scroll_stats_up(),
+        KeyCode::Down => app.scroll_stats_down(),
+        KeyCode::Char('t') => app.open_tag_trend(conn),
+        KeyCode::Char('y') => app.open_year_summary(conn),
+        KeyCode::Char('x') => app.export_chart_data(conn),
+        KeyCode::Char('s') => app.open_source_report(conn),
+        _ => {}
+    }
+
+    false
+}
+
+/// Keys for the annual summary popup opened from the Stats view.
+pub fn handle_year_summary(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
+    match key {
+        KeyCode::Esc | KeyCode::Char('y') => app.mode = crate::app::Mode::Stats,
+        KeyCode::Right => app.step_summary_year(conn, 1),
+        KeyCode::Left => app.step_summary_year(conn, -1),
+        _ => {}
+    }
+
+    false
+}
+
+/// Keys for the per-tag trend popup opened from the Stats view.
+pub fn handle_tag_trend(app: &mut App, key: KeyCode, conn: &Connection) -> bool {
+    match key {
+        KeyCode::Esc | KeyCode::Char('t') => app.mode = crate::app::Mode::Stats,
+        KeyCode::Right => app.cycle_trend_tag(conn, 1),
+        KeyCode::Left => app.cycle_trend_tag(conn, -1),
         _ => {}
     }
 
@@ -629,9 +1338,33 @@ mod tests {
             kind,
             tag: Tag::from_str(tag),
             date: date.to_string(),
+            ..Default::default()
         }
     }
 
+    #[test]
+    fn income_is_grouped_by_source_largest_first() {
+        let txs = vec![
+            tx(1, "Salary", 3000.0, TransactionType::Credit, "salary", "2026-03-01"),
+            tx(2, "Client A", 400.0, TransactionType::Credit, "freelance", "2026-03-03"),
+            tx(3, "Client A", 700.0, TransactionType::Credit, "freelance", "2026-03-20"),
+            tx(4, "Salary", 90.0, TransactionType::Debit, "fees", "2026-03-21"),
+        ];
+
+        assert_eq!(
+            calculate_earned_per_source(&txs),
+            vec![("Salary".to_string(), 3000.0), ("Client A".to_string(), 1100.0)]
+        );
+        assert!(calculate_earned_per_source(&txs[3..]).is_empty());
+
+        // Summed exactly, not drifting like f64 addition
+        let tips = vec![
+            tx(1, "Tips", 0.1, TransactionType::Credit, "tips", "2026-03-01"),
+            tx(2, "Tips", 0.2, TransactionType::Credit, "tips", "2026-03-02"),
+        ];
+        assert_eq!(calculate_earned_per_source(&tips), vec![("Tips".to_string(), 0.3)]);
+    }
+
     #[test]
     fn calculate_earned_and_spent() {
         let transactions = vec![
@@ -647,6 +1380,36 @@ mod tests {
         assert_eq!(spent, 75.0);
     }
 
+    #[test]
+    fn uncleared_skips_cleared_rows() {
+        let mut paid = tx(3, "c", 25.0, TransactionType::Debit, "misc", "2026-02-03");
+        paid.cleared = true;
+        let transactions = vec![
+            tx(1, "a", 200.0, TransactionType::Credit, "salary", "2026-02-01"),
+            tx(2, "b", 50.0, TransactionType::Debit, "food", "2026-02-02"),
+            paid,
+        ];
+
+        assert_eq!(calculate_uncleared(&transactions), (2, 150.0));
+    }
+
+    #[test]
+    fn month_comparison_uses_current_and_previous_month() {
+        let transactions = vec![
+            tx(1, "a", 900.0, TransactionType::Credit, "salary", "2026-01-31"),
+            tx(2, "b", 40.0, TransactionType::Debit, "food", "2026-01-15"),
+            tx(3, "c", 1000.0, TransactionType::Credit, "salary", "2026-02-28"),
+            tx(4, "d", 25.0, TransactionType::Debit, "food", "2026-02-02"),
+            tx(5, "e", 99.0, TransactionType::Debit, "food", "2025-12-30"),
+        ];
+        let today = NaiveDate::from_ymd_opt(2026, 2, 10).unwrap();
+
+        let comparison = MonthComparison::for_month(&transactions, today);
+        assert_eq!(comparison.earned, (1000.0, 900.0));
+        assert_eq!(comparison.spent, (25.0, 40.0));
+        assert_eq!(comparison.net(), (975.0, 860.0));
+    }
+
     #[test]
     fn monthly_history_groups_and_orders() {
         let transactions = vec![
@@ -656,7 +1419,7 @@ mod tests {
             tx(4, "d", 20.0, TransactionType::Debit, "z", "2025-12-31"),
         ];
 
-        let months = calculate_monthly_history(&transactions);
+        let months = calculate_monthly_history(&transactions, 6);
 
         // Expect descending months (latest first)
         assert!(months.len() >= 3);
@@ -667,6 +1430,127 @@ mod tests {
         assert_eq!(feb.2, 30.0); // spent
     }
 
+    #[test]
+    fn monthly_history_respects_window() {
+        let transactions = vec![
+            tx(1, "a", 10.0, TransactionType::Debit, "x", "2026-01-05"),
+            tx(2, "b", 10.0, TransactionType::Debit, "x", "2026-02-05"),
+            tx(3, "c", 10.0, TransactionType::Debit, "x", "2026-03-05"),
+        ];
+
+        let months = calculate_monthly_history(&transactions, 2);
+        assert_eq!(months.len(), 2);
+        assert_eq!(months[0].0, "2026-03");
+        assert_eq!(months[1].0, "2026-02");
+    }
+
+    #[test]
+    fn weekly_history_follows_week_start() {
+        // 2026-10-17 is a Saturday, 2026-10-18 a Sunday
+        let transactions = vec![
+            tx(1, "a", 10.0, TransactionType::Debit, "x", "2026-10-17"),
+            tx(2, "b", 5.0, TransactionType::Debit, "x", "2026-10-18"),
+        ];
+
+        let monday = calculate_weekly_history(&transactions, 6, Weekday::Mon);
+        assert_eq!(monday, vec![("2026-10-12".to_string(), 0.0, 15.0)]);
+
+        let sunday = calculate_weekly_history(&transactions, 6, Weekday::Sun);
+        assert_eq!(
+            sunday,
+            vec![
+                ("2026-10-18".to_string(), 0.0, 5.0),
+                ("2026-10-11".to_string(), 0.0, 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn opening_balance_is_added_once() {
+        let transactions = vec![
+            tx(1, "pay", 100.0, TransactionType::Credit, "salary", "2026-01-05"),
+            tx(2, "buy", 30.0, TransactionType::Debit, "food", "2026-01-06"),
+        ];
+
+        let mut snapshot = StatsSnapshot::new(&transactions, 6, Weekday::Mon);
+        snapshot.set_opening_balance(500.0);
+        snapshot.set_opening_balance(500.0);
+
+        assert_eq!(snapshot.balance, 570.0);
+        assert_eq!(snapshot.opening_balance, 500.0);
+    }
+
+    #[test]
+    fn savings_rate_needs_income_and_follows_thresholds() {
+        let theme = Theme::default();
+        let spend_only = vec![tx(1, "buy", 30.0, TransactionType::Debit, "food", "2026-01-06")];
+        let snapshot = StatsSnapshot::new(&spend_only, 6, Weekday::Mon);
+        assert_eq!(snapshot.savings_rate(), None);
+        assert_eq!(snapshot.savings_rate_text(), "n/a");
+
+        let transactions = vec![
+            tx(1, "pay", 100.0, TransactionType::Credit, "salary", "2026-01-05"),
+            tx(2, "buy", 90.0, TransactionType::Debit, "food", "2026-01-06"),
+        ];
+        let mut snapshot = StatsSnapshot::new(&transactions, 6, Weekday::Mon);
+        assert_eq!(snapshot.savings_rate_text(), "10.0%");
+        assert_eq!(snapshot.savings_rate_color(&theme), theme.accent);
+
+        snapshot.savings_thresholds = (15.0, 30.0);
+        assert_eq!(snapshot.savings_rate_color(&theme), theme.debit);
+        snapshot.savings_thresholds = (0.0, 10.0);
+        assert_eq!(snapshot.savings_rate_color(&theme), theme.credit);
+    }
+
+    #[test]
+    fn goal_progress_projects_from_recent_months() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+        let transactions = vec![
+            tx(1, "to savings", 100.0, TransactionType::Debit, "savings", "2026-01-10"),
+            tx(2, "to savings", 100.0, TransactionType::Debit, "savings", "2026-02-10"),
+            tx(3, "to savings", 100.0, TransactionType::Debit, "savings", "2026-03-10"),
+            tx(4, "withdrawal", 50.0, TransactionType::Credit, "savings", "2026-03-12"),
+            tx(5, "groceries", 80.0, TransactionType::Debit, "food", "2026-03-12"),
+        ];
+        let goal = |amount: f64, tag: Option<&str>, target: Option<&str>| GoalConfig {
+            name: "fund".into(),
+            amount,
+            target_date: target.map(str::to_string),
+            tag: tag.map(str::to_string),
+        };
+
+        // 250 saved at 250 / 3 a month: 750 more takes 9 months
+        let progress = calculate_goal_progress(&goal(1000.0, Some("savings"), Some("2026-09-30")), &transactions, 0.0, 3, today);
+        assert_eq!(progress.saved, 250.0);
+        assert_eq!(progress.percent(), 25.0);
+        assert_eq!(progress.projected, NaiveDate::from_ymd_opt(2026, 12, 15));
+        assert!(progress.behind());
+
+        let progress = calculate_goal_progress(&goal(200.0, Some("savings"), None), &transactions, 0.0, 3, today);
+        assert!(progress.reached());
+        assert_eq!(progress.projected, None);
+        assert_eq!(progress.percent(), 100.0);
+
+        // Untagged goals follow the balance, and a shrinking one never arrives
+        let progress = calculate_goal_progress(&goal(1000.0, None, Some("2027-01-01")), &transactions, 120.0, 3, today);
+        assert_eq!(progress.saved, 120.0);
+        assert_eq!(progress.projected, None);
+        assert!(progress.behind());
+    }
+
+    #[test]
+    fn zero_fill_covers_gaps_and_window() {
+        let series = vec![("2026-01".to_string(), 25.0), ("2026-03".to_string(), 7.0)];
+        let end = NaiveDate::from_ymd_opt(2026, 4, 15).unwrap();
+
+        let filled = zero_fill_months(&series, end, 5);
+        let months: Vec<&str> = filled.iter().map(|(m, _)| m.as_str()).collect();
+        assert_eq!(months, vec!["2025-12", "2026-01", "2026-02", "2026-03", "2026-04"]);
+
+        let amounts: Vec<f64> = filled.iter().map(|(_, a)| *a).collect();
+        assert_eq!(amounts, vec![0.0, 25.0, 0.0, 7.0, 0.0]);
+    }
+
     #[test]
     fn top_tags_sort_descending() {
         let mut map = std::collections::HashMap::new();
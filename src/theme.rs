@@ -2,6 +2,7 @@
 use serde::{Deserialize, Serialize};
 use ratatui::{
     prelude::*,
+    symbols::{bar, border},
     widgets::{Block, Borders},
 };
 
@@ -19,6 +20,15 @@ pub struct ThemeConfig {
     pub surface: String,
     pub row_alt: String,
     pub foreground: String,
+    // Popup borders for destructive confirms and for notices; `debit` and
+    // `accent_soft` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub danger: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<String>,
+    // Amber for "close to the limit"; yellow when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -37,6 +47,169 @@ pub struct Theme {
     pub row_alt: Color,
 
     pub foreground: Color,
+
+    // Popup borders and titles: destructive confirms, and notices
+    pub danger: Color,
+    pub info: Color,
+
+    // A low balance, or a budget nearly used up
+    pub warning: Color,
+
+    pub glyphs: Glyphs,
+}
+
+/// Decorative symbols drawn around the data. `ascii_mode` swaps in
+/// `Glyphs::ASCII` for terminals and fonts without emoji or box drawing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Glyphs {
+    // Rounded panels, and the plain frame some charts use
+    pub border: border::Set,
+    pub plain_border: border::Set,
+    // Bar chart fills, empty to full in eighths
+    pub bar_levels: [&'static str; 9],
+    pub scrollbar_thumb: &'static str,
+    pub scrollbar_track: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+    pub up_down: &'static str,
+    pub left_right: &'static str,
+    pub left: &'static str,
+    pub right: &'static str,
+    pub credit: &'static str,
+    pub debit: &'static str,
+    pub cleared: &'static str,
+    // Before the source of a row marked for a batch action
+    pub marked: &'static str,
+    pub warning: &'static str,
+    pub pointer: &'static str,
+    pub divider: &'static str,
+    pub rule: &'static str,
+    pub heavy_rule: &'static str,
+    pub bar_full: &'static str,
+    pub bar_empty: &'static str,
+    pub ellipsis: &'static str,
+    pub attachment: &'static str,
+    pub active: &'static str,
+    pub paused: &'static str,
+    pub recurring_on: &'static str,
+    pub recurring_off: &'static str,
+    pub shift_tab: &'static str,
+    // Section icons, each followed by a space (or empty)
+    pub overview_icon: &'static str,
+    pub breakdown_icon: &'static str,
+    pub scheduled_icon: &'static str,
+}
+
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+impl Glyphs {
+    pub const UNICODE: Glyphs = Glyphs {
+        border: border::ROUNDED,
+        plain_border: border::PLAIN,
+        bar_levels: [
+            " ",
+            bar::ONE_EIGHTH,
+            bar::ONE_QUARTER,
+            bar::THREE_EIGHTHS,
+            bar::HALF,
+            bar::FIVE_EIGHTHS,
+            bar::THREE_QUARTERS,
+            bar::SEVEN_EIGHTHS,
+            bar::FULL,
+        ],
+        scrollbar_thumb: "█",
+        scrollbar_track: "║",
+        up: "↑",
+        down: "↓",
+        up_down: "↑↓",
+        left_right: "←→",
+        left: "←",
+        right: "→",
+        credit: "▲",
+        debit: "▼",
+        cleared: "✓",
+        marked: "■",
+        warning: "⚠",
+        pointer: "▶",
+        divider: "│",
+        rule: "─",
+        heavy_rule: "━",
+        bar_full: "█",
+        bar_empty: "░",
+        ellipsis: "…",
+        attachment: "📎",
+        active: "●",
+        paused: "○",
+        recurring_on: "🔄",
+        recurring_off: "🚫",
+        shift_tab: "⇧Tab",
+        overview_icon: "💰 ",
+        breakdown_icon: "📊 ",
+        scheduled_icon: "🔄 ",
+    };
+
+    // Printable ASCII only, so every character takes one column and shows
+    // in any font. Some glyphs are longer than their Unicode counterpart
+    // and the emoji icons are left out.
+    pub const ASCII: Glyphs = Glyphs {
+        border: ASCII_BORDER,
+        plain_border: ASCII_BORDER,
+        bar_levels: [" ", "-", "-", "=", "=", "#", "#", "#", "#"],
+        scrollbar_thumb: "#",
+        scrollbar_track: "|",
+        up: "^",
+        down: "v",
+        up_down: "^v",
+        left_right: "<>",
+        left: "<",
+        right: ">",
+        credit: "+",
+        debit: "-",
+        cleared: "*",
+        marked: "x",
+        warning: "!",
+        pointer: ">",
+        divider: "|",
+        rule: "-",
+        heavy_rule: "=",
+        bar_full: "#",
+        bar_empty: ".",
+        ellipsis: "~",
+        attachment: "@",
+        active: "*",
+        paused: "o",
+        recurring_on: "+",
+        recurring_off: "x",
+        shift_tab: "S-Tab",
+        overview_icon: "",
+        breakdown_icon: "",
+        scheduled_icon: "",
+    };
+
+    pub fn bar_set(&self) -> bar::Set {
+        let [empty, one_eighth, one_quarter, three_eighths, half, five_eighths, three_quarters, seven_eighths, full] =
+            self.bar_levels;
+        bar::Set {
+            empty,
+            one_eighth,
+            one_quarter,
+            three_eighths,
+            half,
+            five_eighths,
+            three_quarters,
+            seven_eighths,
+            full,
+        }
+    }
 }
 
 pub fn parse_color(s: &str) -> Option<Color> {
@@ -89,7 +262,28 @@ pub fn parse_color(s: &str) -> Option<Color> {
     }
 }
 
+/// Lighten a color toward white by `factor` (0.0–1.0). Named colors map to
+/// their light variants since they have no RGB value to blend.
+pub fn brighten(color: Color, factor: f32) -> Color {
+    let factor = factor.clamp(0.0, 1.0);
+    let lift = |c: u8| c + ((255 - c) as f32 * factor).round() as u8;
+
+    match color {
+        Color::Rgb(r, g, b) => Color::Rgb(lift(r), lift(g), lift(b)),
+        Color::Red => Color::LightRed,
+        Color::Green => Color::LightGreen,
+        Color::Yellow => Color::LightYellow,
+        Color::Blue => Color::LightBlue,
+        Color::Magenta => Color::LightMagenta,
+        Color::Cyan => Color::LightCyan,
+        Color::Gray => Color::White,
+        Color::DarkGray => Color::Gray,
+        other => other,
+    }
+}
+
 impl Theme {
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> Self {
         Self {
             accent: Color::Rgb(100, 181, 246),
@@ -106,6 +300,13 @@ impl Theme {
             row_alt: Color::Rgb(29, 29, 44), // midpoint between background and surface
 
             foreground: Color::Rgb(220, 225, 245),
+
+            danger: Color::Rgb(239, 83, 80),
+            info: Color::Rgb(80, 140, 200),
+
+            warning: Color::Yellow,
+
+            glyphs: Glyphs::UNICODE,
         }
     }
 
@@ -125,6 +326,19 @@ impl Theme {
             surface: parse("surface", &config.surface)?,
             row_alt: parse("row_alt", &config.row_alt)?,
             foreground: parse("foreground", &config.foreground)?,
+            danger: match &config.danger {
+                Some(val) => parse("danger", val)?,
+                None => parse("debit", &config.debit)?,
+            },
+            info: match &config.info {
+                Some(val) => parse("info", val)?,
+                None => parse("accent_soft", &config.accent_soft)?,
+            },
+            warning: match &config.warning {
+                Some(val) => parse("warning", val)?,
+                None => Color::Yellow,
+            },
+            glyphs: Glyphs::UNICODE,
         })
     }
 
@@ -142,6 +356,10 @@ impl Theme {
                 surface: Color::Rgb(52, 55, 70),        // current line/surface
                 row_alt: Color::Rgb(45, 47, 59),        // midpoint
                 foreground: Color::Rgb(248, 248, 242),  // fg
+                danger: Color::Rgb(255, 85, 85),
+                info: Color::Rgb(98, 114, 164),
+                warning: Color::Rgb(241, 250, 140),     // yellow
+                glyphs: Glyphs::UNICODE,
             }),
             "nord" => Some(Self {
                 accent: Color::Rgb(136, 192, 208),      // frost blue (nord8)
@@ -154,6 +372,10 @@ impl Theme {
                 surface: Color::Rgb(67, 76, 94),        // polar night (nord2)
                 row_alt: Color::Rgb(53, 60, 74),        // midpoint
                 foreground: Color::Rgb(216, 222, 233),  // snow storm (nord4)
+                danger: Color::Rgb(191, 97, 106),
+                info: Color::Rgb(129, 161, 193),
+                warning: Color::Rgb(235, 203, 139),     // yellow (nord13)
+                glyphs: Glyphs::UNICODE,
             }),
             "gruvbox" | "gruvbox_dark" | "gruvbox-dark" => Some(Self {
                 accent: Color::Rgb(250, 189, 47),       // yellow
@@ -166,6 +388,10 @@ impl Theme {
                 surface: Color::Rgb(60, 56, 54),        // bg1
                 row_alt: Color::Rgb(50, 48, 47),        // midpoint
                 foreground: Color::Rgb(235, 219, 178),  // fg0
+                danger: Color::Rgb(251, 73, 52),
+                info: Color::Rgb(215, 153, 33),
+                warning: Color::Rgb(254, 128, 25),      // orange, apart from the yellow accent
+                glyphs: Glyphs::UNICODE,
             }),
             _ => None,
         }
@@ -216,33 +442,39 @@ impl Theme {
             Block::default()
                 .title(Span::styled(title, self.title()))
                 .borders(Borders::ALL)
-                .border_set(ratatui::symbols::border::ROUNDED)
+                .border_set(self.glyphs.border)
                 .border_style(Style::default().fg(self.accent_soft))
         }
 
         pub fn panel<'a>(&self) -> Block<'a> {
             Block::default()
                 .borders(Borders::ALL)
-                .border_set(ratatui::symbols::border::ROUNDED)
+                .border_set(self.glyphs.border)
                 .border_style(Style::default().fg(self.subtle))
                 .style(Style::default().bg(self.background))
         }
 
         pub fn popup<'a>(&self, title: &'a str) -> Block<'a> {
+            self.tinted_popup(title, self.accent)
+        }
+
+        /// A popup block whose border and title use `color`, e.g.
+        /// `danger` for a delete confirmation.
+        pub fn tinted_popup<'a>(&self, title: &'a str, color: Color) -> Block<'a> {
             Block::default()
-                .title(Span::styled(title, self.title()))
+                .title(Span::styled(title, Style::default().fg(color).add_modifier(Modifier::BOLD)))
                 .borders(Borders::ALL)
-                .border_set(ratatui::symbols::border::ROUNDED)
-                .border_style(Style::default().fg(self.accent))
+                .border_set(self.glyphs.border)
+                .border_style(Style::default().fg(color))
                 .style(Style::default().bg(self.surface))
         }
 
         pub fn separator_span(&self) -> Span<'static> {
-            Span::styled(" │ ", Style::default().fg(self.subtle))
+            Span::styled(format!(" {} ", self.glyphs.divider), Style::default().fg(self.subtle))
         }
 
         pub fn cursor_indicator(&self) -> Span<'static> {
-            Span::styled("►", Style::default().fg(self.accent).add_modifier(Modifier::BOLD))
+            Span::styled(self.glyphs.pointer, Style::default().fg(self.accent).add_modifier(Modifier::BOLD))
         }
 
         pub fn bracket_open(&self) -> Span<'static> {
@@ -292,6 +524,9 @@ mod theme_tests {
             surface: "#000000".to_string(),
             row_alt: "#111111".to_string(),
             foreground: "white".to_string(),
+            danger: None,
+            info: Some("cyan".to_string()),
+            warning: None,
         };
 
         let theme = Theme::from_config(&config).unwrap();
@@ -299,6 +534,17 @@ mod theme_tests {
         assert_eq!(theme.accent_soft, Color::Rgb(17, 34, 51));
         assert_eq!(theme.credit, Color::Green);
         assert_eq!(theme.debit, Color::Rgb(255, 0, 0));
+        // Unset popup colors follow debit, set ones are used as given
+        assert_eq!(theme.danger, Color::Rgb(255, 0, 0));
+        assert_eq!(theme.info, Color::Cyan);
+        assert_eq!(theme.warning, Color::Yellow);
+    }
+
+    #[test]
+    fn test_brighten() {
+        assert_eq!(brighten(Color::Rgb(0, 100, 255), 0.5), Color::Rgb(128, 178, 255));
+        assert_eq!(brighten(Color::Rgb(10, 20, 30), 0.0), Color::Rgb(10, 20, 30));
+        assert_eq!(brighten(Color::Red, 0.3), Color::LightRed);
     }
 
     #[test]
@@ -309,4 +555,19 @@ mod theme_tests {
         assert!(Theme::get_preconfigured("default").is_some());
         assert!(Theme::get_preconfigured("invalid").is_none());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn ascii_glyphs_are_plain_ascii() {
+        // Debug prints every symbol, borders and bar levels included
+        assert!(format!("{:?}", Glyphs::ASCII).is_ascii());
+        assert!(!format!("{:?}", Glyphs::UNICODE).is_ascii());
+        assert!(Glyphs::ASCII.bar_set().full.is_ascii());
+    }
+
+    #[test]
+    fn marked_rows_look_different_from_cleared_ones() {
+        for glyphs in [Glyphs::UNICODE, Glyphs::ASCII] {
+            assert_ne!(glyphs.marked, glyphs.cleared);
+        }
+    }
+}
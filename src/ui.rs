@@ -3,13 +3,16 @@ use ratatui::{
     widgets::{Block, Table, Row, Cell, TableState, Padding, Paragraph},
 };
 use chrono::Datelike as _;
+use std::collections::HashMap;
 
 use crate::{
-    app::{App, Mode},
-    models::{Transaction, TransactionType, RecurringInterval},
+    app::{App, BudgetBand, Mode, PopupSize},
+    config::ListColumn,
+    models::{Tag, Transaction, TransactionType, RecurringInterval},
+    money::Money,
     stats,
     stats::StatsSnapshot,
-    theme::Theme,
+    theme::{Glyphs, Theme},
 };
 
 // list of tab titles; order must align with `App::current_tab` mapping
@@ -18,7 +21,7 @@ mod form;
 use form::draw_transaction_form;
 
 mod header;
-use header::draw_header;
+use header::{draw_header, draw_profile_indicator};
 
 mod modal;
 use modal::draw_popup;
@@ -26,9 +29,118 @@ use modal::draw_popup;
 mod filter;
 use filter::draw_filter_popup;
 
-const POPUP_WIDTH_PERCENT: u16 = 60;
+mod jump;
+use jump::draw_jump_popup;
+
+mod search;
+use search::draw_search_popup;
+
+mod retag;
+use retag::draw_retag_popup;
+mod scale;
+use scale::draw_scale_popup;
+mod quick_add;
+use quick_add::draw_quick_add_popup;
+
+mod trend;
+use trend::draw_tag_trend_popup;
+
+mod year;
+use year::draw_year_summary_popup;
+
+mod onboarding;
+use onboarding::draw_onboarding;
+
+// Popups are never shorter than this; long messages grow them up to their
+// size's maximum, then scroll
 const POPUP_HEIGHT_PERCENT: u16 = 30;
 
+// Below this the header, table and popups no longer fit
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 20;
+
+/// Rows `text` takes once word-wrapped to `width` columns, the way popups
+/// draw it. Words wider than `width` are broken across rows.
+pub fn wrapped_line_count(text: &str, width: usize) -> usize {
+    let width = width.max(1);
+    text.lines()
+        .map(|line| {
+            let mut rows = 1;
+            let mut used = 0;
+            for word in line.split_whitespace() {
+                let len = word.chars().count();
+                if used > 0 && used + 1 + len <= width {
+                    used += 1 + len;
+                    continue;
+                }
+                if used > 0 {
+                    rows += 1;
+                }
+                rows += (len - 1) / width;
+                used = (len - 1) % width + 1;
+            }
+            rows
+        })
+        .sum()
+}
+
+/// Most rows a popup message can wrap to: its count at the narrowest popup
+/// the minimum terminal size allows.
+pub fn popup_line_bound(message: &str) -> usize {
+    let narrowest = MIN_WIDTH * PopupSize::Normal.width_percent() / 100 - 2;
+    wrapped_line_count(message, narrowest as usize)
+}
+
+/// The middle `percent_x` by `percent_y` of `rect`, for the popups sized
+/// relative to the screen.
+pub(crate) fn centered_rect(percent_x: u16, percent_y: u16, rect: Rect) -> Rect {
+    let vertical_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(rect);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical_layout[1])[1]
+}
+
+fn too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+fn draw_too_small(f: &mut Frame, area: Rect, theme: &Theme) {
+    let content = vec![
+        Line::styled(
+            "Terminal too small",
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        ),
+        Line::styled(
+            format!("{}x{} (need {}x{})", area.width, area.height, MIN_WIDTH, MIN_HEIGHT),
+            theme.muted_text(),
+        ),
+    ];
+
+    // Vertically centre when there is room for it
+    let top = area.height.saturating_sub(content.len() as u16) / 2;
+    let body = Rect { y: area.y + top, height: area.height - top, ..area };
+
+    f.render_widget(
+        Paragraph::new(content)
+            .alignment(Alignment::Center)
+            .wrap(ratatui::widgets::Wrap { trim: true }),
+        body,
+    );
+}
+
 fn draw_tabs(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let titles: Vec<Line> = TAB_TITLES
         .iter()
@@ -61,7 +173,7 @@ fn draw_tabs(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
                 .fg(theme.background)
                 .add_modifier(Modifier::BOLD),
         )
-        .divider(Span::styled("│", Style::default().fg(theme.subtle)));
+        .divider(Span::styled(theme.glyphs.divider, Style::default().fg(theme.subtle)));
 
     f.render_widget(tabs, area);
 }
@@ -69,6 +181,11 @@ fn draw_tabs(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
 pub fn draw_ui(f: &mut Frame, app: &App, snapshot: &StatsSnapshot) {
     let theme = app.theme;
 
+    if too_small(f.size()) {
+        draw_too_small(f, f.size(), &theme);
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -82,154 +199,170 @@ pub fn draw_ui(f: &mut Frame, app: &App, snapshot: &StatsSnapshot) {
 
     match app.mode {
         Mode::Stats => {
-            stats::draw_stats_view(f, content_area, snapshot, &theme, &app.currency);
+            stats::draw_stats_view(f, content_area, snapshot, &theme, &app.money(), app.stats_scroll);
+        }
+
+        Mode::TagTrend => {
+            stats::draw_stats_view(f, content_area, snapshot, &theme, &app.money(), app.stats_scroll);
+            draw_tag_trend_popup(f, app, &theme);
+        }
+
+        Mode::YearSummary => {
+            stats::draw_stats_view(f, content_area, snapshot, &theme, &app.money(), app.stats_scroll);
+            draw_year_summary_popup(f, app, &theme);
         }
 
         Mode::Adding => {
-            let filtered_txs = app.get_filtered_transactions();
-            draw_main_view(
-                f,
-                content_area,
-                &filtered_txs,
-                snapshot.earned,
-                snapshot.spent,
-                snapshot.balance,
-                app,
-                &theme,
-            );
+            draw_main_view(f, content_area, snapshot, app, &theme);
             draw_transaction_form(f, app, &theme);
         }
 
         Mode::Popup => {
-            let filtered_txs = app.get_filtered_transactions();
-            draw_main_view(
-                f,
-                content_area,
-                &filtered_txs,
-                snapshot.earned,
-                snapshot.spent,
-                snapshot.balance,
-                app,
-                &theme,
-            );
+            draw_main_view(f, content_area, snapshot, app, &theme);
             draw_popup(f, app, &theme);
         }
 
         Mode::Filtering => {
-            let filtered_txs = app.get_filtered_transactions();
-            draw_main_view(
-                f,
-                content_area,
-                &filtered_txs,
-                snapshot.earned,
-                snapshot.spent,
-                snapshot.balance,
-                app,
-                &theme,
-            );
+            draw_main_view(f, content_area, snapshot, app, &theme);
             draw_filter_popup(f, app, &theme);
         }
 
+        Mode::JumpToDate => {
+            draw_main_view(f, content_area, snapshot, app, &theme);
+            draw_jump_popup(f, app, &theme);
+        }
+
+        Mode::Search => {
+            draw_main_view(f, content_area, snapshot, app, &theme);
+            draw_search_popup(f, app, &theme);
+        }
+
+        Mode::Retag => {
+            draw_main_view(f, content_area, snapshot, app, &theme);
+            draw_retag_popup(f, app, &theme);
+        }
+
+        Mode::ScaleAmounts => {
+            draw_main_view(f, content_area, snapshot, app, &theme);
+            draw_scale_popup(f, app, &theme);
+        }
+
+        Mode::QuickAdd => {
+            draw_main_view(f, content_area, snapshot, app, &theme);
+            draw_quick_add_popup(f, app, &theme);
+        }
+
         Mode::RecurringManagement => {
             draw_recurring_management(f, content_area, app, &theme);
         }
 
         _ => {
-            let filtered_txs = app.get_filtered_transactions();
-            draw_main_view(
-                f,
-                content_area,
-                &filtered_txs,
-                snapshot.earned,
-                snapshot.spent,
-                snapshot.balance,
-                app,
-                &theme,
-            );
+            draw_main_view(f, content_area, snapshot, app, &theme);
         }
     }
+
+    if let Some(toast) = &app.toast {
+        draw_toast(f, &toast.message, &theme);
+    }
 }
 
-fn draw_main_view(
-    f: &mut Frame,
-    area: Rect,
-    transactions: &[Transaction],
-    earned: f64,
-    spent: f64,
-    balance: f64,
-    app: &App,
-    theme: &Theme,
-) {
+/// A one-line notice in the bottom-right corner, over whatever is there.
+fn draw_toast(f: &mut Frame, message: &str, theme: &Theme) {
+    let size = f.size();
+    let width = (message.chars().count() as u16 + 4).min(size.width);
+    let area = Rect::new(size.x + size.width - width, size.y + size.height.saturating_sub(1), width, 1);
+
+    let toast = Paragraph::new(format!("  {}  ", message))
+        .style(Style::default().bg(theme.surface).fg(theme.accent).add_modifier(Modifier::BOLD));
+
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(toast, area);
+}
+
+/// The header and transaction list. Draws `app.list_view`, which the
+/// caller keeps in sync.
+fn draw_main_view(f: &mut Frame, area: Rect, snapshot: &StatsSnapshot, app: &App, theme: &Theme) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(0)
         .constraints([Constraint::Length(7), Constraint::Min(1)])
         .split(area);
 
-    draw_header(f, chunks[0], earned, spent, balance, theme, &app.currency);
-    draw_transactions_list(f, chunks[1], transactions, app, theme);
+    let base_label = app
+        .is_multi_currency()
+        .then(|| format!("base {}", app.currency));
+
+    draw_header(
+        f,
+        chunks[0],
+        snapshot,
+        &app.header_panels,
+        theme,
+        &app.money(),
+        base_label.as_deref(),
+        app.low_balance_warning,
+    );
+    if let Some(profile) = &app.profile {
+        draw_profile_indicator(f, chunks[0], profile, theme);
+    }
+    draw_transactions_list(f, chunks[1], app, theme);
 }
 
-fn draw_transactions_list(
-    f: &mut Frame,
-    area: Rect,
-    transactions: &[Transaction],
-    app: &App,
-    theme: &Theme,
-) {
+fn draw_transactions_list(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let view = &app.list_view;
+    let transactions = view.transactions.as_slice();
+
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(1), Constraint::Length(3)])
         .split(area);
 
-    if transactions.is_empty() {
-        let empty = Paragraph::new(Line::from(vec![
-            Span::raw("   "),
-            Span::styled(
-                "No transactions yet. Press ",
-                Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
-            ),
-            Span::styled(
-                "a",
-                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                " to add one.",
-                Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
-            ),
-        ]));
-        f.render_widget(empty, layout[0]);
-    } else {
-        // Column header row — TYPE removed, BALANCE added
-        let header = Row::new(vec![
-            centered_header_cell("SOURCE",  theme.subtle, theme),
-            sep_cell(theme),
-            centered_header_cell("AMOUNT",  theme.accent, theme),
-            sep_cell(theme),
-            centered_header_cell("BALANCE", theme.subtle, theme),
-            sep_cell(theme),
-            centered_header_cell("RECUR",   theme.accent, theme),
-            sep_cell(theme),
-            centered_header_cell("TAG",     theme.accent, theme),
-        ])
-        .style(Style::default().bg(theme.accent_soft))
-        .height(1);
+    if app.show_onboarding {
+        draw_onboarding(f, layout[0], theme);
+    } else if transactions.is_empty() {
+        let muted = Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC);
+        let key = Style::default().fg(theme.accent).add_modifier(Modifier::BOLD);
+
+        // A filter with no hits shouldn't look like the data is gone
+        let spans = if app.filter.active && !app.transactions.is_empty() {
+            vec![
+                Span::styled("No matches for your filter. Press ", muted),
+                Span::styled("c", key),
+                Span::styled(" to clear it.", muted),
+            ]
+        } else if let Some(message) = &app.empty_message {
+            vec![Span::styled(message.clone(), muted)]
+        } else {
+            vec![
+                Span::styled("No transactions yet. Press ", muted),
+                Span::styled("a", key),
+                Span::styled(" to add one.", muted),
+            ]
+        };
 
-        // Pre-compute running balance for each transaction (oldest → newest).
-        // Transactions are assumed to be stored newest-first, so we reverse,
-        // accumulate, then reverse back so the index matches the display order.
-        let mut running: Vec<f64> = Vec::with_capacity(transactions.len());
-        {
-            let mut bal = 0f64;
-            for tx in transactions.iter().rev() {
-                match tx.kind {
-                    TransactionType::Credit => bal += tx.amount,
-                    TransactionType::Debit  => bal -= tx.amount,
-                }
-                running.push(bal);
+        let mut line = vec![Span::raw("   ")];
+        line.extend(spans);
+        f.render_widget(Paragraph::new(Line::from(line)), layout[0]);
+    } else {
+        // Column header row, in the configured order
+        let mut header_cells = Vec::with_capacity(app.columns.len() * 2);
+        for (i, (column, _)) in app.columns.iter().enumerate() {
+            if i > 0 {
+                header_cells.push(sep_cell(theme));
             }
-            running.reverse();
+            let (label, fg) = match column {
+                ListColumn::Date    => ("DATE",    theme.subtle),
+                ListColumn::Source  => ("SOURCE",  theme.subtle),
+                ListColumn::Amount  => ("AMOUNT",  theme.accent),
+                ListColumn::Balance => ("BALANCE", theme.subtle),
+                ListColumn::Recur   => ("RECUR",   theme.accent),
+                ListColumn::Tag     => ("TAG",     theme.accent),
+            };
+            header_cells.push(centered_header_cell(label, fg, theme));
         }
+        let header = Row::new(header_cells)
+            .style(Style::default().bg(theme.accent_soft))
+            .height(1);
 
         // Build rows, inserting a date-group divider whenever the date changes.
         // We track the "previous date label" and inject a separator row before
@@ -241,34 +374,72 @@ fn draw_transactions_list(
             if let Ok(d) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
                 if d == today     { return "Today".to_string(); }
                 if d == yesterday { return "Yesterday".to_string(); }
+                if app.relative_dates
+                    && let Some(label) = relative_date(date_str, today)
+                {
+                    return label; // e.g. "3d ago"
+                }
                 // Same year → omit the year for brevity
+                let month = app.locale.month_abbr(d.month());
                 if d.year() == today.year() {
-                    return d.format("%b %-d").to_string(); // e.g. "Feb 24"
+                    return format!("{} {}", month, d.day()); // e.g. "Feb 24"
                 }
-                return d.format("%b %-d, %Y").to_string();
+                return format!("{} {}, {}", month, d.day(), d.year());
             }
             date_str.to_string()
         };
 
-        // We need to know the total column count to span the divider row.
-        // Columns: SOURCE │ AMOUNT │ BALANCE │ RECUR │ TAG = 9 cells.
-        const COL_COUNT: usize = 9;
+        // We need to know the total cell count to span the divider row:
+        // every configured column plus a │ between each pair.
+        let col_count = app.columns.len() * 2 - 1;
+
+        let limit = app.visible_limit(transactions.len());
+        let money = app.money();
 
-        let limit = std::cmp::min(15, transactions.len());
-        let mut rows: Vec<Row> = Vec::new();
-        let mut prev_date: Option<String> = None;
-        let mut table_index: usize = 0; // tracks real tx rows for alternating shade
+        // Date dividers only group rows when the list is in date order
+        let show_dividers = app.shows_date_groups();
 
-        for (i, tx) in transactions.iter().take(limit).enumerate() {
-            let needs_divider = prev_date.as_deref() != Some(&tx.date);
+        // Table row of each transaction: its position, plus the date
+        // dividers above it when they're shown
+        let grouped_rows = &view.grouped_rows[..limit];
+        let visual_row = |i: usize| if show_dividers { grouped_rows[i] } else { i };
+        let visual_selected = if app.selected < limit { visual_row(app.selected) } else { 0 };
+
+        let title_text = list_title(app, chrono::Local::now().date_naive(), &theme.glyphs);
+        let block = theme.block(&title_text);
+
+        // Only the rows that fit are built; the header takes one line
+        let height = block.inner(layout[0]).height.saturating_sub(1) as usize;
+        let window = visible_window(visual_selected, height);
+
+        // Transactions with their row, or their divider, inside the window
+        let (first, last) = if show_dividers {
+            (
+                grouped_rows.partition_point(|&row| row < window.start),
+                grouped_rows.partition_point(|&row| row <= window.end),
+            )
+        } else {
+            (window.start.min(limit), window.end.min(limit))
+        };
+        let on_screen = &transactions[first..last.max(first)];
+
+        let mut rows: Vec<Row> = Vec::with_capacity(window.len() + 1);
+        for (i, tx) in on_screen.iter().enumerate().map(|(i, tx)| (first + i, tx)) {
+            // The first of each day gets a divider, drawn when it's inside
+            // the window
+            let needs_divider = show_dividers
+                && (i == 0 || transactions[i - 1].date != tx.date)
+                && visual_row(i) > window.start;
 
             if needs_divider {
                 let label = date_label(&tx.date);
                 // Divider: lighter surface bg so it reads as a section heading
                 // sitting above the darker transaction rows.
                 // No per-cell bg — set at row level only so it stays consistent.
-                let divider_cells: Vec<Cell> = (0..COL_COUNT)
+                let subtotal = crate::money::from_units(view.day_totals.get(&tx.date).copied().unwrap_or(0));
+                let divider_cells: Vec<Cell> = (0..col_count)
                     .map(|col| {
+                        let column = (col % 2 == 0).then(|| app.columns[col / 2].0);
                         if col == 0 {
                             Cell::from(
                                 Text::from(format!("  {} ", label))
@@ -276,9 +447,18 @@ fn draw_transactions_list(
                                         .fg(theme.accent)
                                         .add_modifier(Modifier::BOLD)),
                             )
+                        } else if column == Some(ListColumn::Amount) {
+                            // The day's net, lined up under the amounts
+                            let sign = if subtotal < 0.0 { "-" } else { "+" };
+                            Cell::from(
+                                Text::from(format!("{}{}", sign, money.format(subtotal.abs())))
+                                    .alignment(Alignment::Center)
+                                    .style(Style::default()
+                                        .fg(if subtotal < 0.0 { theme.debit } else { theme.credit })),
+                            )
                         } else {
                             Cell::from(
-                                Text::from(if col % 2 == 0 { "─────" } else { "" })
+                                Text::from(if col % 2 == 0 { theme.glyphs.rule.repeat(5) } else { String::new() })
                                     .style(Style::default().fg(theme.subtle)),
                             )
                         }
@@ -291,39 +471,18 @@ fn draw_transactions_list(
                         .style(Style::default().bg(theme.surface))
                         .height(1),
                 );
-                prev_date = Some(tx.date.clone());
             }
 
             // Transaction rows: darker than the divider (background / row_alt).
             // Do NOT set bg on individual cells — only on the Row via .style().
             // Cell-level bg overrides highlight_style, killing selection visibility.
-            let row_bg = if table_index % 2 == 0 {
-                theme.background
-            } else {
-                theme.row_alt  // Add `row_alt` to Theme: a shade just above background, e.g. Color::Rgb(30,30,35) if background is Rgb(24,24,28)
-            };
-            table_index += 1;
-
-            rows.push(transaction_row(tx, running[i], app, theme, &app.currency, row_bg));
+            let row_bg = stripe_bg(i, app.stripe_rows, theme);
+            let balance = view.balances.get(&tx.id).copied().unwrap_or_default();
+            rows.push(transaction_row(tx, balance, view.amount_width, app, theme, &money, row_bg, &view.budget_bands));
         }
 
-        // Compute the visual row index of the selected transaction.
-        // Each date group adds one divider row above its transactions, so we
-        // count how many unique date groups appear at or before `app.selected`.
-        let visual_selected = {
-            let mut dividers_above = 0usize;
-            let mut last_date: Option<&str> = None;
-            for (i, tx) in transactions.iter().take(limit).enumerate() {
-                if last_date != Some(tx.date.as_str()) {
-                    dividers_above += 1;
-                    last_date = Some(tx.date.as_str());
-                }
-                if i == app.selected { break; }
-            }
-            app.selected + dividers_above
-        };
-
-        let mut state = create_table_state(visual_selected);
+        // The rows start at the window, so the selection is relative to it
+        let mut state = create_table_state(visual_selected - window.start);
 
         // Highlight: fully inverted accent. Works ONLY because transaction
         // cells carry no per-cell bg — the Row .style() stripe is set at row
@@ -334,39 +493,21 @@ fn draw_transactions_list(
             .fg(theme.background)
             .add_modifier(Modifier::BOLD);
 
-        let title_text = if app.filter.active {
-            let filter_tag = match app.filter.tag_index {
-                None => "ALL".to_string(),
-                Some(idx) => format!("#{}", app.tags[idx].as_str()),
-            };
-            let range_str = match (app.filter.start_date.is_empty(), app.filter.end_date.is_empty()) {
-                (true, true) => "ALL".to_string(),
-                (false, true) => format!("from {}", app.filter.start_date),
-                (true, false) => format!("to {}", app.filter.end_date),
-                (false, false) => format!("{} to {}", app.filter.start_date, app.filter.end_date),
-            };
-            format!(" Transactions [Filter: Range={}, Tag={}] ", range_str, filter_tag)
-        } else {
-            " Transactions ".to_string()
-        };
+        let mut widths = Vec::with_capacity(col_count);
+        for (i, (_, width)) in app.columns.iter().enumerate() {
+            if i > 0 {
+                widths.push(Constraint::Length(1)); // │
+            }
+            widths.push(Constraint::Percentage(*width));
+        }
 
-        let table = Table::new(rows, &[
-                Constraint::Percentage(32), // SOURCE
-                Constraint::Length(1),      // │
-                Constraint::Percentage(14), // AMOUNT
-                Constraint::Length(1),      // │
-                Constraint::Percentage(14), // BALANCE
-                Constraint::Length(1),      // │
-                Constraint::Percentage(10), // RECUR
-                Constraint::Length(1),      // │
-                Constraint::Percentage(22), // TAG
-            ])
+        let table = Table::new(rows, widths)
             .header(header)
-            .block(theme.block(&title_text))
+            .block(block)
             .column_spacing(0)
             .style(Style::default().bg(theme.background))
             .highlight_style(highlight)
-            .highlight_symbol("▶ ");
+            .highlight_symbol(app.highlight_symbol.as_str());
 
         f.render_stateful_widget(table, layout[0], &mut state);
     }
@@ -382,47 +523,231 @@ fn draw_transactions_list(
     let label = |l: &'static str| Span::styled(l, theme.muted_text());
     let sep   = || Span::styled("  ", theme.muted_text());
 
-    let mut footer_spans = vec![
-        key("↑↓"), label(" Navigate"),  sep(),
-        key("Tab"), label("/"), key("←→"), label(" Switch view"), sep(),
-        key("a"), label(" Add"),  sep(),
-        key("e"), label(" Edit"),  sep(),
-        key("d"), label(" Delete"), sep(),
-        key("f"), label(" Filter"), sep(),
-    ];
-    
-    if app.filter.active {
-        footer_spans.push(key("c"));
-        footer_spans.push(label(" Clear"));
+    let mut footer_spans = Vec::new();
+    let mut room = layout[1].width.saturating_sub(2) as usize;
+    if app.read_only {
+        footer_spans.push(Span::styled("READ-ONLY", Style::default().fg(theme.debit).add_modifier(Modifier::BOLD)));
         footer_spans.push(sep());
+        room = room.saturating_sub("READ-ONLY".len() + 2);
     }
-    
-    footer_spans.push(key("q"));
-    footer_spans.push(label(" Quit"));
 
-    let footer = Paragraph::new(Line::from(footer_spans))
+    // Hints that don't fit are dropped from the end, keeping Quit
+    let hints = normal_key_hints(app, &theme.glyphs);
+    let widths: Vec<usize> = hints.iter().map(hint_width).collect();
+    let shown = hints_that_fit(&widths, room, theme.glyphs.ellipsis.chars().count());
+    let (quit, rest) = hints.split_last().expect("the hint list always ends with Quit");
+
+    let push_hint = |(keys, text): &KeyHint, spans: &mut Vec<Span<'static>>| {
+        for (i, k) in keys.iter().enumerate() {
+            if i > 0 {
+                spans.push(label("/"));
+            }
+            spans.push(key(k));
+        }
+        spans.push(label(text));
+    };
+    for hint in rest.iter().take(shown) {
+        push_hint(hint, &mut footer_spans);
+        footer_spans.push(sep());
+    }
+    if shown < rest.len() {
+        footer_spans.push(label(theme.glyphs.ellipsis));
+        footer_spans.push(sep());
+    }
+    push_hint(quit, &mut footer_spans);
+
+    let mut footer_lines = vec![Line::from(footer_spans)];
+
+    // Running sum of marked rows
+    if !app.selected_ids.is_empty() {
+        let (credit, debit) = app.marked_totals();
+        footer_lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} marked", app.selected_ids.len()),
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+            ),
+            sep(),
+            Span::styled(format!("{} {}", theme.glyphs.credit, app.money().format(credit)), Style::default().fg(theme.credit)),
+            sep(),
+            Span::styled(format!("{} {}", theme.glyphs.debit, app.money().format(debit)), Style::default().fg(theme.debit)),
+            sep(),
+            key("Esc"),
+            label(" Clear marks"),
+        ]));
+    }
+
+    let footer = Paragraph::new(footer_lines)
         .block(footer_block);
 
     f.render_widget(footer, layout[1]);
 }
 
+/// Title of the transaction list: the configured `list_title`, then
+/// whatever narrows or reorders the view, e.g.
+/// ` Transactions ─ #food ─ Jun '24 `.
+fn list_title(app: &App, today: chrono::NaiveDate, glyphs: &Glyphs) -> String {
+    let mut parts = vec![app.list_title.clone()];
+
+    if let Some(index) = app.active_preset(today) {
+        parts.push(format!("Preset {}: {}", index + 1, app.filter_presets[index].name));
+    } else if app.filter.active {
+        if let Some(tag) = app.filter.tag_index.and_then(|idx| app.tags.get(idx)) {
+            parts.push(format!("#{}", tag.as_str()));
+        }
+        parts.extend(range_label(&app.filter.start_date, &app.filter.end_date, app.locale));
+        let search = app.filter.search.trim();
+        if !search.is_empty() {
+            parts.push(format!("\"{}\"", search));
+        }
+    }
+
+    // Mention the sort unless it's the default newest-first
+    if !(app.is_date_sorted() && app.sort_desc) {
+        let arrow = if app.sort_desc { glyphs.down } else { glyphs.up };
+        parts.push(format!("Sort: {} {}", app.sort_key.as_str(), arrow));
+    }
+
+    format!(" {} ", parts.join(&format!(" {} ", glyphs.rule)))
+}
+
+/// Short description of a date filter. A range covering exactly one
+/// calendar month is named after it, e.g. "Jun '24".
+fn range_label(start: &str, end: &str, locale: crate::locale::Locale) -> Option<String> {
+    match (start.is_empty(), end.is_empty()) {
+        (true, true) => None,
+        (false, true) => Some(format!("from {}", start)),
+        (true, false) => Some(format!("to {}", end)),
+        (false, false) => {
+            let parse = |d: &str| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok();
+            let whole_month = parse(start).zip(parse(end)).filter(|(s, e)| {
+                let month_end = s.checked_add_months(chrono::Months::new(1)).and_then(|n| n.pred_opt());
+                s.day() == 1 && month_end == Some(*e)
+            });
+            Some(match whole_month {
+                Some((s, _)) => locale.month_label(&s.format("%Y-%m").to_string()),
+                None => format!("{} to {}", start, end),
+            })
+        }
+    }
+}
+
+// A footer hint: the keys bound to an action, shown joined by "/", and
+// its label with a leading space
+type KeyHint = (Vec<&'static str>, &'static str);
+
+/// Footer hints for the transaction list, in display order and always
+/// ending with Quit. Keys are bound separately in `handlers`; a test checks
+/// every key hinted here is matched there.
+fn normal_key_hints(app: &App, glyphs: &Glyphs) -> Vec<KeyHint> {
+    let mut hints: Vec<KeyHint> = vec![
+        (vec![glyphs.up_down], " Navigate"),
+        (vec!["Tab", glyphs.left_right], " Switch view"),
+        (vec!["a"], " Add"),
+        (vec!["A"], " Add another"),
+        (vec!["e"], " Edit"),
+        (vec!["d"], " Delete"),
+        (vec!["f"], " Filter"),
+        (vec!["/"], " Search"),
+        (vec!["g"], " Jump"),
+        (vec!["n", "N"], " Next/prev other kind"),
+        (vec!["x"], " Export"),
+        (vec!["X"], " Tag totals"),
+        (vec!["y"], " Copy"),
+        (vec!["Space"], " Mark"),
+        (vec!["t"], " Retag"),
+        (vec!["%"], " Scale amounts"),
+        (vec!["u", "Ctrl+r"], " Undo/redo"),
+        (vec!["o", "O"], " Sort"),
+        (vec!["r"], " Receipt"),
+        (vec!["C"], " Cleared"),
+        (vec!["M"], " Month rollover"),
+        (vec!["S"], " Merge sources"),
+        (vec!["v"], if app.group_by_date { " Ungroup days" } else { " Group by day" }),
+        (vec!["p"], if app.privacy { " Show amounts" } else { " Hide amounts" }),
+    ];
+
+    if !app.filter_presets.is_empty() {
+        hints.push((vec!["1-9"], " Presets"));
+    }
+    if app.filter.active {
+        hints.push((vec!["c"], " Clear filters"));
+    }
+
+    hints.push((vec!["q"], " Quit"));
+    hints
+}
+
+fn hint_width((keys, label): &KeyHint) -> usize {
+    keys.iter().map(|k| k.chars().count()).sum::<usize>() + keys.len() - 1 + label.chars().count()
+}
+
+/// How many hints before the last one fit in `room` columns, with two
+/// spaces between hints. The last hint is always shown; when others are
+/// dropped, an ellipsis of `ellipsis` columns stands in for them.
+fn hints_that_fit(widths: &[usize], room: usize, ellipsis: usize) -> usize {
+    let Some((last, rest)) = widths.split_last() else {
+        return 0;
+    };
+
+    let total: usize = rest.iter().map(|w| w + 2).sum::<usize>() + last;
+    if total <= room {
+        return rest.len();
+    }
+
+    let mut budget = room.saturating_sub(last + ellipsis + 2);
+    rest.iter()
+        .take_while(|w| {
+            let fits = *w + 2 <= budget;
+            if fits {
+                budget -= *w + 2;
+            }
+            fits
+        })
+        .count()
+}
+
 // ---------------------------------------------------------------------------
 // Row builders
 // ---------------------------------------------------------------------------
 
+/// Direction arrow and amount, e.g. `▼ $12.50`. Rows in another currency
+/// show its code instead of the base symbol.
+pub(crate) fn amount_text(tx: &Transaction, money: &Money, glyphs: &Glyphs) -> String {
+    let direction_symbol = match tx.kind {
+        TransactionType::Credit => glyphs.credit,
+        TransactionType::Debit  => glyphs.debit,
+    };
+
+    if tx.currency.is_empty() {
+        format!("{} {}", direction_symbol, money.format(tx.amount))
+    } else {
+        format!("{} {}", direction_symbol, money.with_code(&tx.currency, tx.amount))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn transaction_row(
     tx: &Transaction,
     running_balance: f64,
+    amount_width: usize,
     app: &App,
     theme: &Theme,
-    currency: &str,
+    money: &Money,
     row_bg: ratatui::style::Color,
+    budget_bands: &HashMap<(String, Tag), BudgetBand>,
 ) -> Row<'static> {
-    let color = theme.transaction_color(tx.kind);
-
-    let direction_symbol = match tx.kind {
-        TransactionType::Credit => "▲",
-        TransactionType::Debit  => "▼",
+    // Big debits get a brighter, underlined amount so they stand out
+    let large = app.is_large_debit(tx);
+    let (color, amount_modifier) = if large {
+        (crate::theme::brighten(theme.debit, 0.35), Modifier::BOLD | Modifier::UNDERLINED)
+    } else {
+        (theme.transaction_color(tx.kind), Modifier::BOLD)
+    };
+    // Spending under a budgeted tag shows how that month's budget is doing
+    let month = tx.date.get(..7).unwrap_or(&tx.date).to_string();
+    let color = match budget_bands.get(&(month, tx.tag.clone())) {
+        Some(band) if tx.kind == TransactionType::Debit => budget_color(*band, theme),
+        _ => color,
     };
 
     let recur_label = app
@@ -434,8 +759,17 @@ fn transaction_row(
         })
         .unwrap_or("-");
 
-    let amount_str  = format!("{} {}{:.2}", direction_symbol, currency, tx.amount);
-    let balance_str = format!("{}{:.2}", currency, running_balance);
+    // Each row shows its own currency; the running balance is in the base one.
+    // Right-aligned to `amount_width` so the centered column stays aligned.
+    let mut amount_str = format!("{:>width$}", amount_text(tx, money, &theme.glyphs), width = amount_width);
+    // Cleared against the bank statement; blank otherwise to keep the width
+    if tx.cleared {
+        amount_str.push(' ');
+        amount_str.push_str(theme.glyphs.cleared);
+    } else {
+        amount_str.push_str("  ");
+    }
+    let balance_str = money.format(running_balance);
 
     // Balance color: green if positive, red if negative, muted if zero
     let balance_color = if running_balance > 0.0 {
@@ -446,59 +780,82 @@ fn transaction_row(
         theme.muted
     };
 
+    let marked = app.selected_ids.contains(&tx.id);
+    let mut source_str = if marked {
+        format!("{} {}", theme.glyphs.marked, truncate_string(&tx.source, 38, theme.glyphs.ellipsis))
+    } else {
+        truncate_string(&tx.source, 40, theme.glyphs.ellipsis)
+    };
+    // A paperclip marks rows with a receipt attached
+    if !tx.receipt_path.is_empty() {
+        source_str.push(' ');
+        source_str.push_str(theme.glyphs.attachment);
+    }
+    let source_color = if marked { theme.accent } else { theme.foreground };
+
+    let date_str = if app.relative_dates {
+        relative_date(&tx.date, chrono::Local::now().date_naive())
+            .unwrap_or_else(|| tx.date.clone())
+    } else {
+        tx.date.clone()
+    };
+
     // bg is set at the Row level only (via .style below).
     // No per-cell bg — if cells override bg, highlight_style cannot paint
     // the selected row and selection becomes invisible.
-    Row::new(vec![
-        // SOURCE
-        Cell::from(
-            Text::from(truncate_string(&tx.source, 40))
-                .alignment(Alignment::Center)
-                .style(Style::default().fg(theme.foreground).add_modifier(Modifier::BOLD)),
-        ),
-        sep_cell_bg(theme, row_bg),
-        // AMOUNT — colored with direction symbol
-        Cell::from(
-            Text::from(amount_str)
-                .alignment(Alignment::Center)
-                .style(Style::default().fg(color).add_modifier(Modifier::BOLD)),
-        ),
-        sep_cell_bg(theme, row_bg),
-        // BALANCE — running total, color reflects sign
-        Cell::from(
-            Text::from(balance_str)
-                .alignment(Alignment::Center)
+    let mut cells = Vec::with_capacity(app.columns.len() * 2);
+    for (i, (column, _)) in app.columns.iter().enumerate() {
+        if i > 0 {
+            cells.push(sep_cell_bg(theme, row_bg));
+        }
+        let cell = match column {
+            ListColumn::Date => Text::from(date_str.clone())
+                .style(Style::default().fg(theme.muted)),
+            ListColumn::Source => Text::from(source_str.clone())
+                .style(Style::default().fg(source_color).add_modifier(Modifier::BOLD)),
+            // Colored with direction symbol
+            ListColumn::Amount => Text::from(amount_str.clone())
+                .style(Style::default().fg(color).add_modifier(amount_modifier)),
+            // Running total, color reflects sign
+            ListColumn::Balance => Text::from(balance_str.clone())
                 .style(Style::default().fg(balance_color)),
-        ),
-        sep_cell_bg(theme, row_bg),
-        // RECUR
-        Cell::from(
-            Text::from(recur_label)
-                .alignment(Alignment::Center)
+            ListColumn::Recur => Text::from(recur_label)
                 .style(Style::default().fg(
                     if recur_label == "-" { theme.muted } else { theme.accent },
                 )),
-        ),
-        sep_cell_bg(theme, row_bg),
-        // TAG
-        Cell::from(
-            Text::from(tx.tag.as_str().to_owned())
-                .alignment(Alignment::Center)
+            ListColumn::Tag => Text::from(tx.tag.as_str().to_owned())
                 .style(Style::default().fg(theme.accent_soft).add_modifier(Modifier::ITALIC)),
-        ),
-    ])
+        };
+        cells.push(Cell::from(cell.alignment(Alignment::Center)));
+    }
+
+    Row::new(cells)
     // Row-level bg: drives alternating stripes AND lets highlight_style override cleanly
     .style(Style::default().bg(row_bg))
 }
 
-fn recurring_row(entry: &crate::models::RecurringEntry, theme: &Theme) -> Row<'static> {
+fn budget_color(band: BudgetBand, theme: &Theme) -> ratatui::style::Color {
+    match band {
+        BudgetBand::Under => theme.credit,
+        BudgetBand::Near => theme.warning,
+        BudgetBand::Over => theme.debit,
+    }
+}
+
+fn recurring_row(entry: &crate::models::RecurringEntry, theme: &Theme, money: &Money) -> Row<'static> {
     let (status_symbol, status_style) = if entry.active {
-        ("● Active",   theme.success())
+        (format!("{} Active", theme.glyphs.active), theme.success())
     } else {
-        ("○ Paused",   Style::default().fg(theme.muted))
+        (format!("{} Paused", theme.glyphs.paused), Style::default().fg(theme.muted))
     };
 
     let interval_str = entry.interval.display().to_owned();
+    // Stored per interval: a date, an ISO week or a month
+    let last_str = if entry.last_inserted_date.is_empty() {
+        "never".to_string()
+    } else {
+        entry.last_inserted_date.clone()
+    };
 
     Row::new(vec![
         Cell::from(
@@ -508,13 +865,13 @@ fn recurring_row(entry: &crate::models::RecurringEntry, theme: &Theme) -> Row<'s
         ),
         sep_cell(theme),
         Cell::from(
-            Text::from(truncate_string(&entry.source, 30))
+            Text::from(truncate_string(&entry.source, 30, theme.glyphs.ellipsis))
                 .alignment(Alignment::Center)
                 .style(Style::default().fg(theme.foreground).add_modifier(Modifier::BOLD)),
         ),
         sep_cell(theme),
         Cell::from(
-            Text::from(format!("{:.2}", entry.amount))
+            Text::from(money.number(entry.amount))
                 .alignment(Alignment::Center)
                 .style(Style::default().fg(theme.accent)),
         ),
@@ -524,6 +881,12 @@ fn recurring_row(entry: &crate::models::RecurringEntry, theme: &Theme) -> Row<'s
                 .alignment(Alignment::Center)
                 .style(Style::default().fg(theme.accent_soft).add_modifier(Modifier::ITALIC)),
         ),
+        sep_cell(theme),
+        Cell::from(
+            Text::from(last_str)
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(theme.muted)),
+        ),
     ])
 }
 
@@ -546,6 +909,7 @@ fn draw_recurring_management(f: &mut Frame, area: Rect, app: &App, theme: &Theme
             " Recurring Entries",
             Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
         ),
+        Span::styled(format!("  by {}", app.recurring_sort.as_str()), theme.muted_text()),
     ]))
     .block(theme.block(""))
     .alignment(Alignment::Left);
@@ -567,14 +931,18 @@ fn draw_recurring_management(f: &mut Frame, area: Rect, app: &App, theme: &Theme
             centered_header_cell("AMOUNT",   theme.accent,      theme),
             sep_cell(theme),
             centered_header_cell("INTERVAL", theme.accent_soft, theme),
+            sep_cell(theme),
+            centered_header_cell("LAST",     theme.subtle,      theme),
         ])
         .style(Style::default().bg(theme.accent_soft))
         .height(1);
 
+        let money = app.money();
+        let scheduled_title = format!(" {}Scheduled", theme.glyphs.scheduled_icon);
         let rows: Vec<Row> = app
             .recurring_entries
             .iter()
-            .map(|e| recurring_row(e, theme))
+            .map(|e| recurring_row(e, theme, &money))
             .collect();
 
         let mut state = create_table_state(app.selected_recurring);
@@ -582,20 +950,23 @@ fn draw_recurring_management(f: &mut Frame, area: Rect, app: &App, theme: &Theme
         // Same spacing philosophy: sep_cell handles gaps, column_spacing(0) avoids
         // double-spacing. Percentage splits the available width evenly:
         //   STATUS   15% — "● Active" / "○ Paused"
-        //   SOURCE   45% — free text, deserves most space
-        //   AMOUNT   20% — numbers
-        //   INTERVAL 20% — "Monthly" etc.
+        //   SOURCE   35% — free text, deserves most space
+        //   AMOUNT   15% — numbers
+        //   INTERVAL 15% — "Monthly" etc.
+        //   LAST     20% — last period a transaction was inserted for
         let table = Table::new(rows, &[
                 Constraint::Percentage(15), // STATUS
                 Constraint::Length(1),      // │
-                Constraint::Percentage(45), // SOURCE
+                Constraint::Percentage(35), // SOURCE
+                Constraint::Length(1),      // │
+                Constraint::Percentage(15), // AMOUNT
                 Constraint::Length(1),      // │
-                Constraint::Percentage(20), // AMOUNT
+                Constraint::Percentage(15), // INTERVAL
                 Constraint::Length(1),      // │
-                Constraint::Percentage(20), // INTERVAL
+                Constraint::Percentage(20), // LAST
             ])
             .header(table_header)
-            .block(theme.block(" 🔄 Scheduled"))
+            .block(theme.block(&scheduled_title))
             .column_spacing(0)
             .style(Style::default().bg(theme.background))
             // Same inverted-accent highlight as transactions table
@@ -605,7 +976,7 @@ fn draw_recurring_management(f: &mut Frame, area: Rect, app: &App, theme: &Theme
                     .fg(theme.background)
                     .add_modifier(Modifier::BOLD),
             )
-            .highlight_symbol("▶ ");
+            .highlight_symbol(app.highlight_symbol.as_str());
 
         f.render_stateful_widget(table, layout[1], &mut state);
     }
@@ -615,11 +986,14 @@ fn draw_recurring_management(f: &mut Frame, area: Rect, app: &App, theme: &Theme
     let sep = || Span::styled("  ", theme.muted_text());
 
     let footer = Paragraph::new(Line::from(vec![
-        key("↑↓"), label(" Navigate"), sep(),
+        key(theme.glyphs.up_down), label(" Navigate"), sep(),
         key("Space"), label(" Toggle active"), sep(),
+        key("e"), label(" Edit"), sep(),
         key("d"), label(" Delete"), sep(),
+        key("x"), label(" Delete its transactions"), sep(),
+        key("o"), label(" Sort"), sep(),
         key("Esc"), label(" Back"), sep(),
-        key("Tab"), label("/"), key("←→"), label(" Switch view"),
+        key("Tab"), label("/"), key(theme.glyphs.left_right), label(" Switch view"),
     ]))
     .block(
         Block::default()
@@ -639,16 +1013,26 @@ fn draw_recurring_management(f: &mut Frame, area: Rect, app: &App, theme: &Theme
 
 fn sep_cell(theme: &Theme) -> Cell<'static> {
     Cell::from(Span::styled(
-        "│",
+        theme.glyphs.divider,
         Style::default().fg(theme.subtle),
     ))
 }
 
+/// Background for the `index`-th transaction row. Parity counts
+/// transactions only, so date dividers don't shift the stripe.
+fn stripe_bg(index: usize, striped: bool, theme: &Theme) -> ratatui::style::Color {
+    if striped && !index.is_multiple_of(2) {
+        theme.row_alt
+    } else {
+        theme.background
+    }
+}
+
 /// Separator cell with an explicit background — use inside transaction rows so
 /// the stripe color is consistent across the full row width including separators.
 fn sep_cell_bg(theme: &Theme, bg: ratatui::style::Color) -> Cell<'static> {
     Cell::from(Span::styled(
-        "│",
+        theme.glyphs.divider,
         Style::default().fg(theme.subtle).bg(bg),
     ))
 }
@@ -662,16 +1046,39 @@ fn centered_header_cell(label: &'static str, fg: ratatui::style::Color, _theme:
     )
 }
 
-/// Truncate a string to `max_len` chars, appending an ellipsis if cut.
-fn truncate_string(s: &str, max_len: usize) -> String {
+/// "today", "yesterday" or "3d ago" for dates in the last week; `None` for
+/// older, future or unparsable dates so callers keep their usual format.
+fn relative_date(date_str: &str, today: chrono::NaiveDate) -> Option<String> {
+    let date = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok()?;
+    match (today - date).num_days() {
+        0 => Some("today".to_string()),
+        1 => Some("yesterday".to_string()),
+        days @ 2..=6 => Some(format!("{}d ago", days)),
+        _ => None,
+    }
+}
+
+/// Truncate a string to `max_len` chars, appending a one-char `ellipsis`
+/// if cut.
+pub fn truncate_string(s: &str, max_len: usize, ellipsis: &str) -> String {
     if s.chars().count() <= max_len {
         s.to_string()
     } else {
         let truncated: String = s.chars().take(max_len - 1).collect();
-        format!("{}…", truncated)
+        format!("{}{}", truncated, ellipsis)
     }
 }
 
+/// Visual rows of the transaction list worth building for a table
+/// `height` rows tall. Matches where the table itself would scroll: from
+/// the top until the selection passes the bottom edge, then keeping the
+/// selection on the last line.
+fn visible_window(selected: usize, height: usize) -> std::ops::Range<usize> {
+    let height = height.max(1);
+    let start = (selected + 1).saturating_sub(height);
+    start..start + height
+}
+
 fn create_table_state(selected: usize) -> TableState {
     let mut state = TableState::default();
     state.select(Some(selected));
@@ -686,26 +1093,200 @@ fn create_table_state(selected: usize) -> TableState {
 mod tests {
     use super::*;
     use crate::models::{Transaction, TransactionType, Tag, RecurringEntry, RecurringInterval};
+    use crate::app::SortKey;
+
+    #[test]
+    fn range_label_names_whole_months() {
+        let en = crate::locale::Locale::En;
+        assert_eq!(range_label("", "", en), None);
+        assert_eq!(range_label("2024-06-01", "", en).as_deref(), Some("from 2024-06-01"));
+        assert_eq!(range_label("", "2024-06-30", en).as_deref(), Some("to 2024-06-30"));
+        assert_eq!(range_label("2024-06-01", "2024-06-30", en).as_deref(), Some("Jun '24"));
+        assert_eq!(range_label("2024-02-01", "2024-02-29", en).as_deref(), Some("Feb '24"));
+        assert_eq!(
+            range_label("2024-06-01", "2024-06-29", en).as_deref(),
+            Some("2024-06-01 to 2024-06-29")
+        );
+        // Unpadded dates still parse; the label comes from the date, not the text
+        assert_eq!(range_label("2024-6-1", "2024-6-30", en).as_deref(), Some("Jun '24"));
+    }
+
+    #[test]
+    fn footer_hints_drop_from_the_end_but_keep_quit() {
+        // "a Add", "e Edit", "q Quit"
+        let widths = [5, 6, 6];
+        assert_eq!(hints_that_fit(&widths, 21, 1), 2);
+        // Room for "a Add  …  q Quit" only
+        assert_eq!(hints_that_fit(&widths, 20, 1), 1);
+        assert_eq!(hints_that_fit(&widths, 16, 1), 1);
+        assert_eq!(hints_that_fit(&widths, 15, 1), 0);
+        assert_eq!(hints_that_fit(&[], 10, 1), 0);
+    }
+
+    #[test]
+    fn every_hinted_key_is_handled() {
+        use crate::models::{Tag, TransactionType};
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        // A fresh app per key, with a middle row selected so both arrows
+        // move and there's a row of the other kind either way
+        let setup = || {
+            let conn = crate::db::init_in_memory().unwrap();
+            let add = |source: &str, kind, date: &str| {
+                crate::db::add_transaction(&conn, source, 10.0, kind, &Tag("food".into()), date).unwrap();
+            };
+            add("Salary", TransactionType::Credit, "2026-02-01");
+            add("Coffee", TransactionType::Debit, "2026-02-02");
+            add("Salary", TransactionType::Credit, "2026-02-03");
+
+            let mut app = App::with_config(&conn, crate::config::Config::default(), crate::state::UiState::default());
+            app.selected = 1;
+            // Show the hints that depend on state too
+            app.filter.active = true;
+            app.filter_presets =
+                vec![crate::config::FilterPresetConfig { name: "food".into(), tag: Some("food".into()), ..Default::default() }];
+            (conn, app)
+        };
+        let state = |app: &App| {
+            format!(
+                "{:?}",
+                (
+                    app.mode,
+                    app.current_tab(),
+                    app.popup.is_some(),
+                    app.selected,
+                    &app.selected_ids,
+                    (app.sort_key, app.sort_desc, app.group_by_date, app.privacy),
+                    &app.filter,
+                    app.toast.as_ref().map(|t| &t.message),
+                    &app.transactions,
+                )
+            )
+        };
+
+        for glyphs in [Glyphs::UNICODE, Glyphs::ASCII] {
+            let (_conn, app) = setup();
+            for (keys, label) in normal_key_hints(&app, &glyphs) {
+                for key in keys {
+                    let events: Vec<KeyEvent> = match key {
+                        // These write files or the system clipboard
+                        "x" | "X" | "y" => continue,
+                        k if k == glyphs.up_down => vec![KeyCode::Up.into(), KeyCode::Down.into()],
+                        k if k == glyphs.left_right => vec![KeyCode::Left.into(), KeyCode::Right.into()],
+                        "Tab" => vec![KeyCode::Tab.into()],
+                        "Space" => vec![KeyCode::Char(' ').into()],
+                        "Ctrl+r" => vec![KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)],
+                        "1-9" => vec![KeyCode::Char('1').into()],
+                        k => vec![KeyCode::Char(k.chars().next().unwrap()).into()],
+                    };
+                    for event in events {
+                        let (conn, mut app) = setup();
+                        let before = state(&app);
+                        let quit = crate::handlers::handle_key_event(&mut app, event, &conn);
+                        assert!(quit || state(&app) != before, "{:?} for{} does nothing", event.code, label);
+                    }
+                }
+            }
+        }
+    }
 
     #[test]
     fn truncate_string_short() {
-        assert_eq!(truncate_string("abc", 5), "abc");
+        assert_eq!(truncate_string("abc", 5, "…"), "abc");
     }
 
     #[test]
     fn truncate_string_long() {
         // 5 chars → keep 4 + ellipsis
-        assert_eq!(truncate_string("abcdef", 5), "abcd…");
+        assert_eq!(truncate_string("abcdef", 5, "…"), "abcd…");
+    }
+
+    #[test]
+    fn ascii_glyphs_stay_ascii() {
+        let money = Money::new("$");
+        let tx = Transaction { amount: 5.0, ..Default::default() };
+        assert_eq!(amount_text(&tx, &money, &Glyphs::ASCII), "- $5.00");
+        assert_eq!(truncate_string("abcdef", 5, Glyphs::ASCII.ellipsis), "abcd~");
+    }
+
+    #[test]
+    fn wrapped_line_count_breaks_on_words() {
+        assert_eq!(wrapped_line_count("", 10), 0);
+        assert_eq!(wrapped_line_count("short", 10), 1);
+        assert_eq!(wrapped_line_count("one two three", 7), 2);
+        assert_eq!(wrapped_line_count("a\n\nb", 10), 3);
+        // A word wider than the popup takes several rows on its own
+        assert_eq!(wrapped_line_count("x abcdefghijklmnopqrst", 10), 3);
     }
 
     #[test]
     fn truncate_string_unicode() {
         // Should truncate on char boundary, not byte boundary
         let s = "héllo world";
-        let t = truncate_string(s, 6);
+        let t = truncate_string(s, 6, "…");
         assert_eq!(t.chars().count(), 6); // 5 chars + ellipsis
     }
 
+    #[test]
+    fn relative_date_covers_last_week_only() {
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        assert_eq!(relative_date("2026-03-10", today).as_deref(), Some("today"));
+        assert_eq!(relative_date("2026-03-09", today).as_deref(), Some("yesterday"));
+        assert_eq!(relative_date("2026-03-07", today).as_deref(), Some("3d ago"));
+        assert_eq!(relative_date("2026-03-03", today), None);
+        assert_eq!(relative_date("2026-03-11", today), None);
+        assert_eq!(relative_date("not a date", today), None);
+    }
+
+    #[test]
+    fn too_small_below_minimum() {
+        assert!(too_small(Rect::new(0, 0, 80, 10)));
+        assert!(too_small(Rect::new(0, 0, 40, 30)));
+        assert!(!too_small(Rect::new(0, 0, MIN_WIDTH, MIN_HEIGHT)));
+
+        // Rendering the notice into a tiny area must not panic
+        let backend = ratatui::backend::TestBackend::new(10, 2);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| draw_too_small(f, f.size(), &Theme::default()))
+            .unwrap();
+    }
+
+    #[test]
+    fn popup_sizes_dont_overflow_on_huge_terminals() {
+        assert_eq!(modal::percent_of(1000, 80), 800);
+        assert_eq!(modal::percent_of(800, 90), 720);
+        assert_eq!(modal::percent_of(u16::MAX, 90), 58981);
+    }
+
+    #[test]
+    fn amount_text_pads_to_common_width() {
+        let money = Money::new("$");
+        let small = Transaction { amount: 5.0, ..Default::default() };
+        let large = Transaction { amount: 12345.5, ..Default::default() };
+
+        let width = amount_text(&large, &money, &Glyphs::UNICODE).chars().count();
+        assert_eq!(amount_text(&large, &money, &Glyphs::UNICODE), "▼ $12345.50");
+        assert_eq!(format!("{:>width$}", amount_text(&small, &money, &Glyphs::UNICODE)), "    ▼ $5.00");
+    }
+
+    #[test]
+    fn visible_window_keeps_selection_on_screen() {
+        assert_eq!(visible_window(0, 10), 0..10);
+        assert_eq!(visible_window(9, 10), 0..10);
+        assert_eq!(visible_window(10, 10), 1..11);
+        assert_eq!(visible_window(20_000, 25), 19_976..20_001);
+        assert_eq!(visible_window(3, 0), 3..4);
+    }
+
+    #[test]
+    fn stripe_bg_alternates_only_when_enabled() {
+        let theme = Theme::default();
+        assert_eq!(stripe_bg(0, true, &theme), theme.background);
+        assert_eq!(stripe_bg(1, true, &theme), theme.row_alt);
+        assert_eq!(stripe_bg(1, false, &theme), theme.background);
+    }
+
     #[test]
     fn table_state_selection() {
         let state = create_table_state(3);
@@ -719,13 +1300,17 @@ mod tests {
             mode: Mode::Normal,
             form: crate::form::TransactionForm::new(),
             editing: None,
+            editing_recurring: None,
             tags: vec![],
             transactions: vec![],
+            note_tags: HashMap::new(),
             recurring_entries: vec![],
             selected: 0,
             selected_recurring: 0,
             currency: "$".into(),
             popup: None,
+            popup_scroll: 0,
+            popup_choice: crate::app::ConfirmChoice::Yes,
             theme: Theme::default(),
             filter: crate::app::TransactionFilter {
                 active: false,
@@ -733,7 +1318,69 @@ mod tests {
                 end_date: "".into(),
                 tag_index: None,
                 active_field: crate::app::FilterField::StartDate,
+                search: String::new(),
             },
+            dirty: false,
+            data_version: 0,
+            list_view: Default::default(),
+            poll_interval_ms: 200,
+            auto_confirm_recurring: false,
+            confirm_edit: false,
+            jump_date: String::new(),
+            search: Default::default(),
+            exchange_rates: Default::default(),
+            selected_ids: Default::default(),
+            history: Default::default(),
+            highlight_threshold: None,
+            tag_budgets: HashMap::new(),
+            budget_warning_percent: 80.0,
+            low_balance_warning: None,
+            stats_months: 6,
+            week_start: chrono::Weekday::Mon,
+            ties_newest_first: true,
+            savings_thresholds: (0.0, 20.0),
+            goals: Vec::new(),
+            filter_presets: Vec::new(),
+            profile: None,
+            source_history: Vec::new(),
+            stats_scroll: 0,
+            stats_lines: 0,
+            balance_snapshots: Vec::new(),
+            opening_balance: 0.0,
+            retag_index: 0,
+            scale_input: String::new(),
+            sort_key: SortKey::Date,
+            sort_desc: true,
+            recurring_sort: crate::app::RecurringSort::Newest,
+            group_by_date: true,
+            trend_tag_index: 0,
+            tag_trend: Vec::new(),
+            tag_total: (0.0, 0.0),
+            summary_year: 2026,
+            year_net: [None; 12],
+            privacy: false,
+            decimal_places: 2,
+            rounding: crate::money::Rounding::HalfUp,
+            locale: crate::locale::Locale::En,
+            fallback_tag: Tag::from_str("other"),
+            show_onboarding: false,
+            columns: crate::config::list_columns(&[]),
+            header_panels: crate::config::header_panels(&[]),
+            alternate_screen: true,
+            read_only: false,
+            relative_dates: false,
+            stripe_rows: true,
+            list_title: "Transactions".into(),
+            highlight_symbol: "▶ ".into(),
+            empty_message: None,
+            amount_step: 1.0,
+            amount_step_large: 10.0,
+            typed_confirm_threshold: 0,
+            typed_confirm_word: "DELETE".into(),
+            clipboard: None,
+            toast: None,
+            popup_file: None,
+            state_file: None,
         };
 
         let tx = Transaction {
@@ -743,9 +1390,10 @@ mod tests {
             kind: TransactionType::Credit,
             tag: Tag("tag".into()),
             date: "2026-02-25".into(),
+            ..Default::default()
         };
 
-        let row = transaction_row(&tx, 12.34, &app, &theme, &app.currency, theme.background);
+        let row = transaction_row(&tx, 12.34, 0, &app, &theme, &app.money(), theme.background, &HashMap::new());
         let debug = format!("{:?}", row);
         assert!(debug.contains("Test"));
         assert!(debug.contains("12.34"));
@@ -763,13 +1411,17 @@ mod tests {
             mode: Mode::Normal,
             form: crate::form::TransactionForm::new(),
             editing: None,
+            editing_recurring: None,
             tags: vec![],
             transactions: vec![],
+            note_tags: HashMap::new(),
             recurring_entries: vec![],
             selected: 0,
             selected_recurring: 0,
             currency: "$".into(),
             popup: None,
+            popup_scroll: 0,
+            popup_choice: crate::app::ConfirmChoice::Yes,
             theme: Theme::default(),
             filter: crate::app::TransactionFilter {
                 active: false,
@@ -777,7 +1429,69 @@ mod tests {
                 end_date: "".into(),
                 tag_index: None,
                 active_field: crate::app::FilterField::StartDate,
+                search: String::new(),
             },
+            dirty: false,
+            data_version: 0,
+            list_view: Default::default(),
+            poll_interval_ms: 200,
+            auto_confirm_recurring: false,
+            confirm_edit: false,
+            jump_date: String::new(),
+            search: Default::default(),
+            exchange_rates: Default::default(),
+            selected_ids: Default::default(),
+            history: Default::default(),
+            highlight_threshold: None,
+            tag_budgets: HashMap::new(),
+            budget_warning_percent: 80.0,
+            low_balance_warning: None,
+            stats_months: 6,
+            week_start: chrono::Weekday::Mon,
+            ties_newest_first: true,
+            savings_thresholds: (0.0, 20.0),
+            goals: Vec::new(),
+            filter_presets: Vec::new(),
+            profile: None,
+            source_history: Vec::new(),
+            stats_scroll: 0,
+            stats_lines: 0,
+            balance_snapshots: Vec::new(),
+            opening_balance: 0.0,
+            retag_index: 0,
+            scale_input: String::new(),
+            sort_key: SortKey::Date,
+            sort_desc: true,
+            recurring_sort: crate::app::RecurringSort::Newest,
+            group_by_date: true,
+            trend_tag_index: 0,
+            tag_trend: Vec::new(),
+            tag_total: (0.0, 0.0),
+            summary_year: 2026,
+            year_net: [None; 12],
+            privacy: false,
+            decimal_places: 2,
+            rounding: crate::money::Rounding::HalfUp,
+            locale: crate::locale::Locale::En,
+            fallback_tag: Tag::from_str("other"),
+            show_onboarding: false,
+            columns: crate::config::list_columns(&[]),
+            header_panels: crate::config::header_panels(&[]),
+            alternate_screen: true,
+            read_only: false,
+            relative_dates: false,
+            stripe_rows: true,
+            list_title: "Transactions".into(),
+            highlight_symbol: "▶ ".into(),
+            empty_message: None,
+            amount_step: 1.0,
+            amount_step_large: 10.0,
+            typed_confirm_threshold: 0,
+            typed_confirm_word: "DELETE".into(),
+            clipboard: None,
+            toast: None,
+            popup_file: None,
+            state_file: None,
         };
         assert_eq!(app.current_tab(), 0);
         app.mode = Mode::Stats;
@@ -799,13 +1513,15 @@ mod tests {
             original_date: "2026-02-01".into(),
             last_inserted_date: "".into(),
             active: true,
+            prorate_first: false,
         };
 
-        let row = recurring_row(&entry, &theme);
+        let row = recurring_row(&entry, &theme, &Money::new("$"));
         let debug = format!("{:?}", row);
         assert!(debug.contains("Foo"));
         assert!(debug.contains("99"));
         assert!(debug.contains('│'));
         assert!(debug.contains("Active"));
+        assert!(debug.contains("never"));
     }
 }
\ No newline at end of file
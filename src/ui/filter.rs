@@ -7,6 +7,7 @@ use crate::{
     app::{App, FilterField},
     theme::Theme,
 };
+use super::centered_rect;
 
 pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
     let area = centered_rect(60, 55, f.size());
@@ -31,7 +32,7 @@ pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
     };
 
     let start_indicator = if start_active {
-        Span::styled("▶ ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
     } else {
         Span::raw("  ")
     };
@@ -42,7 +43,7 @@ pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
     } else {
         start_value_spans.push(Span::styled(filter.start_date.clone(), start_val_style));
         if start_active {
-            start_value_spans.push(Span::styled("│", theme.cursor_style()));
+            start_value_spans.push(Span::styled(theme.glyphs.divider, theme.cursor_style()));
         }
         if filter.start_date.len() < 10 {
             let mask = "YYYY-MM-DD";
@@ -55,7 +56,7 @@ pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
         let mut spans = vec![
             start_indicator,
             Span::styled("Start Date", start_label_style),
-            Span::styled(" │ ", Style::default().fg(theme.subtle)),
+            theme.separator_span(),
         ];
         spans.extend(start_value_spans);
         spans
@@ -76,7 +77,7 @@ pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
     };
 
     let end_indicator = if end_active {
-        Span::styled("▶ ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
     } else {
         Span::raw("  ")
     };
@@ -87,7 +88,7 @@ pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
     } else {
         end_value_spans.push(Span::styled(filter.end_date.clone(), end_val_style));
         if end_active {
-            end_value_spans.push(Span::styled("│", theme.cursor_style()));
+            end_value_spans.push(Span::styled(theme.glyphs.divider, theme.cursor_style()));
         }
         if filter.end_date.len() < 10 {
             let mask = "YYYY-MM-DD";
@@ -100,7 +101,7 @@ pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
         let mut spans = vec![
             end_indicator,
             Span::styled("End Date  ", end_label_style),
-            Span::styled(" │ ", Style::default().fg(theme.subtle)),
+            theme.separator_span(),
         ];
         spans.extend(end_value_spans);
         spans
@@ -126,7 +127,7 @@ pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
     };
 
     let tag_indicator = if tag_active {
-        Span::styled("▶ ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
     } else {
         Span::raw("  ")
     };
@@ -134,11 +135,11 @@ pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
     let tag_line = Line::from(vec![
         tag_indicator,
         Span::styled("Tag       ", tag_label_style),
-        Span::styled(" │ ", Style::default().fg(theme.subtle)),
+        theme.separator_span(),
         Span::styled(tag_display, tag_val_style),
         Span::raw("  "),
         Span::styled(
-            "← →",
+            format!("{} {}", theme.glyphs.left, theme.glyphs.right),
             if tag_active {
                 Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
             } else {
@@ -151,7 +152,7 @@ pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
     let content = vec![
         Line::raw(""),
         Line::styled(" Filter Transactions", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
-        Line::styled(" ───────────────────", Style::default().fg(theme.subtle)),
+        Line::styled(format!(" {}", theme.glyphs.rule.repeat(19)), Style::default().fg(theme.subtle)),
         Line::raw(""),
         start_line,
         Line::raw(""),
@@ -160,7 +161,7 @@ pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
         tag_line,
         Line::raw(""),
         Line::raw(""),
-        Line::styled(" ───────────────────", Style::default().fg(theme.subtle)),
+        Line::styled(format!(" {}", theme.glyphs.rule.repeat(19)), Style::default().fg(theme.subtle)),
         Line::from(vec![
             Span::raw("  "),
             Span::styled("[", theme.muted_text()),
@@ -185,23 +186,3 @@ pub fn draw_filter_popup(f: &mut Frame, app: &App, theme: &Theme) {
     f.render_widget(Clear, area);
     f.render_widget(popup, area);
 }
-
-fn centered_rect(percent_x: u16, percent_y: u16, rect: Rect) -> Rect {
-    let vertical_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(rect);
-
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(vertical_layout[1])[1]
-}
@@ -9,12 +9,15 @@ use crate::{
     models::{RecurringInterval, TransactionType, Tag},
     theme::Theme,
 };
+use super::centered_rect;
 
 pub fn draw_transaction_form(f: &mut Frame, app: &App, theme: &Theme) {
     let area = centered_rect(65, 65, f.size());
     let form_content = build_form_content(app, theme);
 
-    let title = if app.editing.is_some() {
+    let title = if app.editing_recurring.is_some() {
+        " Edit Recurring Entry "
+    } else if app.editing.is_some() {
         " Edit Transaction "
     } else {
         " Add New Transaction "
@@ -30,24 +33,45 @@ pub fn draw_transaction_form(f: &mut Frame, app: &App, theme: &Theme) {
 
 fn build_form_content(app: &App, theme: &Theme) -> Vec<Line<'static>> {
     let form = &app.form;
+
+    let mut source_line = create_form_field(
+        "Source",
+        &form.source,
+        form.active,
+        Field::Source,
+        "e.g., Salary, Groceries, Rent",
+        theme,
+    );
+    // Ghost text for the completion, accepted with Tab or →
+    if form.active == Field::Source
+        && let Some(rest) = form.source_completion(&app.source_history)
+    {
+        source_line.spans.push(Span::styled(
+            rest.to_string(),
+            Style::default().fg(theme.subtle).add_modifier(Modifier::ITALIC),
+        ));
+    }
+
     vec![
         Line::raw(""),
         
-        create_form_field(
-            "Source",
-            &form.source,
-            form.active,
-            Field::Source,
-            "e.g., Salary, Groceries, Rent",
-            theme,
-        ),
+        source_line,
         Line::raw(""),
         create_form_field(
             "Amount",
             &form.amount,
             form.active,
             Field::Amount,
-            "e.g., 1000.50",
+            &format!("e.g., 1000.50 (a leading - records an expense, {} to step)", theme.glyphs.up_down),
+            theme,
+        ),
+        create_balance_preview_line(app, theme),
+        create_form_field(
+            "Currency",
+            &form.currency,
+            form.active,
+            Field::Currency,
+            &format!("{} (base)", app.currency),
             theme,
         ),
         Line::raw(""),
@@ -56,7 +80,7 @@ fn build_form_content(app: &App, theme: &Theme) -> Vec<Line<'static>> {
             &form.date,
             form.active,
             Field::Date,
-            "YYYY-MM-DD",
+            "YYYY-MM-DD, today, yesterday or -N days",
             theme,
         ),
         Line::raw(""),
@@ -64,20 +88,42 @@ fn build_form_content(app: &App, theme: &Theme) -> Vec<Line<'static>> {
 
         create_type_selector(&form.kind, form.active == Field::Kind, theme),
         Line::raw(""),
-        create_tag_selector(&app.tags, form.tag_index, form.active == Field::Tag, theme),
+        create_tag_selector(&app.tag_at(form.tag_index), form.active == Field::Tag, theme),
+        Line::raw(""),
+        create_form_field(
+            "Receipt",
+            &form.receipt_path,
+            form.active,
+            Field::Receipt,
+            "optional path to a receipt image",
+            theme,
+        ),
+        create_form_field(
+            "Note",
+            &form.note,
+            form.active,
+            Field::Note,
+            "optional; #hashtags can be searched",
+            theme,
+        ),
         Line::raw(""),
         create_recurring_selector(form.recurring, form.active == Field::Recurring, theme),
         Line::raw(""),
         create_recurring_interval_selector(&form.recurring_interval, form.active == Field::RecurringInterval, form.recurring, theme),
-        Line::raw(""),
+        create_prorate_selector(form, theme),
+        create_error_line(form.error.as_deref(), theme),
         Line::from(vec![
             Span::raw("  "),
             Span::styled("[", theme.muted_text()),
             Span::styled("Tab", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
             Span::styled("] Next  ", theme.muted_text()),
+
+            Span::styled("[", theme.muted_text()),
+            Span::styled(theme.glyphs.shift_tab, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled("] Prev  ", theme.muted_text()),
             
             Span::styled("[", theme.muted_text()),
-            Span::styled("←→", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(theme.glyphs.left_right, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
             Span::styled("] Toggle  ", theme.muted_text()),
             
             Span::styled("[", theme.muted_text()),
@@ -92,6 +138,30 @@ fn build_form_content(app: &App, theme: &Theme) -> Vec<Line<'static>> {
     ]
 }
 
+/// "Balance after saving" under the Amount field, updated as you type.
+fn create_balance_preview_line(app: &App, theme: &Theme) -> Line<'static> {
+    let Some(balance) = app.balance_preview() else {
+        return Line::raw("");
+    };
+
+    let color = if balance < 0.0 { theme.debit } else { theme.credit };
+    Line::from(vec![
+        Span::raw("  "),
+        Span::styled("Balance after saving: ", theme.muted_text()),
+        Span::styled(app.money().format(balance), Style::default().fg(color)),
+    ])
+}
+
+fn create_error_line(error: Option<&str>, theme: &Theme) -> Line<'static> {
+    match error {
+        Some(msg) => Line::from(vec![
+            Span::raw("  "),
+            Span::styled(format!("{} {}", theme.glyphs.warning, msg), theme.danger().add_modifier(Modifier::BOLD)),
+        ]),
+        None => Line::raw(""),
+    }
+}
+
 fn create_form_field(
     label: &str,
     value: &str,
@@ -129,13 +199,13 @@ fn create_form_field(
     };
     
     let cursor = if is_active { 
-        Span::styled("│", theme.cursor_style())
+        Span::styled(theme.glyphs.divider, theme.cursor_style())
     } else { 
         Span::raw("")
     };
     
     let indicator = if is_active {
-        Span::styled("▶ ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
     } else {
         Span::raw("  ")
     };
@@ -143,7 +213,7 @@ fn create_form_field(
     Line::from(vec![
         indicator,
         Span::styled(format!("{:<9}", label), label_style),
-        Span::styled("│ ", Style::default().fg(theme.subtle)),
+        Span::styled(format!("{} ", theme.glyphs.divider), Style::default().fg(theme.subtle)),
         Span::styled(display_value, value_style),
         cursor,
     ])
@@ -155,8 +225,8 @@ fn create_type_selector(
     theme: &Theme,
 ) -> Line<'static> {
     let (kind_icon, kind_label, kind_style) = match kind {
-        TransactionType::Credit => ("↑", "Credit (Income)", theme.success()),
-        TransactionType::Debit => ("↓", "Debit (Expense)", theme.danger()),
+        TransactionType::Credit => (theme.glyphs.up, "Credit (Income)", theme.success()),
+        TransactionType::Debit => (theme.glyphs.down, "Debit (Expense)", theme.danger()),
     };
     
     let label_style = if is_active {
@@ -168,7 +238,7 @@ fn create_type_selector(
     };
     
     let indicator = if is_active {
-        Span::styled("▶ ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
     } else {
         Span::raw("  ")
     };
@@ -176,25 +246,23 @@ fn create_type_selector(
     Line::from(vec![
         indicator,
         Span::styled("Type     ", label_style),
-        Span::styled("│ ", Style::default().fg(theme.subtle)),
+        Span::styled(format!("{} ", theme.glyphs.divider), Style::default().fg(theme.subtle)),
         Span::styled(kind_icon, kind_style.add_modifier(Modifier::BOLD)),
         Span::raw(" "),
         Span::styled(kind_label, kind_style),
         Span::raw("  "),
         Span::styled(
-            "← →",
+            format!("{} {}", theme.glyphs.left, theme.glyphs.right),
             Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
         ),
     ])
 }
 
 fn create_tag_selector(
-    tags: &[Tag],
-    index: usize,
+    tag: &Tag,
     is_active: bool,
     theme: &Theme,
 ) -> Line<'static> {
-    let tag = tags.get(index).map(|t| t.as_str()).unwrap_or("other");
     
     let label_style = if is_active {
         Style::default()
@@ -205,7 +273,7 @@ fn create_tag_selector(
     };
     
     let indicator = if is_active {
-        Span::styled("▶ ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
     } else {
         Span::raw("  ")
     };
@@ -213,16 +281,16 @@ fn create_tag_selector(
     Line::from(vec![
         indicator,
         Span::styled("Tag      ", label_style),
-        Span::styled("│ ", Style::default().fg(theme.subtle)),
+        Span::styled(format!("{} ", theme.glyphs.divider), Style::default().fg(theme.subtle)),
         Span::styled(
-            format!("#{}", tag),
+            format!("#{}", tag.as_str()),
             Style::default()
                 .fg(theme.accent_soft)
                 .add_modifier(Modifier::ITALIC | Modifier::BOLD),
         ),
         Span::raw("  "),
         Span::styled(
-            "← →",
+            format!("{} {}", theme.glyphs.left, theme.glyphs.right),
             Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
         ),
     ])
@@ -230,9 +298,9 @@ fn create_tag_selector(
 
 fn create_recurring_selector(recurring: bool, is_active: bool, theme: &Theme) -> Line<'static> {
     let (status_icon, status_text, status_style) = if recurring {
-        ("🔄", "Yes", theme.success())
+        (theme.glyphs.recurring_on, "Yes", theme.success())
     } else {
-        ("🚫", "No", Style::default().fg(theme.muted))
+        (theme.glyphs.recurring_off, "No", Style::default().fg(theme.muted))
     };
     
     let label_style = if is_active {
@@ -244,7 +312,7 @@ fn create_recurring_selector(recurring: bool, is_active: bool, theme: &Theme) ->
     };
     
     let indicator = if is_active {
-        Span::styled("▶ ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
     } else {
         Span::raw("  ")
     };
@@ -252,13 +320,13 @@ fn create_recurring_selector(recurring: bool, is_active: bool, theme: &Theme) ->
     Line::from(vec![
         indicator,
         Span::styled("Recurring", label_style),
-        Span::styled("│ ", Style::default().fg(theme.subtle)),
+        Span::styled(format!("{} ", theme.glyphs.divider), Style::default().fg(theme.subtle)),
         Span::styled(status_icon, status_style),
         Span::raw(" "),
         Span::styled(status_text, status_style),
         Span::raw("  "),
         Span::styled(
-            "← →",
+            format!("{} {}", theme.glyphs.left, theme.glyphs.right),
             Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
         ),
     ])
@@ -274,7 +342,7 @@ fn create_recurring_interval_selector(interval: &RecurringInterval, is_active: b
     };
     
     let indicator = if is_active {
-        Span::styled("▶ ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
     } else {
         Span::raw("  ")
     };
@@ -290,11 +358,11 @@ fn create_recurring_interval_selector(interval: &RecurringInterval, is_active: b
     Line::from(vec![
         indicator,
         Span::styled("Interval", label_style),
-        Span::styled(" │ ", Style::default().fg(theme.subtle)),
+        theme.separator_span(),
         Span::styled(interval_text, interval_style),
         Span::raw("  "),
         Span::styled(
-            "← →",
+            format!("{} {}", theme.glyphs.left, theme.glyphs.right),
             if is_active {
                 Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
             } else {
@@ -304,22 +372,34 @@ fn create_recurring_interval_selector(interval: &RecurringInterval, is_active: b
     ])
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, rect: Rect) -> Rect {
-    let vertical_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(rect);
+fn create_prorate_selector(form: &crate::form::TransactionForm, theme: &Theme) -> Line<'static> {
+    let is_active = form.active == Field::Prorate;
+    // Only monthly rules are ever prorated
+    let applies = form.recurring && form.recurring_interval == RecurringInterval::Monthly;
+
+    let label_style = if is_active {
+        Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+    } else {
+        theme.muted_text()
+    };
 
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(vertical_layout[1])[1]
+    let indicator = if is_active {
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+    } else {
+        Span::raw("  ")
+    };
+
+    let value_style = if applies && form.prorate_first {
+        theme.success()
+    } else {
+        Style::default().fg(theme.muted)
+    };
+
+    Line::from(vec![
+        indicator,
+        Span::styled("Prorate", label_style),
+        theme.separator_span(),
+        Span::styled(if form.prorate_first { "Yes" } else { "No" }, value_style),
+        Span::styled("  first month only", theme.muted_text()),
+    ])
 }
@@ -3,49 +3,71 @@ use ratatui::{
     widgets::Paragraph,
 };
 
-use crate::theme::Theme;
+use crate::{config::HeaderPanel, money::Money, stats::StatsSnapshot, theme::Theme};
 
+/// One panel per entry of `panels`, sharing the width equally.
+/// `base_label` is shown next to each amount title when totals are
+/// converted from several currencies into the base one. A balance under
+/// `low_balance` is flagged even while it's still positive.
+#[allow(clippy::too_many_arguments)]
 pub fn draw_header(
     f: &mut Frame,
     area: Rect,
-    earned: f64,
-    spent: f64,
-    balance: f64,
+    snapshot: &StatsSnapshot,
+    panels: &[HeaderPanel],
     theme: &Theme,
-    currency: &str,
+    money: &Money,
+    base_label: Option<&str>,
+    low_balance: Option<f64>,
 ) {
+    let count = panels.len().max(1) as u32;
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(33),
-            Constraint::Percentage(34),
-            Constraint::Percentage(33),
-        ])
+        .constraints(vec![Constraint::Ratio(1, count); panels.len()])
         .split(area);
 
-    f.render_widget(
-        build_earned_panel(earned, currency, theme),
-        chunks[0],
-    );
-    f.render_widget(
-        build_balance_panel(balance, currency, theme),
-        chunks[1],
-    );
-    f.render_widget(
-        build_spent_panel(spent, currency, theme),
-        chunks[2],
-    );
+    let suffix = base_label
+        .map(|label| format!(" ({})", label))
+        .unwrap_or_default();
+
+    for (panel, chunk) in panels.iter().zip(chunks.iter()) {
+        let widget = match panel {
+            HeaderPanel::Earned => build_earned_panel(snapshot.earned, money, &suffix, theme),
+            HeaderPanel::Balance => {
+                let low = low_balance.is_some_and(|threshold| snapshot.balance < threshold);
+                build_balance_panel(snapshot.balance, low, money, &suffix, theme)
+            }
+            HeaderPanel::Spent => build_spent_panel(snapshot.spent, money, &suffix, theme),
+            HeaderPanel::SavingsRate => build_savings_rate_panel(snapshot, theme),
+        };
+        f.render_widget(widget, *chunk);
+    }
+}
+
+/// Which dataset this is, set into the right end of the header's top border.
+pub fn draw_profile_indicator(f: &mut Frame, area: Rect, profile: &str, theme: &Theme) {
+    let top = Rect { height: area.height.min(1), width: area.width.saturating_sub(1), ..area };
+    let indicator = Paragraph::new(Line::from(vec![
+        Span::styled(" profile ", theme.muted_text()),
+        Span::styled(
+            format!("{} ", profile),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        ),
+    ]))
+    .alignment(Alignment::Right);
+    f.render_widget(indicator, top);
 }
 
-fn build_earned_panel(earned: f64, currency: &str, theme: &Theme) -> Paragraph<'static> {
+fn build_earned_panel(earned: f64, money: &Money, suffix: &str, theme: &Theme) -> Paragraph<'static> {
     let content = vec![
         Line::from(vec![
-            Span::styled("↑ ", Style::default().fg(theme.credit).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("{} ", theme.glyphs.up), Style::default().fg(theme.credit).add_modifier(Modifier::BOLD)),
             Span::styled("EARNED", theme.title()),
+            Span::styled(suffix.to_string(), theme.muted_text()),
         ]),
         Line::raw(""),
         Line::styled(
-            format!("{}{:.2}", currency, earned),
+            money.format(earned),
             Style::default()
                 .fg(theme.credit)
                 .add_modifier(Modifier::BOLD),
@@ -57,19 +79,20 @@ fn build_earned_panel(earned: f64, currency: &str, theme: &Theme) -> Paragraph<'
         .alignment(Alignment::Center)
 }
 
-fn build_balance_panel(balance: f64, currency: &str, theme: &Theme) -> Paragraph<'static> {
-    let balance_color = calculate_balance_color(balance, theme);
-    let balance_symbol = if balance >= 0.0 { "✓" } else { "⚠" };
+fn build_balance_panel(balance: f64, low: bool, money: &Money, suffix: &str, theme: &Theme) -> Paragraph<'static> {
+    let balance_color = calculate_balance_color(balance, low, theme);
+    let balance_symbol = if balance >= 0.0 && !low { theme.glyphs.cleared } else { theme.glyphs.warning };
     
     let content = vec![
         Line::from(vec![
             Span::styled(balance_symbol, Style::default().fg(balance_color).add_modifier(Modifier::BOLD)),
             Span::raw(" "),
             Span::styled("BALANCE", theme.title()),
+            Span::styled(suffix.to_string(), theme.muted_text()),
         ]),
         Line::raw(""),
         Line::styled(
-            format!("{}{:.2}", currency, balance),
+            money.format(balance),
             Style::default()
                 .fg(balance_color)
                 .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
@@ -81,15 +104,16 @@ fn build_balance_panel(balance: f64, currency: &str, theme: &Theme) -> Paragraph
         .alignment(Alignment::Center)
 }
 
-fn build_spent_panel(spent: f64, currency: &str, theme: &Theme) -> Paragraph<'static> {
+fn build_spent_panel(spent: f64, money: &Money, suffix: &str, theme: &Theme) -> Paragraph<'static> {
     let content = vec![
         Line::from(vec![
-            Span::styled("↓ ", Style::default().fg(theme.debit).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("{} ", theme.glyphs.down), Style::default().fg(theme.debit).add_modifier(Modifier::BOLD)),
             Span::styled("SPENT", theme.title()),
+            Span::styled(suffix.to_string(), theme.muted_text()),
         ]),
         Line::raw(""),
         Line::styled(
-            format!("{}{:.2}", currency, spent),
+            money.format(spent),
             Style::default()
                 .fg(theme.debit)
                 .add_modifier(Modifier::BOLD),
@@ -101,10 +125,33 @@ fn build_spent_panel(spent: f64, currency: &str, theme: &Theme) -> Paragraph<'st
         .alignment(Alignment::Center)
 }
 
-fn calculate_balance_color(balance: f64, theme: &Theme) -> Color {
-    if balance >= 0.0 {
-        theme.credit
-    } else {
+/// Earned minus spent as a share of earned, colored by the Stats thresholds.
+fn build_savings_rate_panel(snapshot: &StatsSnapshot, theme: &Theme) -> Paragraph<'static> {
+    let color = snapshot.savings_rate_color(theme);
+    let content = vec![
+        Line::from(vec![
+            Span::styled("% ", Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            Span::styled("SAVINGS RATE", theme.title()),
+        ]),
+        Line::raw(""),
+        Line::styled(
+            snapshot.savings_rate_text(),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+    ];
+
+    Paragraph::new(content)
+        .block(theme.panel())
+        .alignment(Alignment::Center)
+}
+
+fn calculate_balance_color(balance: f64, low: bool, theme: &Theme) -> Color {
+    if balance < 0.0 {
         theme.debit
+    } else if low {
+        // Still positive, but under the configured buffer
+        theme.warning
+    } else {
+        theme.credit
     }
 }
@@ -0,0 +1,59 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Clear, Paragraph, Padding},
+};
+
+use crate::{app::App, theme::Theme};
+use super::centered_rect;
+
+pub fn draw_jump_popup(f: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(40, 30, f.size());
+
+    let mut value_spans = vec![
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        Span::styled("Date", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        theme.separator_span(),
+        Span::styled(
+            app.jump_date.clone(),
+            Style::default().fg(theme.foreground).bg(theme.surface).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(theme.glyphs.divider, theme.cursor_style()),
+    ];
+
+    // Remaining part of the mask, same as the filter popup
+    if app.jump_date.len() < 10 {
+        let mask = "YYYY-MM-DD";
+        value_spans.push(Span::styled(
+            mask[app.jump_date.len()..].to_string(),
+            Style::default().fg(theme.subtle).add_modifier(Modifier::ITALIC),
+        ));
+    }
+
+    let content = vec![
+        Line::raw(""),
+        Line::styled(
+            " Newest transaction on or before:",
+            theme.muted_text(),
+        ),
+        Line::raw(""),
+        Line::from(value_spans),
+        Line::raw(""),
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[", theme.muted_text()),
+            Span::styled("Enter", theme.success()),
+            Span::styled("] Jump  ", theme.muted_text()),
+
+            Span::styled("[", theme.muted_text()),
+            Span::styled("Esc", theme.danger()),
+            Span::styled("] Cancel", theme.muted_text()),
+        ]),
+    ];
+
+    let popup = Paragraph::new(content)
+        .block(theme.popup(" Jump to Date ").padding(Padding::new(2, 2, 0, 0)))
+        .alignment(Alignment::Left);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
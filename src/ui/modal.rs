@@ -1,97 +1,222 @@
 use ratatui::{
     prelude::*,
-    widgets::{Clear, Paragraph},
+    widgets::{Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap},
 };
 
 use crate::{
-    app::{App, PopupKind},
+    app::{App, ConfirmChoice, PopupKind, PopupTone},
     theme::Theme,
 };
 
 pub fn draw_popup(f: &mut Frame, app: &App, theme: &Theme) {
     if let Some(popup) = &app.popup {
-        let area = centered_rect(super::POPUP_WIDTH_PERCENT, super::POPUP_HEIGHT_PERCENT, f.size());
-
-        // Clear behind popup with slight shadow effect
-        f.render_widget(Clear, area);
-
         let (title, lines, is_confirm) = match popup {
             PopupKind::Confirm { title, message, .. } => {
                 (title.as_str(), message.clone(), true)
             }
-            PopupKind::Info { title, message } => {
+            PopupKind::Info { title, message, .. } => {
+                (title.as_str(), message.clone(), false)
+            }
+            PopupKind::TypedConfirm { title, message, .. } => {
                 (title.as_str(), message.clone(), false)
             }
         };
 
-        // Enhanced styled button row with better visual separation
-        let buttons = if is_confirm {
+        // Tall enough for the wrapped message plus the borders, spacer and
+        // button rows, within the usual minimum and a maximum share of the
+        // screen
+        let screen = f.size();
+        let size = popup.size();
+        let width = percent_of(screen.width, size.width_percent());
+        let wrapped = super::wrapped_line_count(&lines, width.saturating_sub(2) as usize);
+        let min_height = percent_of(screen.height, super::POPUP_HEIGHT_PERCENT);
+        let max_height = percent_of(screen.height, size.max_height_percent());
+        let height = u16::try_from(wrapped)
+            .unwrap_or(u16::MAX)
+            .saturating_add(POPUP_CHROME)
+            .clamp(min_height, max_height);
+        let area = centered_area(width, height, screen);
+
+        // Clear behind popup with slight shadow effect
+        f.render_widget(Clear, area);
+
+        let tone = match popup.tone() {
+            PopupTone::Danger => theme.danger,
+            PopupTone::Neutral => theme.accent,
+            PopupTone::Info => theme.info,
+        };
+
+        // Enhanced styled button row with better visual separation. The
+        // highlighted choice is drawn inverted so Enter's target is obvious.
+        let buttons = if let PopupKind::TypedConfirm { word, typed, .. } = popup {
+            // Red until the answer matches the word exactly
+            let typed_color = if typed == word { theme.credit } else { theme.debit };
+            Line::from(vec![
+                Span::styled(format!("Type {}: ", word), theme.muted_text()),
+                Span::styled(
+                    format!("{}_", typed),
+                    Style::default().fg(typed_color).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("    "),
+                theme.bracket_open(),
+                Span::styled("Esc", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+                theme.bracket_close(),
+                Span::styled("Cancel", theme.muted_text()),
+            ])
+        } else if is_confirm {
+            let yes_selected = app.popup_choice == ConfirmChoice::Yes;
+            // Yes on a destructive confirm is the dangerous choice, and No
+            // the safe one, so it isn't drawn in red as well
+            let danger = popup.tone() == PopupTone::Danger;
+            let yes_color = if danger { theme.danger } else { theme.credit };
+            let no_color = if danger { theme.foreground } else { theme.debit };
+            let button_style = |selected: bool, color: Color| {
+                if selected {
+                    Style::default()
+                        .fg(theme.surface)
+                        .bg(color)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(color)
+                }
+            };
+
             Line::from(vec![
                 Span::raw("   "),
                 theme.bracket_open(),
-                Span::styled("y", Style::default().fg(theme.credit).add_modifier(Modifier::BOLD)),
+                Span::styled("y", Style::default().fg(yes_color).add_modifier(Modifier::BOLD)),
                 theme.bracket_close(),
-                Span::styled("Yes", theme.success()),
+                Span::styled(" Yes ", button_style(yes_selected, yes_color)),
                 Span::raw("      "),
                 theme.bracket_open(),
-                Span::styled("n", Style::default().fg(theme.debit).add_modifier(Modifier::BOLD)),
+                Span::styled("n", Style::default().fg(no_color).add_modifier(Modifier::BOLD)),
                 theme.bracket_close(),
-                Span::styled("No", theme.danger()),
+                Span::styled(" No ", button_style(!yes_selected, no_color)),
             ])
         } else {
-            Line::from(vec![
+            let mut spans = vec![
                 theme.bracket_open(),
                 Span::styled("Esc", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
                 theme.bracket_close(),
                 Span::styled("Close", theme.muted_text()),
+            ];
+            if app.popup_file.is_some() {
+                spans.extend([
+                    Span::raw("    "),
+                    theme.bracket_open(),
+                    Span::styled("o", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+                    theme.bracket_close(),
+                    Span::styled("Open file", theme.muted_text()),
+                ]);
+            }
+            Line::from(spans)
+        };
+
+        let block = theme.tinted_popup(title, tone);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let sections = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(5),
             ])
+            .split(inner);
+        let body_area = sections[1];
+
+        let message_lines: Vec<Line> = lines
+            .lines()
+            .map(|l| {
+                Line::styled(
+                    l.to_string(),
+                    Style::default()
+                        .fg(theme.foreground)
+                        .add_modifier(Modifier::BOLD),
+                )
+            })
+            .collect();
+
+        let total = wrapped;
+        let visible = body_area.height as usize;
+        let scrollable = total > visible;
+
+        // Short messages sit in the middle of the body; long ones scroll.
+        let (body, offset) = if scrollable {
+            let max_offset = total - visible;
+            (message_lines, (app.popup_scroll as usize).min(max_offset))
+        } else {
+            let mut padded = vec![Line::raw(""); (visible - total) / 2];
+            padded.extend(message_lines);
+            (padded, 0)
         };
 
-        // Popup content with enhanced spacing and hierarchy
-        let content = vec![
-            Line::raw(""),
-            Line::raw(""),
-            Line::styled(
-                lines,
-                Style::default()
-                    .fg(theme.foreground)
-                    .add_modifier(Modifier::BOLD)
-            ),
-            Line::raw(""),
+        let message = Paragraph::new(body)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .scroll((offset as u16, 0));
+        f.render_widget(message, body_area);
+
+        if scrollable {
+            let mut state = ScrollbarState::new(total - visible + 1).position(offset);
+            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .thumb_symbol(theme.glyphs.scrollbar_thumb)
+                .track_symbol(Some(theme.glyphs.scrollbar_track))
+                .track_style(Style::default().fg(theme.subtle))
+                .thumb_style(Style::default().fg(theme.accent));
+            f.render_stateful_widget(scrollbar, body_area, &mut state);
+        }
+
+        let mut footer = vec![
             Line::raw(""),
             Line::styled(
-                "─────────────────────────────────────────────────",
+                theme.glyphs.rule.repeat(49),
                 Style::default().fg(theme.subtle),
             ),
             Line::raw(""),
             buttons,
-            Line::raw(""),
         ];
 
-        let widget = Paragraph::new(content)
-            .block(theme.popup(title))
-            .alignment(Alignment::Center);
+        if scrollable {
+            footer.push(Line::from(vec![
+                Span::styled(theme.glyphs.up_down, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+                Span::styled(" Scroll", theme.muted_text()),
+            ]));
+        } else if is_confirm {
+            footer.push(Line::from(vec![
+                Span::styled(theme.glyphs.left_right, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+                Span::styled(" Choose  ", theme.muted_text()),
+                Span::styled("Enter", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+                Span::styled(" Confirm", theme.muted_text()),
+            ]));
+        }
 
-        f.render_widget(widget, area);
+        let widget = Paragraph::new(footer).alignment(Alignment::Center);
+
+        f.render_widget(widget, sections[2]);
     }
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, rect: Rect) -> Rect {
-    let vertical_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(rect);
-
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(vertical_layout[1])[1]
+// Rows around the message: two borders, the spacer above it and the five
+// button/hint rows below
+const POPUP_CHROME: u16 = 8;
+
+/// `percent` of `length`, worked out in u32 so large terminals don't
+/// overflow.
+pub(super) fn percent_of(length: u16, percent: u16) -> u16 {
+    (u32::from(length) * u32::from(percent) / 100) as u16
+}
+
+fn centered_area(width: u16, height: u16, rect: Rect) -> Rect {
+    let width = width.min(rect.width);
+    let height = height.min(rect.height);
+    Rect {
+        x: rect.x + (rect.width - width) / 2,
+        y: rect.y + (rect.height - height) / 2,
+        width,
+        height,
+    }
 }
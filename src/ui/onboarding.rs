@@ -0,0 +1,51 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Padding, Paragraph, Wrap},
+};
+
+use crate::theme::Theme;
+
+/// Welcome panel shown in place of the list until the first transaction
+/// is recorded.
+pub fn draw_onboarding(f: &mut Frame, area: Rect, theme: &Theme) {
+    let key_style = Style::default().fg(theme.accent).add_modifier(Modifier::BOLD);
+    let text_style = Style::default().fg(theme.foreground);
+
+    let key_line = |key: &str, text: &'static str| {
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled(format!("{:<9}", key), key_style),
+            Span::styled(text, text_style),
+        ])
+    };
+
+    let content = vec![
+        Line::raw(""),
+        Line::styled(
+            "Welcome to FiTui!",
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        ),
+        Line::raw(""),
+        Line::styled(
+            "Your ledger is empty. A few keys to get started:",
+            theme.muted_text(),
+        ),
+        Line::raw(""),
+        key_line("a", "Add your first transaction"),
+        key_line("s", "Add a sample transaction to look around"),
+        key_line(&format!("Tab {}", theme.glyphs.left_right), "Switch between Transactions, Stats and Recurring"),
+        key_line("e / d", "Edit or delete the selected transaction"),
+        key_line("q", "Quit"),
+        Line::raw(""),
+        Line::styled(
+            "Tags, currency and theme live in config.yaml.",
+            Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
+        ),
+    ];
+
+    let panel = Paragraph::new(content)
+        .block(theme.block(" Getting Started ").padding(Padding::new(2, 2, 0, 0)))
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(panel, area);
+}
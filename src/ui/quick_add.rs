@@ -0,0 +1,64 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Clear, Paragraph, Padding},
+};
+
+use crate::{app::App, models::TransactionType, theme::Theme};
+use super::centered_rect;
+
+pub fn draw_quick_add_popup(f: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(40, 30, f.size());
+    let form = &app.form;
+
+    let kind = match form.kind {
+        TransactionType::Credit => "credit",
+        TransactionType::Debit => "debit",
+    };
+    let tag = app.tags.get(form.tag_index).map(|t| t.as_str()).unwrap_or("");
+
+    let value_spans = vec![
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        Span::styled("Amount", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        theme.separator_span(),
+        Span::styled(
+            form.amount.clone(),
+            Style::default().fg(theme.foreground).bg(theme.surface).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(theme.glyphs.divider, theme.cursor_style()),
+    ];
+
+    let mut content = vec![
+        Line::raw(""),
+        Line::styled(
+            format!(" Another '{}' (#{}, {}) dated today:", form.source, tag, kind),
+            theme.muted_text(),
+        ),
+        Line::raw(""),
+        Line::from(value_spans),
+        Line::raw(""),
+    ];
+
+    if let Some(error) = &form.error {
+        content.push(Line::styled(format!(" {}", error), theme.danger()));
+        content.push(Line::raw(""));
+    }
+
+    content.push(Line::from(vec![
+        Span::raw("  "),
+        Span::styled("[", theme.muted_text()),
+        Span::styled("Enter", theme.success()),
+        Span::styled("] Save  ", theme.muted_text()),
+
+        Span::styled("[", theme.muted_text()),
+        Span::styled("Esc", theme.danger()),
+        Span::styled("] Cancel", theme.muted_text()),
+    ]));
+
+    let popup = Paragraph::new(content)
+        .block(theme.popup(" Quick Add ").padding(Padding::new(2, 2, 0, 0)))
+        .alignment(Alignment::Left)
+        .wrap(ratatui::widgets::Wrap { trim: false });
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
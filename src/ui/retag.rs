@@ -0,0 +1,52 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Clear, Paragraph, Padding},
+};
+
+use crate::{app::App, theme::Theme};
+use super::centered_rect;
+
+pub fn draw_retag_popup(f: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(40, 30, f.size());
+
+    let count = app.batch_targets().len();
+    let noun = if count == 1 { "transaction" } else { "transactions" };
+    let tag = app.tag_at(app.retag_index);
+
+    let content = vec![
+        Line::raw(""),
+        Line::styled(format!(" Move {} {} to:", count, noun), theme.muted_text()),
+        Line::raw(""),
+        Line::from(vec![
+            Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled("Tag", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            theme.separator_span(),
+            Span::styled(
+                format!("#{}", tag.as_str()),
+                Style::default()
+                    .fg(theme.accent_soft)
+                    .add_modifier(Modifier::ITALIC | Modifier::BOLD),
+            ),
+            Span::raw("  "),
+            Span::styled(format!("{} {}", theme.glyphs.left, theme.glyphs.right), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        ]),
+        Line::raw(""),
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[", theme.muted_text()),
+            Span::styled("Enter", theme.success()),
+            Span::styled("] Retag  ", theme.muted_text()),
+
+            Span::styled("[", theme.muted_text()),
+            Span::styled("Esc", theme.danger()),
+            Span::styled("] Cancel", theme.muted_text()),
+        ]),
+    ];
+
+    let popup = Paragraph::new(content)
+        .block(theme.popup(" Change Tag ").padding(Padding::new(2, 2, 0, 0)))
+        .alignment(Alignment::Left);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
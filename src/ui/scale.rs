@@ -0,0 +1,54 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Clear, Paragraph, Padding},
+};
+
+use crate::{app::App, theme::Theme};
+use super::centered_rect;
+
+pub fn draw_scale_popup(f: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(40, 30, f.size());
+
+    let value_spans = vec![
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        Span::styled("Percent", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        theme.separator_span(),
+        Span::styled(
+            app.scale_input.clone(),
+            Style::default().fg(theme.foreground).bg(theme.surface).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(theme.glyphs.divider, theme.cursor_style()),
+        Span::styled(
+            if app.scale_input.is_empty() { "e.g. 5 or -10" } else { "" },
+            Style::default().fg(theme.subtle).add_modifier(Modifier::ITALIC),
+        ),
+    ];
+
+    let content = vec![
+        Line::raw(""),
+        Line::styled(
+            format!(" Change the amounts of {} transactions by:", app.scale_targets().len()),
+            theme.muted_text(),
+        ),
+        Line::raw(""),
+        Line::from(value_spans),
+        Line::raw(""),
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[", theme.muted_text()),
+            Span::styled("Enter", theme.success()),
+            Span::styled("] Review  ", theme.muted_text()),
+
+            Span::styled("[", theme.muted_text()),
+            Span::styled("Esc", theme.danger()),
+            Span::styled("] Cancel", theme.muted_text()),
+        ]),
+    ];
+
+    let popup = Paragraph::new(content)
+        .block(theme.popup(" Scale Amounts ").padding(Padding::new(2, 2, 0, 0)))
+        .alignment(Alignment::Left);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
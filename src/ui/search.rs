@@ -0,0 +1,57 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Clear, Paragraph, Padding},
+};
+
+use crate::{app::App, theme::Theme};
+use super::centered_rect;
+
+pub fn draw_search_popup(f: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(50, 30, f.size());
+
+    let value_spans = vec![
+        Span::styled(format!("{} ", theme.glyphs.pointer), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        Span::styled("Search", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        theme.separator_span(),
+        Span::styled(
+            app.filter.search.clone(),
+            Style::default().fg(theme.foreground).bg(theme.surface).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(theme.glyphs.divider, theme.cursor_style()),
+    ];
+
+    // Where we are in the history while recalling
+    let position = match app.search.recall {
+        Some(idx) => format!(" History {}/{}", idx + 1, app.search.history.len()),
+        None => " Matches as you type, e.g. tag:food amount:>50".to_string(),
+    };
+
+    let content = vec![
+        Line::raw(""),
+        Line::styled(position, theme.muted_text()),
+        Line::raw(""),
+        Line::from(value_spans),
+        Line::raw(""),
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[", theme.muted_text()),
+            Span::styled("Enter", theme.success()),
+            Span::styled("] Search  ", theme.muted_text()),
+
+            Span::styled("[", theme.muted_text()),
+            Span::styled(theme.glyphs.up_down, theme.success()),
+            Span::styled("] History  ", theme.muted_text()),
+
+            Span::styled("[", theme.muted_text()),
+            Span::styled("Esc", theme.danger()),
+            Span::styled("] Cancel", theme.muted_text()),
+        ]),
+    ];
+
+    let popup = Paragraph::new(content)
+        .block(theme.popup(" Search ").padding(Padding::new(2, 2, 0, 0)))
+        .alignment(Alignment::Left);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
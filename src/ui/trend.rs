@@ -0,0 +1,106 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Clear, Paragraph, Padding, Sparkline},
+};
+
+use crate::{app::App, theme::Theme};
+use super::centered_rect;
+
+pub fn draw_tag_trend_popup(f: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(60, 50, f.size());
+
+    let tag = app.tag_at(app.trend_tag_index);
+
+    let title = format!(" #{} per month ", tag.as_str());
+    let block = theme.popup(&title).padding(Padding::new(2, 2, 1, 0));
+    let inner = block.inner(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    // Sparkline bars only take whole numbers; cents don't matter at this size
+    let data: Vec<u64> = app
+        .tag_trend
+        .iter()
+        .map(|(_, amount)| amount.round().max(0.0) as u64)
+        .collect();
+
+    let sparkline = Sparkline::default()
+        .data(&data)
+        .bar_set(theme.glyphs.bar_set())
+        .style(Style::default().fg(theme.debit));
+    f.render_widget(sparkline, rows[0]);
+
+    let first = app.tag_trend.first().map(|(m, _)| app.locale.month_label(m)).unwrap_or_default();
+    let last = app.tag_trend.last().map(|(m, _)| app.locale.month_label(m)).unwrap_or_default();
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(first, theme.muted_text()),
+            Span::raw(format!("  {}  ", theme.glyphs.right)),
+            Span::styled(last, theme.muted_text()),
+        ])),
+        rows[1],
+    );
+
+    let money = app.money();
+    let this_month = app.tag_trend.last().map(|(_, a)| *a).unwrap_or(0.0);
+    let peak = app.tag_trend.iter().map(|(_, a)| *a).fold(0.0, f64::max);
+    let average = if app.tag_trend.is_empty() {
+        0.0
+    } else {
+        app.tag_trend.iter().map(|(_, a)| *a).sum::<f64>() / app.tag_trend.len() as f64
+    };
+
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("This month ", theme.muted_text()),
+            Span::styled(
+                money.format(this_month),
+                Style::default().fg(theme.debit).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("   Avg ", theme.muted_text()),
+            Span::styled(money.format(average), Style::default().fg(theme.foreground)),
+            Span::styled("   Peak ", theme.muted_text()),
+            Span::styled(money.format(peak), Style::default().fg(theme.foreground)),
+        ])),
+        rows[2],
+    );
+
+    let (spent, earned) = app.tag_total;
+    let mut totals = vec![
+        Span::styled("All time   Spent ", theme.muted_text()),
+        Span::styled(money.format(spent), Style::default().fg(theme.debit)),
+    ];
+    // Most tags never see a credit; only mention it when there is one
+    if earned > 0.0 {
+        totals.extend([
+            Span::styled("   Earned ", theme.muted_text()),
+            Span::styled(money.format(earned), Style::default().fg(theme.credit)),
+        ]);
+    }
+    f.render_widget(Paragraph::new(Line::from(totals)), rows[3]);
+
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("[", theme.muted_text()),
+            Span::styled(theme.glyphs.left_right, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled("] Tag  ", theme.muted_text()),
+
+            Span::styled("[", theme.muted_text()),
+            Span::styled("Esc", theme.danger()),
+            Span::styled("] Close", theme.muted_text()),
+        ])),
+        rows[4],
+    );
+}
@@ -0,0 +1,108 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph, Padding},
+};
+
+use crate::{app::App, theme::Theme};
+use super::centered_rect;
+
+pub fn draw_year_summary_popup(f: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(70, 60, f.size());
+
+    let title = format!(" {} at a glance ", app.summary_year);
+    let block = theme.popup(&title).padding(Padding::new(2, 2, 1, 0));
+    let inner = block.inner(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(9),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    // 3 rows of 4 months
+    let grid_rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Ratio(1, 3); 3])
+        .split(rows[0]);
+
+    let money = app.money();
+    for (r, row_area) in grid_rows.iter().enumerate() {
+        let cells = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 4); 4])
+            .split(*row_area);
+
+        for (c, cell_area) in cells.iter().enumerate() {
+            let month = r * 4 + c;
+
+            // Months without transactions stay blank rather than showing 0
+            let (text, color) = match app.year_net[month] {
+                Some(net) if net >= 0.0 => (money.format(net), theme.credit),
+                Some(net) => (money.format(net), theme.debit),
+                None => (String::new(), theme.muted),
+            };
+
+            let cell = Paragraph::new(Line::styled(
+                text,
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.subtle))
+                    .title(Span::styled(app.locale.month_abbr(month as u32 + 1), theme.muted_text())),
+            );
+            f.render_widget(cell, *cell_area);
+        }
+    }
+
+    let values: Vec<(usize, f64)> = app
+        .year_net
+        .iter()
+        .enumerate()
+        .filter_map(|(i, net)| net.map(|n| (i, n)))
+        .collect();
+    let total: f64 = values.iter().map(|(_, n)| n).sum();
+    let best = values.iter().max_by(|a, b| a.1.total_cmp(&b.1));
+    let worst = values.iter().min_by(|a, b| a.1.total_cmp(&b.1));
+
+    let mut totals = vec![
+        Span::styled("Year net ", theme.muted_text()),
+        Span::styled(
+            money.format(total),
+            Style::default()
+                .fg(if total >= 0.0 { theme.credit } else { theme.debit })
+                .add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if let (Some(best), Some(worst)) = (best, worst) {
+        totals.extend([
+            Span::styled("   Best ", theme.muted_text()),
+            Span::styled(app.locale.month_abbr(best.0 as u32 + 1), Style::default().fg(theme.foreground)),
+            Span::styled("   Worst ", theme.muted_text()),
+            Span::styled(app.locale.month_abbr(worst.0 as u32 + 1), Style::default().fg(theme.foreground)),
+        ]);
+    }
+    f.render_widget(Paragraph::new(Line::from(totals)), rows[1]);
+
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("[", theme.muted_text()),
+            Span::styled(theme.glyphs.left_right, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled("] Year  ", theme.muted_text()),
+
+            Span::styled("[", theme.muted_text()),
+            Span::styled("Esc", theme.danger()),
+            Span::styled("] Close", theme.muted_text()),
+        ])),
+        rows[3],
+    );
+}
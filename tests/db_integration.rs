@@ -1,6 +1,6 @@
 use chrono::Datelike;
 
-use FiTui::{db, models::{Tag, RecurringInterval, TransactionType}};
+use FiTui::{db, models::{RecurringEntry, Tag, RecurringInterval, TransactionType}};
 
 #[test]
 fn full_transaction_lifecycle() {
@@ -33,7 +33,7 @@ fn recurring_insertion_simulation() {
     let current_month = format!("{:04}-{:02}", chrono::Local::now().year(), chrono::Local::now().month());
 
     // Add a monthly recurring entry starting today
-    db::add_recurring_entry(&conn, "rent", 500.0, TransactionType::Debit, &Tag::from_str("housing"), &RecurringInterval::Monthly, &today).unwrap();
+    db::add_recurring_entry(&conn, &RecurringEntry::new("rent", 500.0, TransactionType::Debit, Tag::from_str("housing"), RecurringInterval::Monthly, &today)).unwrap();
 
     // Run insert logic
     db::insert_recurring_transactions(&conn).unwrap();
@@ -66,7 +66,7 @@ fn recurring_intervals_behavior() {
     };
 
     // === TEST DAILY ===
-    db::add_recurring_entry(&conn, "daily-item", 10.0, TransactionType::Debit, &Tag::from_str("test"), &RecurringInterval::Daily, &today).unwrap();
+    db::add_recurring_entry(&conn, &RecurringEntry::new("daily-item", 10.0, TransactionType::Debit, Tag::from_str("test"), RecurringInterval::Daily, &today)).unwrap();
     let daily_entries = db::get_recurring_entries(&conn).unwrap();
     let daily_id = daily_entries.iter().find(|e| e.source == "daily-item").unwrap().id;
 
@@ -89,7 +89,7 @@ fn recurring_intervals_behavior() {
     // === TEST WEEKLY ===
     let daily_txs_count = db::get_transactions(&conn).unwrap().len();
 
-    db::add_recurring_entry(&conn, "weekly-item", 20.0, TransactionType::Debit, &Tag::from_str("test"), &RecurringInterval::Weekly, &today).unwrap();
+    db::add_recurring_entry(&conn, &RecurringEntry::new("weekly-item", 20.0, TransactionType::Debit, Tag::from_str("test"), RecurringInterval::Weekly, &today)).unwrap();
     let weekly_entries = db::get_recurring_entries(&conn).unwrap();
     let weekly_id = weekly_entries.iter().find(|e| e.source == "weekly-item").unwrap().id;
 
@@ -112,7 +112,7 @@ fn recurring_intervals_behavior() {
     // === TEST MONTHLY ===
     let weekly_txs_count = db::get_transactions(&conn).unwrap().len();
 
-    db::add_recurring_entry(&conn, "monthly-item", 30.0, TransactionType::Debit, &Tag::from_str("test"), &RecurringInterval::Monthly, &today).unwrap();
+    db::add_recurring_entry(&conn, &RecurringEntry::new("monthly-item", 30.0, TransactionType::Debit, Tag::from_str("test"), RecurringInterval::Monthly, &today)).unwrap();
     let monthly_entries = db::get_recurring_entries(&conn).unwrap();
     let monthly_id = monthly_entries.iter().find(|e| e.source == "monthly-item").unwrap().id;
 
@@ -168,3 +168,78 @@ fn migration_safety_test() {
     let active: i32 = conn.query_row("SELECT active FROM recurring_entries WHERE id = 1", [], |r| r.get(0)).unwrap();
     assert!(active == 0 || active == 1);
 }
+
+#[test]
+fn amounts_migrate_to_integer_units() {
+    let path = std::env::temp_dir().join(format!("fitui-units-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    // A database from before amounts were stored as integer units
+    {
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute(
+            "CREATE TABLE transactions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source TEXT NOT NULL,
+                amount REAL NOT NULL,
+                kind TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                date TEXT NOT NULL
+            )",
+            [],
+        ).unwrap();
+        for _ in 0..10 {
+            conn.execute(
+                "INSERT INTO transactions (source, amount, kind, tag, date) VALUES ('gum', 0.1, 'debit', 'food', '2026-02-01')",
+                [],
+            ).unwrap();
+        }
+    }
+
+    let conn = db::init_db_with_path(&path).unwrap();
+    let txs = db::get_transactions(&conn).unwrap();
+    assert_eq!(txs.len(), 10);
+    assert!(txs.iter().all(|t| t.amount == 0.1));
+    assert_eq!(db::total_spent(&conn).unwrap(), 1.0);
+
+    drop(conn);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn recurring_spawned_transactions_can_be_removed() {
+    let conn = db::init_in_memory().expect("init in-memory");
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+    db::add_recurring_entry(&conn, &RecurringEntry::new("streaming", 12.0, TransactionType::Debit, Tag::from_str("bills"), RecurringInterval::Daily, &today)).unwrap();
+    db::add_transaction(&conn, "groceries", 40.0, TransactionType::Debit, &Tag::from_str("food"), &today).unwrap();
+
+    db::insert_recurring_transactions(&conn).unwrap();
+
+    let rec_id = db::get_recurring_entries(&conn).unwrap()[0].id;
+    let txs = db::get_transactions(&conn).unwrap();
+    let spawned = txs.iter().find(|t| t.source == "streaming").unwrap();
+    assert_eq!(spawned.created_by_recurring_id, Some(rec_id));
+    assert_eq!(db::count_transactions_by_recurring(&conn, rec_id).unwrap(), 1);
+
+    // Only the rule's own transactions are deleted
+    let removed = db::delete_transactions_by_recurring(&conn, rec_id).unwrap();
+    assert_eq!(removed, 1);
+    let txs = db::get_transactions(&conn).unwrap();
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0].source, "groceries");
+}
+
+#[test]
+fn file_database_uses_wal_journal() {
+    let path = std::env::temp_dir().join(format!("fitui-wal-{}.db", std::process::id()));
+    let conn = db::init_db_with_path(&path).expect("init file db");
+
+    let mode: String = conn.query_row("PRAGMA journal_mode", [], |r| r.get(0)).unwrap();
+    assert_eq!(mode.to_lowercase(), "wal");
+
+    drop(conn);
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+}